@@ -1,17 +1,31 @@
 use crate::error::ContractError;
-use crate::helpers::get_pool_creation_fee;
-use crate::state::{FREEZESTATE, LAST_STREAM_ID, PARAMS, STREAMS};
+use crate::helpers::{
+    get_pool_creation_fee, validate_code_id, validate_denom, validate_fee_collectors,
+};
+use crate::migrations::{backfill_stream_records, has_legacy_streams, migrate_params};
+use crate::state::{
+    load_roles, streams, CREATOR_EXIT_FEES, DENOM_STATS, FREEZESTATE, IN_DENOM_EXIT_FEES,
+    LAST_STREAM_ID, PARAMS, PENDING_ADMIN, PROTOCOL_STATS, ROLES, STREAM_EXIT_FEES,
+};
 use cosmwasm_std::{
-    entry_point, to_json_binary, Binary, Coin, CosmosMsg, Decimal256, Deps, DepsMut, Env,
-    MessageInfo, Order, Response, StdResult, Uint128, WasmMsg,
+    entry_point, to_json_binary, Addr, Binary, Coin, CosmosMsg, Decimal256, Deps, DepsMut, Env,
+    MessageInfo, Order, Response, StdError, StdResult, Timestamp, Uint128, WasmMsg,
 };
-use cw2::ensure_from_older_version;
-use cw_storage_plus::Bound;
+use cw2::{ensure_from_older_version, set_contract_version, CONTRACT};
+use cw20::Cw20ExecuteMsg;
+use cw_denom::CheckedDenom;
+use cw_storage_plus::{Bound, KeyDeserialize};
 use streamswap_types::controller::{
-    CreateStreamMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, Params, PoolConfig, QueryMsg,
-    StreamResponse, StreamsResponse,
+    CreateStreamMsg, DenomStatsResponse, ExecuteMsg, ExitFeeOverrideTarget, FeeCollectorMsg,
+    InstantiateMsg, MigrateMsg, Params, PoolConfig, ProtocolStatsResponse, QueryMsg, Roles,
+    RolesResponse, StreamCallbackMsg, StreamRecord, StreamResponse, StreamSelection, StreamWindow,
+    StreamsFilter, StreamsResponse,
+};
+use streamswap_types::stream::{
+    ExecuteMsg as StreamExecuteMsg, FinalizedStatus, MigrateMsg as StreamMigrateMsg, Status,
 };
 use streamswap_utils::payment_checker::check_payment;
+use streamswap_utils::to_uint256;
 
 const CONTRACT_NAME: &str = "crates.io:streamswap-controller";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -28,25 +42,34 @@ pub fn instantiate(
         protocol_admin,
         stream_creation_fee,
         exit_fee_percent,
+        referral_fee_percent,
         accepted_in_denoms,
-        fee_collector,
+        fee_collectors,
         vesting_code_id,
         min_waiting_duration,
         min_bootstrapping_duration,
         min_stream_duration,
         tos_version,
+        dust_sweep_grace_period,
     } = msg;
 
     let protocol_admin = deps
         .api
         .addr_validate(&protocol_admin.unwrap_or(info.sender.to_string()))?;
-    let fee_collector = deps
-        .api
-        .addr_validate(&fee_collector.unwrap_or(info.sender.to_string()))?;
+    let fee_collectors = validate_fee_collectors(
+        deps.api,
+        fee_collectors.unwrap_or(vec![FeeCollectorMsg {
+            address: info.sender.to_string(),
+            weight: Decimal256::one(),
+        }]),
+    )?;
 
     if exit_fee_percent > Decimal256::percent(100) || exit_fee_percent < Decimal256::percent(0) {
         return Err(ContractError::InvalidExitFeePercent {});
     }
+    if referral_fee_percent > Decimal256::percent(100) {
+        return Err(ContractError::InvalidReferralFeePercent {});
+    }
     if stream_creation_fee.amount.is_zero() {
         return Err(ContractError::InvalidStreamCreationFee {});
     }
@@ -54,17 +77,20 @@ pub fn instantiate(
     let params = Params {
         stream_creation_fee: stream_creation_fee.clone(),
         exit_fee_percent,
+        referral_fee_percent,
         stream_contract_code_id,
         vesting_code_id,
         accepted_in_denoms: accepted_in_denoms.clone(),
-        fee_collector: fee_collector.clone(),
+        fee_collectors,
         protocol_admin: protocol_admin.clone(),
         min_waiting_duration,
         min_bootstrapping_duration,
         min_stream_duration,
         tos_version,
+        dust_sweep_grace_period,
     };
     PARAMS.save(deps.storage, &params)?;
+    ROLES.save(deps.storage, &Roles::all(protocol_admin.clone()))?;
 
     // Initialize Freeze state
     FREEZESTATE.save(deps.storage, &false)?;
@@ -72,6 +98,8 @@ pub fn instantiate(
     // Initialize Last Stream ID
     LAST_STREAM_ID.save(deps.storage, &0)?;
 
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
     let res = Response::new()
         .add_attributes(params.to_attributes())
         .add_attribute("action", "instantiate controller");
@@ -91,9 +119,14 @@ pub fn execute(
             min_bootstrapping_duration,
             min_stream_duration,
             stream_creation_fee,
-            fee_collector,
+            fee_collectors,
             accepted_in_denoms,
             exit_fee_percent,
+            referral_fee_percent,
+            dust_sweep_grace_period,
+            stream_contract_code_id,
+            vesting_code_id,
+            tos_version,
         } => execute_update_params(
             deps,
             env,
@@ -102,13 +135,44 @@ pub fn execute(
             min_bootstrapping_duration,
             min_stream_duration,
             stream_creation_fee,
-            fee_collector,
+            fee_collectors,
             accepted_in_denoms,
             exit_fee_percent,
+            referral_fee_percent,
+            dust_sweep_grace_period,
+            stream_contract_code_id,
+            vesting_code_id,
+            tos_version,
         ),
         ExecuteMsg::CreateStream { msg } => execute_create_stream(deps, env, info, *msg),
         ExecuteMsg::Freeze {} => execute_freeze(deps, info),
         ExecuteMsg::Unfreeze {} => execute_unfreeze(deps, info),
+        ExecuteMsg::SetExitFeeOverride {
+            target,
+            exit_fee_percent,
+        } => execute_set_exit_fee_override(deps, info, target, exit_fee_percent),
+        ExecuteMsg::StreamCallback { msg } => execute_stream_callback(deps, info, msg),
+        ExecuteMsg::MigrateStreams {
+            new_code_id,
+            selection,
+        } => execute_migrate_streams(deps, info, new_code_id, selection),
+        ExecuteMsg::BackfillStreams { limit } => execute_backfill_streams(deps, limit),
+        ExecuteMsg::ProposeAdmin { new_admin } => execute_propose_admin(deps, info, new_admin),
+        ExecuteMsg::AcceptAdmin {} => execute_accept_admin(deps, info),
+        ExecuteMsg::CancelAdminProposal {} => execute_cancel_admin_proposal(deps, info),
+        ExecuteMsg::UpdateRoles {
+            params_manager,
+            pauser,
+            stream_canceller,
+            fee_manager,
+        } => execute_update_roles(
+            deps,
+            info,
+            params_manager,
+            pauser,
+            stream_canceller,
+            fee_manager,
+        ),
     }
 }
 
@@ -126,6 +190,7 @@ pub fn execute_create_stream(
 
     let CreateStreamMsg {
         treasury,
+        treasury_recipients: _,
         name,
         out_asset,
         start_time,
@@ -133,6 +198,14 @@ pub fn execute_create_stream(
         in_denom,
         stream_admin: _,
         threshold: _,
+        min_average_price: _,
+        emission_curve: _,
+        max_in_per_address: _,
+        max_in_supply: _,
+        hard_cap: _,
+        merkle_root: _,
+        position_nft: _,
+        hooks: _,
         url: _,
         pool_config: create_pool,
         subscriber_vesting: _,
@@ -146,25 +219,41 @@ pub fn execute_create_stream(
     let stream_creation_fee = params.stream_creation_fee.clone();
 
     let accepted_in_denoms = params.accepted_in_denoms.clone();
-    if !accepted_in_denoms.contains(&in_denom) {
+    if !accepted_in_denoms.contains(&in_denom.to_string()) {
         return Err(ContractError::InDenomIsNotAccepted {});
     }
     if out_asset.amount.is_zero() {
         return Err(ContractError::ZeroOutSupply {});
     }
+    validate_denom(deps.as_ref(), &in_denom)?;
+    validate_denom(deps.as_ref(), &out_asset.denom)?;
 
     if tos_version != params.tos_version {
         return Err(ContractError::InvalidToSVersion {});
     }
 
     // These funds shall be sent to controller, after the control these funds will be distributed to the stream contract and fee_collector
-    let mut expected_funds = vec![stream_creation_fee.clone(), out_asset.clone()];
+    let mut expected_funds = vec![stream_creation_fee.clone()];
     // These funds shall be sent to the stream contract
-    let mut instantiate_funds: Vec<Coin> = vec![out_asset.clone()];
+    let mut instantiate_funds: Vec<Coin> = vec![];
+    // Cw20 out asset is transferred from the creator to the stream contract instead
+    if let CheckedDenom::Native(out_denom) = &out_asset.denom {
+        let out_coin = Coin {
+            denom: out_denom.clone(),
+            amount: out_asset.amount,
+        };
+        expected_funds.push(out_coin.clone());
+        instantiate_funds.push(out_coin);
+    }
 
     if let Some(create_pool) = create_pool {
         match create_pool {
             PoolConfig::ConcentratedLiquidity { out_amount_clp } => {
+                // Pools can only be created with native denoms
+                let out_denom = match (&out_asset.denom, &in_denom) {
+                    (CheckedDenom::Native(out_denom), CheckedDenom::Native(_)) => out_denom,
+                    _ => return Err(ContractError::InvalidPoolDenom {}),
+                };
                 let uint128_pool_out_amount = Uint128::try_from(out_amount_clp)?;
                 if uint128_pool_out_amount > out_asset.amount {
                     return Err(ContractError::InvalidPoolOutAmount {});
@@ -177,7 +266,7 @@ pub fn execute_create_stream(
                 let pool_creation_fee_vec = get_pool_creation_fee(&deps)?;
                 // Pool out amount is separate from out asset to be streamed.
                 let pool_out_amount = Coin {
-                    denom: out_asset.denom.clone(),
+                    denom: out_denom.clone(),
                     amount: uint128_pool_out_amount,
                 };
                 // Add the pool out amount to instantiate funds as well
@@ -198,7 +287,8 @@ pub fn execute_create_stream(
 
     let stream_swap_inst_message: CosmosMsg = CosmosMsg::Wasm(WasmMsg::Instantiate2 {
         code_id: params.stream_contract_code_id,
-        admin: Some(params.protocol_admin.to_string()),
+        // Controller is the admin so that streams can be migrated in batches
+        admin: Some(env.contract.address.to_string()),
         label: format!("Stream Swap Stream {} - {}", name, stream_id),
         msg: to_json_binary(&msg)?,
         funds: instantiate_funds,
@@ -218,15 +308,46 @@ pub fn execute_create_stream(
     LAST_STREAM_ID.save(deps.storage, &stream_id)?;
 
     let contract_addr = deps.api.addr_humanize(&canonical_contract_addr)?;
-    STREAMS.save(deps.storage, stream_id, &contract_addr)?;
+    streams().save(
+        deps.storage,
+        stream_id,
+        &StreamRecord {
+            address: contract_addr.clone(),
+            code_id: params.stream_contract_code_id,
+            creator: info.sender.clone(),
+            in_denom: in_denom.to_string(),
+            out_denom: out_asset.denom.to_string(),
+            name: name.clone(),
+            start_time,
+            end_time,
+            status: Status::Waiting,
+        },
+    )?;
 
     let mut msgs = vec![];
 
     msgs.push(stream_swap_inst_message.clone());
-    if !stream_creation_fee.amount.is_zero() {
+    if let CheckedDenom::Cw20(cw20) = &out_asset.denom {
+        msgs.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: cw20.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::TransferFrom {
+                owner: info.sender.to_string(),
+                recipient: contract_addr.to_string(),
+                amount: out_asset.amount,
+            })?,
+            funds: vec![],
+        }));
+    }
+    for (fee_collector, share) in params.split_fee(to_uint256(stream_creation_fee.amount)) {
+        if share.is_zero() {
+            continue;
+        }
         msgs.push(CosmosMsg::Bank(cosmwasm_std::BankMsg::Send {
-            to_address: params.fee_collector.to_string(),
-            amount: vec![stream_creation_fee.clone()],
+            to_address: fee_collector.to_string(),
+            amount: vec![Coin {
+                denom: stream_creation_fee.denom.clone(),
+                amount: Uint128::try_from(share)?,
+            }],
         }));
     }
 
@@ -237,7 +358,7 @@ pub fn execute_create_stream(
         .add_attribute("stream_contract_addr", contract_addr.to_string())
         .add_attribute("stream_creation_fee", stream_creation_fee.to_string())
         .add_attribute("out_asset", out_asset.to_string())
-        .add_attribute("in_denom", in_denom)
+        .add_attribute("in_denom", in_denom.to_string())
         .add_attribute(
             "bootstrapping_start_time",
             msg.bootstraping_start_time.to_string(),
@@ -257,12 +378,36 @@ pub fn execute_update_params(
     min_bootstrapping_duration: Option<u64>,
     min_stream_duration: Option<u64>,
     stream_creation_fee: Option<Coin>,
-    fee_collector: Option<String>,
+    fee_collectors: Option<Vec<FeeCollectorMsg>>,
     accepted_in_denoms: Option<Vec<String>>,
     exit_fee_percent: Option<Decimal256>,
+    referral_fee_percent: Option<Decimal256>,
+    dust_sweep_grace_period: Option<u64>,
+    stream_contract_code_id: Option<u64>,
+    vesting_code_id: Option<u64>,
+    tos_version: Option<String>,
 ) -> Result<Response, ContractError> {
     let mut params = PARAMS.load(deps.storage)?;
-    if info.sender != params.protocol_admin {
+    let roles = load_roles(deps.storage, &params)?;
+    // Fees are updated by the fee manager, the rest of the params by the params manager
+    let updates_fees = stream_creation_fee.is_some()
+        || fee_collectors.is_some()
+        || exit_fee_percent.is_some()
+        || referral_fee_percent.is_some();
+    let updates_params = min_waiting_duration.is_some()
+        || min_bootstrapping_duration.is_some()
+        || min_stream_duration.is_some()
+        || accepted_in_denoms.is_some()
+        || dust_sweep_grace_period.is_some()
+        || stream_contract_code_id.is_some()
+        || vesting_code_id.is_some()
+        || tos_version.is_some();
+    let is_fee_manager = info.sender == roles.fee_manager;
+    let is_params_manager = info.sender == roles.params_manager;
+    if (updates_fees && !is_fee_manager)
+        || (updates_params && !is_params_manager)
+        || !(is_fee_manager || is_params_manager)
+    {
         return Err(ContractError::Unauthorized {});
     }
     if let Some(stream_creation_fee) = stream_creation_fee {
@@ -276,9 +421,15 @@ pub fn execute_update_params(
         }
         params.exit_fee_percent = exit_fee_percent;
     }
+    if let Some(referral_fee_percent) = referral_fee_percent {
+        if referral_fee_percent > Decimal256::percent(100) {
+            return Err(ContractError::InvalidReferralFeePercent {});
+        }
+        params.referral_fee_percent = referral_fee_percent;
+    }
 
-    if let Some(fee_collector) = fee_collector {
-        params.fee_collector = deps.api.addr_validate(&fee_collector)?;
+    if let Some(fee_collectors) = fee_collectors {
+        params.fee_collectors = validate_fee_collectors(deps.api, fee_collectors)?;
     }
     if let Some(accepted_in_denoms) = accepted_in_denoms {
         params.accepted_in_denoms = accepted_in_denoms;
@@ -292,6 +443,20 @@ pub fn execute_update_params(
     if let Some(min_stream_duration) = min_stream_duration {
         params.min_stream_duration = min_stream_duration;
     }
+    if let Some(dust_sweep_grace_period) = dust_sweep_grace_period {
+        params.dust_sweep_grace_period = dust_sweep_grace_period;
+    }
+    if let Some(stream_contract_code_id) = stream_contract_code_id {
+        validate_code_id(deps.as_ref(), stream_contract_code_id)?;
+        params.stream_contract_code_id = stream_contract_code_id;
+    }
+    if let Some(vesting_code_id) = vesting_code_id {
+        validate_code_id(deps.as_ref(), vesting_code_id)?;
+        params.vesting_code_id = vesting_code_id;
+    }
+    if let Some(tos_version) = tos_version {
+        params.tos_version = tos_version;
+    }
 
     PARAMS.save(deps.storage, &params)?;
 
@@ -303,7 +468,7 @@ pub fn execute_update_params(
 
 pub fn execute_freeze(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
     let params = PARAMS.load(deps.storage)?;
-    if info.sender != params.protocol_admin {
+    if info.sender != load_roles(deps.storage, &params)?.pauser {
         return Err(ContractError::Unauthorized {});
     }
     FREEZESTATE.save(deps.storage, &true)?;
@@ -313,7 +478,7 @@ pub fn execute_freeze(deps: DepsMut, info: MessageInfo) -> Result<Response, Cont
 
 pub fn execute_unfreeze(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
     let params = PARAMS.load(deps.storage)?;
-    if info.sender != params.protocol_admin {
+    if info.sender != load_roles(deps.storage, &params)?.pauser {
         return Err(ContractError::Unauthorized {});
     }
     FREEZESTATE.save(deps.storage, &false)?;
@@ -321,44 +486,471 @@ pub fn execute_unfreeze(deps: DepsMut, info: MessageInfo) -> Result<Response, Co
     Ok(Response::new().add_attribute("action", "unfreeze"))
 }
 
+pub fn execute_set_exit_fee_override(
+    deps: DepsMut,
+    info: MessageInfo,
+    target: ExitFeeOverrideTarget,
+    exit_fee_percent: Option<Decimal256>,
+) -> Result<Response, ContractError> {
+    let params = PARAMS.load(deps.storage)?;
+    if info.sender != load_roles(deps.storage, &params)?.fee_manager {
+        return Err(ContractError::Unauthorized {});
+    }
+    if exit_fee_percent.is_some_and(|fee| fee > Decimal256::percent(100)) {
+        return Err(ContractError::InvalidExitFeePercent {});
+    }
+
+    let mut msgs = vec![];
+    let target_attr = match &target {
+        ExitFeeOverrideTarget::Stream { address } => {
+            let address = deps.api.addr_validate(address)?;
+            if streams()
+                .idx
+                .address
+                .item(deps.storage, address.clone())?
+                .is_none()
+            {
+                return Err(ContractError::StreamNotRegistered {});
+            }
+            match exit_fee_percent {
+                Some(fee) => STREAM_EXIT_FEES.save(deps.storage, &address, &fee)?,
+                None => STREAM_EXIT_FEES.remove(deps.storage, &address),
+            }
+            // Stream applies the override until it starts, removing it falls back to the
+            // creator and in denom overrides
+            let exit_fee_override = query_exit_fee_override(deps.as_ref(), address.to_string())?;
+            msgs.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: address.to_string(),
+                msg: to_json_binary(&StreamExecuteMsg::UpdateExitFee { exit_fee_override })?,
+                funds: vec![],
+            }));
+            format!("stream:{}", address)
+        }
+        ExitFeeOverrideTarget::Creator { address } => {
+            let address = deps.api.addr_validate(address)?;
+            match exit_fee_percent {
+                Some(fee) => CREATOR_EXIT_FEES.save(deps.storage, &address, &fee)?,
+                None => CREATOR_EXIT_FEES.remove(deps.storage, &address),
+            }
+            format!("creator:{}", address)
+        }
+        ExitFeeOverrideTarget::InDenom { denom } => {
+            match exit_fee_percent {
+                Some(fee) => IN_DENOM_EXIT_FEES.save(deps.storage, denom, &fee)?,
+                None => IN_DENOM_EXIT_FEES.remove(deps.storage, denom),
+            }
+            format!("in_denom:{}", denom)
+        }
+    };
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "set_exit_fee_override")
+        .add_attribute("target", target_attr)
+        .add_attribute(
+            "exit_fee_percent",
+            exit_fee_percent
+                .map(|fee| fee.to_string())
+                .unwrap_or_else(|| "none".to_string()),
+        ))
+}
+
+pub fn execute_stream_callback(
+    deps: DepsMut,
+    info: MessageInfo,
+    msg: StreamCallbackMsg,
+) -> Result<Response, ContractError> {
+    // Only streams registered by the controller can report their status
+    let (stream_id, mut record) = streams()
+        .idx
+        .address
+        .item(deps.storage, info.sender.clone())?
+        .ok_or(ContractError::Unauthorized {})?;
+    let stream_id = u64::from_vec(stream_id)?;
+    // Settled streams can not report again, so that the outcome is counted once
+    if matches!(record.status, Status::Finalized(_) | Status::Cancelled) {
+        return Err(ContractError::StreamAlreadySettled {});
+    }
+
+    let mut stats = PROTOCOL_STATS.may_load(deps.storage)?.unwrap_or_default();
+    match msg {
+        StreamCallbackMsg::Finalized {
+            status,
+            spent_in,
+            swap_fee,
+        } => {
+            match status {
+                FinalizedStatus::ThresholdReached => {
+                    stats.streams_finalized += 1;
+                    DENOM_STATS.update(deps.storage, &record.in_denom, |denom_stats| {
+                        let mut denom_stats = denom_stats.unwrap_or_default();
+                        denom_stats.volume = denom_stats.volume.checked_add(spent_in)?;
+                        denom_stats.fees_collected =
+                            denom_stats.fees_collected.checked_add(swap_fee)?;
+                        StdResult::Ok(denom_stats)
+                    })?;
+                }
+                FinalizedStatus::ThresholdNotReached => stats.thresholds_missed += 1,
+                FinalizedStatus::MinAveragePriceNotReached => stats.min_average_prices_missed += 1,
+            }
+            record.status = Status::Finalized(status);
+        }
+        StreamCallbackMsg::Cancelled {} => {
+            stats.streams_cancelled += 1;
+            record.status = Status::Cancelled;
+        }
+    }
+    PROTOCOL_STATS.save(deps.storage, &stats)?;
+    streams().save(deps.storage, stream_id, &record)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "stream_callback")
+        .add_attribute("stream_id", stream_id.to_string())
+        .add_attribute("status", record.status.to_string()))
+}
+
+/// Moves a page of the streams registered before the stream records, callable by anyone until
+/// the legacy registry is drained.
+pub fn execute_backfill_streams(
+    deps: DepsMut,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    const MAX_LIMIT: u32 = 30;
+    let limit = limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT) as usize;
+    let (backfilled_streams, completed) = backfill_stream_records(deps, limit)?;
+    Ok(Response::new()
+        .add_attribute("action", "backfill_streams")
+        .add_attribute("backfilled_streams", backfilled_streams.to_string())
+        .add_attribute("completed", completed.to_string()))
+}
+
+pub fn execute_migrate_streams(
+    deps: DepsMut,
+    info: MessageInfo,
+    new_code_id: u64,
+    selection: StreamSelection,
+) -> Result<Response, ContractError> {
+    const MAX_LIMIT: u32 = 30;
+    let params = PARAMS.load(deps.storage)?;
+    if info.sender != params.protocol_admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    validate_code_id(deps.as_ref(), new_code_id)?;
+
+    let selected = match selection {
+        StreamSelection::Ids { stream_ids } => stream_ids
+            .into_iter()
+            .map(|id| Ok((id, streams().load(deps.storage, id)?)))
+            .collect::<StdResult<Vec<_>>>()?,
+        StreamSelection::Range { start_after, limit } => {
+            let limit = limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT) as usize;
+            streams()
+                .range(
+                    deps.storage,
+                    start_after.map(Bound::exclusive),
+                    None,
+                    Order::Ascending,
+                )
+                .take(limit)
+                .collect::<StdResult<Vec<_>>>()?
+        }
+    };
+
+    let migrate_msg = to_json_binary(&StreamMigrateMsg {})?;
+    let mut msgs = vec![];
+    let mut migrated = 0u64;
+    for (stream_id, mut record) in selected {
+        if record.code_id == new_code_id {
+            continue;
+        }
+        msgs.push(WasmMsg::Migrate {
+            contract_addr: record.address.to_string(),
+            new_code_id,
+            msg: migrate_msg.clone(),
+        });
+        record.code_id = new_code_id;
+        streams().save(deps.storage, stream_id, &record)?;
+        migrated += 1;
+    }
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "migrate_streams")
+        .add_attribute("new_code_id", new_code_id.to_string())
+        .add_attribute("migrated_streams", migrated.to_string()))
+}
+
+pub fn execute_propose_admin(
+    deps: DepsMut,
+    info: MessageInfo,
+    new_admin: String,
+) -> Result<Response, ContractError> {
+    let params = PARAMS.load(deps.storage)?;
+    if info.sender != params.protocol_admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    let new_admin = deps.api.addr_validate(&new_admin)?;
+    PENDING_ADMIN.save(deps.storage, &new_admin)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "propose_admin")
+        .add_attribute("pending_admin", new_admin.to_string()))
+}
+
+pub fn execute_accept_admin(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let pending_admin = PENDING_ADMIN
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoPendingAdmin {})?;
+    if info.sender != pending_admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    let mut params = PARAMS.load(deps.storage)?;
+    // Roles still held by the previous admin move to the new admin, delegated roles are kept
+    let mut roles = load_roles(deps.storage, &params)?;
+    roles.reassign(&params.protocol_admin, &pending_admin);
+    ROLES.save(deps.storage, &roles)?;
+    let previous_admin = params.protocol_admin;
+    params.protocol_admin = pending_admin;
+    PARAMS.save(deps.storage, &params)?;
+    PENDING_ADMIN.remove(deps.storage);
+
+    Ok(Response::new()
+        .add_attribute("action", "accept_admin")
+        .add_attribute("previous_admin", previous_admin.to_string())
+        .add_attribute("protocol_admin", params.protocol_admin.to_string()))
+}
+
+pub fn execute_cancel_admin_proposal(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let params = PARAMS.load(deps.storage)?;
+    if info.sender != params.protocol_admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    if PENDING_ADMIN.may_load(deps.storage)?.is_none() {
+        return Err(ContractError::NoPendingAdmin {});
+    }
+    PENDING_ADMIN.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "cancel_admin_proposal"))
+}
+
+pub fn execute_update_roles(
+    deps: DepsMut,
+    info: MessageInfo,
+    params_manager: Option<String>,
+    pauser: Option<String>,
+    stream_canceller: Option<String>,
+    fee_manager: Option<String>,
+) -> Result<Response, ContractError> {
+    let params = PARAMS.load(deps.storage)?;
+    if info.sender != params.protocol_admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    let mut roles = load_roles(deps.storage, &params)?;
+    if let Some(params_manager) = params_manager {
+        roles.params_manager = deps.api.addr_validate(&params_manager)?;
+    }
+    if let Some(pauser) = pauser {
+        roles.pauser = deps.api.addr_validate(&pauser)?;
+    }
+    if let Some(stream_canceller) = stream_canceller {
+        roles.stream_canceller = deps.api.addr_validate(&stream_canceller)?;
+    }
+    if let Some(fee_manager) = fee_manager {
+        roles.fee_manager = deps.api.addr_validate(&fee_manager)?;
+    }
+    ROLES.save(deps.storage, &roles)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_roles")
+        .add_attribute("params_manager", roles.params_manager.to_string())
+        .add_attribute("pauser", roles.pauser.to_string())
+        .add_attribute("stream_canceller", roles.stream_canceller.to_string())
+        .add_attribute("fee_manager", roles.fee_manager.to_string()))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Params {} => to_json_binary(&PARAMS.load(deps.storage)?),
         QueryMsg::Freezestate {} => to_json_binary(&FREEZESTATE.load(deps.storage)?),
         QueryMsg::LastStreamId {} => to_json_binary(&LAST_STREAM_ID.load(deps.storage)?),
-        QueryMsg::ListStreams { start_after, limit } => {
-            to_json_binary(&list_streams(deps, start_after, limit)?)
+        QueryMsg::ListStreams {
+            start_after,
+            limit,
+            filter,
+        } => to_json_binary(&list_streams(deps, env, start_after, limit, filter)?),
+        QueryMsg::StreamByAddress { address } => {
+            to_json_binary(&query_stream_by_address(deps, address)?)
+        }
+        QueryMsg::ProtocolStats {} => to_json_binary(&query_protocol_stats(deps)?),
+        QueryMsg::Roles {} => to_json_binary(&query_roles(deps)?),
+        QueryMsg::ExitFeeOverride { stream } => {
+            to_json_binary(&query_exit_fee_override(deps, stream)?)
         }
     }
 }
 
+pub fn query_exit_fee_override(deps: Deps, stream: String) -> StdResult<Option<Decimal256>> {
+    let stream = deps.api.addr_validate(&stream)?;
+    if let Some(fee) = STREAM_EXIT_FEES.may_load(deps.storage, &stream)? {
+        return Ok(Some(fee));
+    }
+    let Some((_, record)) = streams().idx.address.item(deps.storage, stream)? else {
+        return Ok(None);
+    };
+    if let Some(fee) = CREATOR_EXIT_FEES.may_load(deps.storage, &record.creator)? {
+        return Ok(Some(fee));
+    }
+    IN_DENOM_EXIT_FEES.may_load(deps.storage, &record.in_denom)
+}
+
+pub fn query_roles(deps: Deps) -> StdResult<RolesResponse> {
+    let params = PARAMS.load(deps.storage)?;
+    let roles = load_roles(deps.storage, &params)?;
+    Ok(RolesResponse {
+        protocol_admin: params.protocol_admin.to_string(),
+        pending_admin: PENDING_ADMIN
+            .may_load(deps.storage)?
+            .map(|admin| admin.to_string()),
+        params_manager: roles.params_manager.to_string(),
+        pauser: roles.pauser.to_string(),
+        stream_canceller: roles.stream_canceller.to_string(),
+        fee_manager: roles.fee_manager.to_string(),
+    })
+}
+
+pub fn query_protocol_stats(deps: Deps) -> StdResult<ProtocolStatsResponse> {
+    let stats = PROTOCOL_STATS.may_load(deps.storage)?.unwrap_or_default();
+    let denoms = DENOM_STATS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (denom, denom_stats) = item?;
+            Ok(DenomStatsResponse {
+                denom,
+                volume: denom_stats.volume,
+                fees_collected: denom_stats.fees_collected,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ProtocolStatsResponse {
+        streams_created: LAST_STREAM_ID.load(deps.storage)?,
+        streams_finalized: stats.streams_finalized,
+        streams_cancelled: stats.streams_cancelled,
+        thresholds_missed: stats.thresholds_missed,
+        min_average_prices_missed: stats.min_average_prices_missed,
+        denoms,
+    })
+}
+
+pub fn query_stream_by_address(deps: Deps, address: String) -> StdResult<StreamResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let (id, record) = streams()
+        .idx
+        .address
+        .item(deps.storage, address)?
+        .ok_or_else(|| StdError::not_found("stream"))?;
+    Ok(record.into_response(u64::from_vec(id)?))
+}
+
 pub fn list_streams(
     deps: Deps,
+    env: Env,
     start_after: Option<u64>,
     limit: Option<u32>,
+    filter: Option<StreamsFilter>,
 ) -> StdResult<StreamsResponse> {
     const MAX_LIMIT: u32 = 30;
     let start = start_after.map(Bound::exclusive);
     let limit = limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT) as usize;
-    let streams: StdResult<Vec<StreamResponse>> = STREAMS
-        .range(deps.storage, start, None, Order::Ascending)
-        .take(limit)
-        .map(|item| {
-            let (id, address) = item?;
-            let stream = StreamResponse {
-                id,
-                address: address.to_string(),
-            };
-            Ok(stream)
+    let filter = filter.unwrap_or_default();
+    let creator = filter
+        .creator
+        .as_ref()
+        .map(|creator| deps.api.addr_validate(creator))
+        .transpose()?;
+
+    // Iterate the most selective index available, remaining filters are applied on the records
+    let stream_map = streams();
+    let records = match (&creator, &filter.in_denom, &filter.out_denom) {
+        (Some(creator), _, _) => stream_map.idx.creator.prefix(creator.clone()).range(
+            deps.storage,
+            start,
+            None,
+            Order::Ascending,
+        ),
+        (None, Some(in_denom), _) => stream_map.idx.in_denom.prefix(in_denom.clone()).range(
+            deps.storage,
+            start,
+            None,
+            Order::Ascending,
+        ),
+        (None, None, Some(out_denom)) => stream_map.idx.out_denom.prefix(out_denom.clone()).range(
+            deps.storage,
+            start,
+            None,
+            Order::Ascending,
+        ),
+        (None, None, None) => stream_map.range(deps.storage, start, None, Order::Ascending),
+    };
+
+    let streams = records
+        .filter(|item| match item {
+            Ok((_, record)) => matches_filter(record, creator.as_ref(), &filter, env.block.time),
+            Err(_) => true,
         })
-        .collect();
-    let streams = streams?;
+        .take(limit)
+        .map(|item| item.map(|(id, record)| record.into_response(id)))
+        .collect::<StdResult<Vec<StreamResponse>>>()?;
     Ok(StreamsResponse { streams })
 }
 
+fn matches_filter(
+    record: &StreamRecord,
+    creator: Option<&Addr>,
+    filter: &StreamsFilter,
+    now: Timestamp,
+) -> bool {
+    if creator.is_some_and(|creator| record.creator != creator) {
+        return false;
+    }
+    if let Some(in_denom) = &filter.in_denom {
+        if &record.in_denom != in_denom {
+            return false;
+        }
+    }
+    if let Some(out_denom) = &filter.out_denom {
+        if &record.out_denom != out_denom {
+            return false;
+        }
+    }
+    match filter.window {
+        Some(StreamWindow::Upcoming) => now < record.start_time,
+        Some(StreamWindow::Active) => now >= record.start_time && now < record.end_time,
+        Some(StreamWindow::Ended) => now >= record.end_time,
+        None => true,
+    }
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
-    ensure_from_older_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
-    Ok(Response::default())
+    let from_version = if CONTRACT.exists(deps.storage) {
+        ensure_from_older_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?.to_string()
+    } else {
+        // Controllers instantiated before the contract version was recorded
+        set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+        "unknown".to_string()
+    };
+    let migrated_params = migrate_params(deps.storage)?;
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", from_version)
+        .add_attribute("to_version", CONTRACT_VERSION)
+        .add_attribute("migrated_params", migrated_params.to_string())
+        .add_attribute(
+            "pending_stream_backfill",
+            has_legacy_streams(deps.storage).to_string(),
+        ))
 }
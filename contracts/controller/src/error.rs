@@ -38,9 +38,15 @@ pub enum ContractError {
     #[error("Invalid exit fee percent")]
     InvalidExitFeePercent {},
 
+    #[error("Invalid referral fee percent")]
+    InvalidReferralFeePercent {},
+
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("Stream is not registered by the controller")]
+    StreamNotRegistered {},
+
     #[error("No rewards accrued")]
     NoDistribution {},
 
@@ -115,6 +121,9 @@ pub enum ContractError {
     #[error("Invalid stream creation fee")]
     InvalidStreamCreationFee {},
 
+    #[error("Fee collectors must be unique with positive weights summing up to 100%")]
+    InvalidFeeCollectors {},
+
     #[error("Invalid exit fee")]
     InvalidStreamExitFee {},
 
@@ -132,6 +141,15 @@ pub enum ContractError {
 
     #[error("Invalid terms and services")]
     InvalidToSVersion {},
+
+    #[error("Code id {code_id} is not stored")]
+    InvalidCodeId { code_id: u64 },
+
+    #[error("No pending admin transfer")]
+    NoPendingAdmin {},
+
+    #[error("Stream is already finalized or cancelled")]
+    StreamAlreadySettled {},
 }
 
 impl From<ContractError> for StdError {
@@ -1,8 +1,33 @@
 use std::str::FromStr;
 
 use crate::error::ContractError;
-use cosmwasm_std::{Coin, DepsMut, Uint128};
+use cosmwasm_std::{Api, Coin, Decimal256, Deps, DepsMut, Uint128};
+use cw_denom::{CheckedDenom, UncheckedDenom};
 use osmosis_std::types::osmosis::poolmanager::v1beta1::PoolmanagerQuerier;
+use streamswap_types::controller::{FeeCollector, FeeCollectorMsg};
+
+pub fn validate_fee_collectors(
+    api: &dyn Api,
+    fee_collectors: Vec<FeeCollectorMsg>,
+) -> Result<Vec<FeeCollector>, ContractError> {
+    let mut validated: Vec<FeeCollector> = Vec::with_capacity(fee_collectors.len());
+    let mut total_weight = Decimal256::zero();
+    for collector in fee_collectors {
+        let address = api.addr_validate(&collector.address)?;
+        if collector.weight.is_zero() || validated.iter().any(|c| c.address == address) {
+            return Err(ContractError::InvalidFeeCollectors {});
+        }
+        total_weight = total_weight.checked_add(collector.weight)?;
+        validated.push(FeeCollector {
+            address,
+            weight: collector.weight,
+        });
+    }
+    if total_weight != Decimal256::one() {
+        return Err(ContractError::InvalidFeeCollectors {});
+    }
+    Ok(validated)
+}
 
 pub fn get_pool_creation_fee(deps: &DepsMut) -> Result<Vec<Coin>, ContractError> {
     let pool_creation_fee_vec = PoolmanagerQuerier::new(&deps.querier)
@@ -27,3 +52,21 @@ pub fn get_pool_creation_fee(deps: &DepsMut) -> Result<Vec<Coin>, ContractError>
 
     Ok(cosmwasm_std_coin_vec)
 }
+
+/// Validates the denom, cw20 contracts must respond to token info query
+pub fn validate_denom(deps: Deps, denom: &CheckedDenom) -> Result<(), ContractError> {
+    let unchecked_denom = match denom {
+        CheckedDenom::Native(native) => UncheckedDenom::Native(native.clone()),
+        CheckedDenom::Cw20(cw20) => UncheckedDenom::Cw20(cw20.to_string()),
+    };
+    unchecked_denom.into_checked(deps)?;
+    Ok(())
+}
+
+/// Validates that the code id is stored on chain
+pub fn validate_code_id(deps: Deps, code_id: u64) -> Result<(), ContractError> {
+    deps.querier
+        .query_wasm_code_info(code_id)
+        .map_err(|_| ContractError::InvalidCodeId { code_id })?;
+    Ok(())
+}
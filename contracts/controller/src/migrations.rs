@@ -0,0 +1,97 @@
+use crate::error::ContractError;
+use crate::state::{streams, PARAMS};
+use cosmwasm_std::{Addr, DepsMut, Order, StdResult, Storage, Timestamp};
+use cw_denom::CheckedDenom;
+use cw_storage_plus::{Item, Map};
+use serde::Deserialize;
+use streamswap_types::controller::{LegacyParams, StreamRecord};
+use streamswap_types::stream::{QueryMsg as StreamQueryMsg, Status};
+
+/// Params of the controllers created before the weighted fee collectors
+const LEGACY_PARAMS: Item<LegacyParams> = Item::new("params");
+
+/// Rewrites the params stored in the legacy format, returns whether they were migrated
+pub fn migrate_params(storage: &mut dyn Storage) -> Result<bool, ContractError> {
+    if PARAMS.load(storage).is_ok() {
+        return Ok(false);
+    }
+    let params = LEGACY_PARAMS.load(storage)?.into_params();
+    PARAMS.save(storage, &params)?;
+    Ok(true)
+}
+
+/// Stream registry of the controllers created before the stream records, (stream_id) -> stream_addr
+const LEGACY_STREAMS: Map<u64, Addr> = Map::new("streams");
+
+/// Denoms are plain strings in the responses of streams created before the cw20 support
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StreamDenom {
+    Checked(CheckedDenom),
+    Legacy(String),
+}
+
+impl StreamDenom {
+    fn into_string(self) -> String {
+        match self {
+            StreamDenom::Checked(denom) => denom.to_string(),
+            StreamDenom::Legacy(denom) => denom,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct StreamOutAsset {
+    denom: StreamDenom,
+}
+
+/// Fields of the stream query response recorded by the controller, common to every stream version
+#[derive(Deserialize)]
+struct RecordedStreamResponse {
+    name: String,
+    in_denom: StreamDenom,
+    out_asset: StreamOutAsset,
+    start_time: Timestamp,
+    end_time: Timestamp,
+    status: Status,
+    stream_admin: String,
+}
+
+/// Returns true while streams of the legacy registry are not moved to the stream records
+pub fn has_legacy_streams(storage: &dyn Storage) -> bool {
+    !LEGACY_STREAMS.is_empty(storage)
+}
+
+/// Moves a page of the legacy registry to the indexed stream records, returns the number of
+/// moved streams and whether the registry is drained. Moved streams are removed from the legacy
+/// registry, so each page continues where the previous one stopped. Records are rebuilt from the
+/// stream query, the creator was not stored so the stream admin is recorded instead.
+pub fn backfill_stream_records(deps: DepsMut, limit: usize) -> Result<(u64, bool), ContractError> {
+    let legacy_streams = LEGACY_STREAMS
+        .range(deps.storage, None, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<(u64, Addr)>>>()?;
+    for (stream_id, address) in &legacy_streams {
+        let stream: RecordedStreamResponse = deps
+            .querier
+            .query_wasm_smart(address, &StreamQueryMsg::Stream {})?;
+        let contract_info = deps.querier.query_wasm_contract_info(address)?;
+        let record = StreamRecord {
+            address: address.clone(),
+            code_id: contract_info.code_id,
+            creator: deps.api.addr_validate(&stream.stream_admin)?,
+            in_denom: stream.in_denom.into_string(),
+            out_denom: stream.out_asset.denom.into_string(),
+            name: stream.name,
+            start_time: stream.start_time,
+            end_time: stream.end_time,
+            status: stream.status,
+        };
+        streams().save(deps.storage, *stream_id, &record)?;
+        LEGACY_STREAMS.remove(deps.storage, *stream_id);
+    }
+    Ok((
+        legacy_streams.len() as u64,
+        !has_legacy_streams(deps.storage),
+    ))
+}
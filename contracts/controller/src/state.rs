@@ -1,8 +1,71 @@
-use cosmwasm_std::Addr;
-use cw_storage_plus::{Item, Map};
-use streamswap_types::controller::Params;
+use cosmwasm_std::{Addr, Decimal256, StdResult, Storage};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex, UniqueIndex};
+use streamswap_types::controller::{DenomStats, Params, ProtocolStats, Roles, StreamRecord};
 
 pub const PARAMS: Item<Params> = Item::new("params");
 pub const FREEZESTATE: Item<bool> = Item::new("freezestate");
 pub const LAST_STREAM_ID: Item<u64> = Item::new("last_stream_id");
-pub const STREAMS: Map<u64, Addr> = Map::new("streams");
+pub const ROLES: Item<Roles> = Item::new("roles");
+// Proposed protocol admin, set until the transfer is accepted or cancelled
+pub const PENDING_ADMIN: Item<Addr> = Item::new("pending_admin");
+
+// Controllers instantiated before the roles were introduced give every role to the protocol admin
+pub fn load_roles(storage: &dyn Storage, params: &Params) -> StdResult<Roles> {
+    Ok(ROLES
+        .may_load(storage)?
+        .unwrap_or_else(|| Roles::all(params.protocol_admin.clone())))
+}
+
+pub struct StreamIndexes<'a> {
+    // Reverse lookup from the stream address to its id
+    pub address: UniqueIndex<'a, Addr, StreamRecord, u64>,
+    pub creator: MultiIndex<'a, Addr, StreamRecord, u64>,
+    pub in_denom: MultiIndex<'a, String, StreamRecord, u64>,
+    pub out_denom: MultiIndex<'a, String, StreamRecord, u64>,
+}
+
+impl<'a> IndexList<StreamRecord> for StreamIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<StreamRecord>> + '_> {
+        let v: Vec<&dyn Index<StreamRecord>> = vec![
+            &self.address,
+            &self.creator,
+            &self.in_denom,
+            &self.out_denom,
+        ];
+        Box::new(v.into_iter())
+    }
+}
+
+// Stream registry (stream_id) -> StreamRecord, replaces the stream address map of the previous
+// versions that is moved here on migration
+pub fn streams<'a>() -> IndexedMap<'a, u64, StreamRecord, StreamIndexes<'a>> {
+    let indexes = StreamIndexes {
+        address: UniqueIndex::new(|record| record.address.clone(), "stream_records__address"),
+        creator: MultiIndex::new(
+            |_, record| record.creator.clone(),
+            "stream_records",
+            "stream_records__creator",
+        ),
+        in_denom: MultiIndex::new(
+            |_, record| record.in_denom.clone(),
+            "stream_records",
+            "stream_records__in_denom",
+        ),
+        out_denom: MultiIndex::new(
+            |_, record| record.out_denom.clone(),
+            "stream_records",
+            "stream_records__out_denom",
+        ),
+    };
+    IndexedMap::new("stream_records", indexes)
+}
+
+// Exit fee overrides set by the protocol admin
+pub const STREAM_EXIT_FEES: Map<&Addr, Decimal256> = Map::new("stream_exit_fees");
+pub const CREATOR_EXIT_FEES: Map<&Addr, Decimal256> = Map::new("creator_exit_fees");
+pub const IN_DENOM_EXIT_FEES: Map<&str, Decimal256> = Map::new("in_denom_exit_fees");
+
+// Statistics aggregated from the stream callbacks
+pub const PROTOCOL_STATS: Item<ProtocolStats> = Item::new("protocol_stats");
+// in_denom -> DenomStats
+pub const DENOM_STATS: Map<&str, DenomStats> = Map::new("denom_stats");
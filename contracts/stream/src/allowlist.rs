@@ -0,0 +1,77 @@
+use crate::state::{ALLOWLIST_VERIFIED, MERKLE_ROOT};
+use crate::ContractError;
+use cosmwasm_std::{Addr, HexBinary, StdResult, Storage};
+use sha2::{Digest, Sha256};
+
+/// Parses hex encoded sha256 merkle root
+pub fn parse_merkle_root(merkle_root: &str) -> Result<HexBinary, ContractError> {
+    let root = HexBinary::from_hex(merkle_root).map_err(|_| ContractError::InvalidMerkleRoot {})?;
+    if root.len() != 32 {
+        return Err(ContractError::InvalidMerkleRoot {});
+    }
+    Ok(root)
+}
+
+/// Verifies that the address is a leaf of the merkle tree with the given root.
+/// Leaves are sha256 hashes of the bech32 address, pairs are sorted before hashing.
+pub fn verify_merkle_proof(
+    merkle_root: &HexBinary,
+    addr: &Addr,
+    proof: &[String],
+) -> Result<(), ContractError> {
+    let leaf: [u8; 32] = Sha256::digest(addr.as_bytes()).into();
+    let computed_root = proof.iter().try_fold(leaf, |hash, p| {
+        let proof_hash: [u8; 32] = HexBinary::from_hex(p)
+            .map_err(|_| ContractError::InvalidMerkleProof {})?
+            .to_array()
+            .map_err(|_| ContractError::InvalidMerkleProof {})?;
+        let mut hashes = [hash, proof_hash];
+        hashes.sort_unstable();
+        Ok::<[u8; 32], ContractError>(Sha256::digest(hashes.concat()).into())
+    })?;
+
+    if computed_root != merkle_root.as_slice() {
+        return Err(ContractError::NotAllowlisted {});
+    }
+    Ok(())
+}
+
+/// Checks subscriber against the allowlist if the stream has one.
+/// Verified addresses are recorded so that later subscriptions against the same root do not
+/// require a proof.
+pub fn check_allowlist(
+    storage: &mut dyn Storage,
+    addr: &Addr,
+    proof: Option<Vec<String>>,
+) -> Result<(), ContractError> {
+    if verify_allowlist(storage, addr, proof.as_deref())? {
+        let merkle_root = MERKLE_ROOT.load(storage)?;
+        ALLOWLIST_VERIFIED.save(storage, addr, &merkle_root)?;
+    }
+    Ok(())
+}
+
+/// Returns true if the address was verified against the current merkle root
+pub fn is_allowlist_verified(storage: &dyn Storage, addr: &Addr) -> StdResult<bool> {
+    let verified_root = ALLOWLIST_VERIFIED.may_load(storage, addr)?;
+    Ok(verified_root.is_some() && verified_root == MERKLE_ROOT.may_load(storage)?)
+}
+
+/// Checks subscriber against the allowlist if the stream has one, without recording it.
+/// Returns true if the address was verified by the proof.
+pub fn verify_allowlist(
+    storage: &dyn Storage,
+    addr: &Addr,
+    proof: Option<&[String]>,
+) -> Result<bool, ContractError> {
+    let merkle_root = match MERKLE_ROOT.may_load(storage)? {
+        Some(merkle_root) => merkle_root,
+        None => return Ok(false),
+    };
+    if ALLOWLIST_VERIFIED.may_load(storage, addr)? == Some(merkle_root.clone()) {
+        return Ok(false);
+    }
+    let proof = proof.ok_or(ContractError::NotAllowlisted {})?;
+    verify_merkle_proof(&merkle_root, addr, proof)?;
+    Ok(true)
+}
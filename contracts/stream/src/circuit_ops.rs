@@ -1,11 +1,12 @@
+use crate::helpers::{build_refund_msgs, query_protocol_roles};
+use crate::hooks::controller_callback_msg;
 use crate::pool::pool_refund;
 use crate::state::{CONTROLLER_PARAMS, POST_STREAM, STREAM_INFO, STREAM_STATE};
 use crate::stream::{sync_stream, sync_stream_status};
 use crate::ContractError;
-use cosmwasm_std::{BankMsg, CosmosMsg, DepsMut, Env, MessageInfo, Response};
-use cw_utils::NativeBalance;
-use streamswap_types::controller::Params;
-use streamswap_types::stream::Status;
+use cosmwasm_std::{DepsMut, Env, MessageInfo, Response, Uint128};
+use streamswap_types::controller::{Params, StreamCallbackMsg};
+use streamswap_types::stream::{Asset, Status};
 
 pub fn execute_cancel_stream(
     deps: DepsMut,
@@ -13,8 +14,9 @@ pub fn execute_cancel_stream(
     info: MessageInfo,
 ) -> Result<Response, ContractError> {
     let controller_params: Params = CONTROLLER_PARAMS.load(deps.storage)?;
+    let roles = query_protocol_roles(deps.as_ref(), &controller_params)?;
 
-    if controller_params.protocol_admin != info.sender {
+    if roles.stream_canceller != info.sender {
         return Err(ContractError::Unauthorized {});
     }
     let mut stream = STREAM_STATE.load(deps.storage)?;
@@ -25,45 +27,111 @@ pub fn execute_cancel_stream(
             current_status: stream.status_info.status.to_string(),
         });
     }
-    stream.status_info.status = Status::Cancelled;
-
+    // Paused streams are not synced, paused duration is not distributed
     sync_stream(&mut stream, env.block.time);
+    stream.status_info.status = Status::Cancelled;
     STREAM_STATE.save(deps.storage, &stream)?;
 
-    // Refund all out tokens to stream creator(treasury)
-    let mut refund_coins = NativeBalance::default() + stream.out_asset.clone();
+    // Refund all unclaimed out tokens to stream creator(treasury), together with the
+    // in tokens spent for the claimed ones
+    let mut refund_assets = vec![
+        Asset::new(
+            stream.out_asset.denom.clone(),
+            stream.out_asset.amount - Uint128::try_from(stream.claimed_out)?,
+        ),
+        Asset::new(
+            stream.in_denom.clone(),
+            Uint128::try_from(stream.claimed_spent_in)?,
+        ),
+    ];
 
     // refund pool creation if any
     let post_stream_ops = POST_STREAM.may_load(deps.storage)?;
     if let Some(post_stream_ops) = post_stream_ops {
-        let pool_refund_coins = pool_refund(
+        let pool_refund_assets = pool_refund(
             &deps,
             post_stream_ops.pool_config,
             stream.out_asset.denom.clone(),
         )?;
-        for coin in pool_refund_coins {
-            refund_coins += coin;
-        }
+        refund_assets.extend(pool_refund_assets);
     }
 
-    refund_coins.normalize();
     let stream_info = STREAM_INFO.load(deps.storage)?;
-    let funds_msgs: Vec<CosmosMsg> = refund_coins
-        .into_vec()
-        .iter()
-        .map(|coin| {
-            CosmosMsg::Bank(BankMsg::Send {
-                to_address: stream_info.treasury.to_string(),
-                amount: vec![coin.clone()],
-            })
-        })
-        .collect();
+    let funds_msgs = build_refund_msgs(refund_assets, &stream_info.treasury)?;
+    let callback_msg = controller_callback_msg(deps.storage, StreamCallbackMsg::Cancelled {})?;
 
     Ok(Response::new()
         .add_attribute("action", "cancel_stream")
         .add_attribute("status", "cancelled")
-        .add_messages(funds_msgs))
+        .add_messages(funds_msgs)
+        .add_submessages(callback_msg))
+}
+
+pub fn execute_pause_stream(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let controller_params: Params = CONTROLLER_PARAMS.load(deps.storage)?;
+    let roles = query_protocol_roles(deps.as_ref(), &controller_params)?;
+    if roles.pauser != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    let mut stream = STREAM_STATE.load(deps.storage)?;
+    sync_stream_status(&mut stream, env.block.time);
+    // Distribute up to the pause time, the stream can end here if the hard cap is reached
+    sync_stream(&mut stream, env.block.time);
+
+    // Only active streams can be paused
+    if !stream.is_active() {
+        return Err(ContractError::OperationNotAllowed {
+            current_status: stream.status_info.status.to_string(),
+        });
+    }
+    stream.status_info.status = Status::Paused;
+    stream.status_info.paused_at = Some(env.block.time);
+    STREAM_STATE.save(deps.storage, &stream)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "pause_stream")
+        .add_attribute("paused_at", env.block.time.to_string()))
+}
+
+pub fn execute_resume_stream(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let controller_params: Params = CONTROLLER_PARAMS.load(deps.storage)?;
+    let roles = query_protocol_roles(deps.as_ref(), &controller_params)?;
+    if roles.pauser != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    let mut stream = STREAM_STATE.load(deps.storage)?;
+    let paused_at = match (stream.is_paused(), stream.status_info.paused_at) {
+        (true, Some(paused_at)) => paused_at,
+        _ => {
+            return Err(ContractError::OperationNotAllowed {
+                current_status: stream.status_info.status.to_string(),
+            })
+        }
+    };
+
+    // Extend the end time so that paused duration is not counted as streamed time
+    let paused_nanos = env.block.time.nanos().saturating_sub(paused_at.nanos());
+    stream.status_info.end_time = stream.status_info.end_time.plus_nanos(paused_nanos);
+    stream.status_info.paused_nanos += paused_nanos;
+    stream.status_info.paused_at = None;
+    stream.status_info.last_updated = env.block.time;
+    stream.status_info.status = Status::Active;
+    STREAM_STATE.save(deps.storage, &stream)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "resume_stream")
+        .add_attribute("paused_duration", paused_nanos.to_string())
+        .add_attribute("end_time", stream.status_info.end_time.to_string()))
 }
+
 pub fn execute_stream_admin_cancel(
     deps: DepsMut,
     env: Env,
@@ -89,36 +157,25 @@ pub fn execute_stream_admin_cancel(
     STREAM_STATE.save(deps.storage, &stream)?;
 
     // Refund all out tokens to stream creator(treasury)
-    let mut refund_coins = NativeBalance::default() + stream.out_asset.clone();
+    let mut refund_assets = vec![stream.out_asset.clone()];
 
     // refund pool creation if any
     let post_stream_ops = POST_STREAM.may_load(deps.storage)?;
     if let Some(post_stream_ops) = post_stream_ops {
-        let pool_refund_coins = pool_refund(
+        let pool_refund_assets = pool_refund(
             &deps,
             post_stream_ops.pool_config,
             stream.out_asset.denom.clone(),
         )?;
-        for coin in pool_refund_coins {
-            refund_coins += coin;
-        }
+        refund_assets.extend(pool_refund_assets);
     }
 
-    refund_coins.normalize();
-
-    let funds_msgs: Vec<CosmosMsg> = refund_coins
-        .into_vec()
-        .iter()
-        .map(|coin| {
-            CosmosMsg::Bank(BankMsg::Send {
-                to_address: stream_info.treasury.to_string(),
-                amount: vec![coin.clone()],
-            })
-        })
-        .collect();
+    let funds_msgs = build_refund_msgs(refund_assets, &stream_info.treasury)?;
+    let callback_msg = controller_callback_msg(deps.storage, StreamCallbackMsg::Cancelled {})?;
 
     Ok(Response::new()
         .add_attribute("action", "cancel_stream")
         .add_messages(funds_msgs)
+        .add_submessages(callback_msg)
         .add_attribute("status", "cancelled"))
 }
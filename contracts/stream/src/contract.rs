@@ -1,33 +1,54 @@
+use crate::allowlist::{
+    check_allowlist, is_allowlist_verified, parse_merkle_root, verify_allowlist,
+};
 use crate::helpers::{
-    build_u128_bank_send_msg, check_name_and_url, get_decimals, validate_stream_times,
+    build_refund_msgs, build_u128_transfer_msg, check_name_and_url, get_decimals,
+    query_exit_fee_percent, resolve_exit_fee_percent, validate_stream_times,
+    validate_treasury_recipients,
+};
+use crate::hooks::{
+    add_hook, controller_callback_msg, hook_msgs, remove_hook, CONTROLLER_CALLBACK_REPLY_ID,
+    HOOK_REPLY_ID,
 };
+use crate::migrations::{migrate_positions, migrate_state};
 use crate::stream::{compute_shares_amount, sync_stream, sync_stream_status};
-use crate::{circuit_ops, ContractError};
+use crate::{circuit_ops, nft, ContractError};
 use core::str;
 use cosmwasm_std::{
-    attr, entry_point, to_json_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Decimal256, Deps,
-    DepsMut, Env, MessageInfo, Order, Response, StdResult, Timestamp, Uint128, Uint256,
+    attr, entry_point, from_json, to_json_binary, Addr, Binary, Decimal256, Deps, DepsMut, Env,
+    Event, MessageInfo, Order, Reply, Response, StdError, StdResult, Storage, Timestamp, Uint128,
+    Uint256,
 };
 use cw2::{ensure_from_older_version, set_contract_version};
+use cw20::Cw20ReceiveMsg;
+use cw_denom::CheckedDenom;
 use cw_storage_plus::Bound;
-use cw_utils::{maybe_addr, must_pay, NativeBalance};
+use cw_utils::{maybe_addr, must_pay};
 use std::env;
 use streamswap_types::stream::{
-    AveragePriceResponse, ExecuteMsg, FinalizedStatus, LatestStreamedPriceResponse,
-    PositionResponse, PositionsResponse, QueryMsg, StreamResponse,
+    AveragePriceResponse, ExecuteMsg, FinalizedStatus, HooksResponse, LatestStreamedPriceResponse,
+    PositionDelta, PositionResponse, PositionsResponse, QueryMsg, ReceiveMsg, ReferralResponse,
+    ReferralsResponse, ShareRounding, SimulateSubscribeResponse, SimulateWithdrawResponse,
+    StreamHookMsg, StreamResponse,
 };
 use streamswap_types::stream::{PostStreamActions, StreamInfo, StreamState};
 use streamswap_utils::to_uint256;
 
 use crate::pool::{pool_operations, pool_refund};
 use crate::state::{
-    CONTROLLER_PARAMS, CREATOR_VESTING, POSITIONS, POST_STREAM, STREAM_INFO, STREAM_STATE,
-    SUBSCRIBER_VESTING, TOS, TOS_SIGNED,
+    CONTROLLER, CONTROLLER_PARAMS, CREATOR_VESTING, DUST_SWEPT, EXIT_FEE_PERCENT, EXIT_TOTALS,
+    HOOKS, MERKLE_ROOT, POSITIONS, POSITIONS_MIGRATION, POSITION_NFT, POST_STREAM, REFERRALS,
+    STREAM_INFO, STREAM_STATE, SUBSCRIBER_VESTING, TOS, TOS_SIGNED, TREASURY_RECIPIENTS,
+    TREASURY_VESTING, UNEXITED_POSITIONS,
+};
+use crate::vesting::{
+    creator_vesting_salt, subscriber_vesting_salt, treasury_vesting_salt, vesting_operations,
+};
+use streamswap_types::controller::{
+    split_by_weight, CreatePool, Params as ControllerParams, PoolConfig,
 };
-use crate::vesting::vesting_operations;
-use streamswap_types::controller::{CreatePool, Params as ControllerParams, PoolConfig};
-use streamswap_types::controller::{CreateStreamMsg, MigrateMsg};
-use streamswap_types::stream::{Position, Status};
+use streamswap_types::controller::{CreateStreamMsg, StreamCallbackMsg};
+use streamswap_types::stream::{Asset, MigrateMsg, Position, Status};
 
 // Version and contract info for migration
 const CONTRACT_NAME: &str = "crates.io:streamswap-stream";
@@ -48,15 +69,29 @@ pub fn instantiate(
     // Controller parameters are collected at the time of stream creation
     // Any changes to controller parameters will not affect the stream
     CONTROLLER_PARAMS.save(deps.storage, &controller_params)?;
+    CONTROLLER.save(deps.storage, &info.sender)?;
+    // Exit fee overrides set on the controller are resolved once, at stream creation
+    let exit_fee_percent =
+        resolve_exit_fee_percent(deps.as_ref(), &env, &info.sender, &controller_params)?;
+    EXIT_FEE_PERCENT.save(deps.storage, &exit_fee_percent)?;
 
     let CreateStreamMsg {
         bootstraping_start_time,
         start_time,
         end_time,
         treasury,
+        treasury_recipients,
         name,
         url,
         threshold,
+        min_average_price,
+        emission_curve,
+        max_in_per_address,
+        max_in_supply,
+        hard_cap,
+        merkle_root,
+        position_nft,
+        hooks,
         out_asset,
         in_denom,
         stream_admin,
@@ -67,11 +102,13 @@ pub fn instantiate(
         tos_version,
     } = msg;
 
+    let emission_curve = emission_curve.unwrap_or_default();
     validate_stream_times(
         env.block.time,
         bootstraping_start_time,
         start_time,
         end_time,
+        &emission_curve,
         &controller_params,
     )?;
 
@@ -83,11 +120,51 @@ pub fn instantiate(
             return Err(ContractError::InvalidThreshold {});
         }
     }
+    if min_average_price.is_some_and(|price| price.is_zero()) {
+        return Err(ContractError::InvalidMinAveragePrice {});
+    }
+    if max_in_per_address.is_some_and(|cap| cap.is_zero())
+        || max_in_supply.is_some_and(|cap| cap.is_zero())
+    {
+        return Err(ContractError::InvalidSubscriptionCap {});
+    }
+    // A hard cap below the threshold would end the stream before it can be finalized
+    if hard_cap.is_some_and(|cap| cap.is_zero() || threshold.is_some_and(|t| cap < t)) {
+        return Err(ContractError::InvalidHardCap {});
+    }
+    // Pools can only be created with native denoms
+    if pool_config.is_some()
+        && !(matches!(in_denom, CheckedDenom::Native(_))
+            && matches!(out_asset.denom, CheckedDenom::Native(_)))
+    {
+        return Err(ContractError::InvalidPoolConfig {});
+    }
     let stream_admin = deps.api.addr_validate(&stream_admin)?;
     let treasury = deps.api.addr_validate(&treasury)?;
+    if let Some(treasury_recipients) = treasury_recipients {
+        // Vesting of the creator revenue is configured per recipient
+        if creator_vesting.is_some() {
+            return Err(ContractError::InvalidTreasuryRecipients {});
+        }
+        let treasury_recipients = validate_treasury_recipients(deps.api, treasury_recipients)?;
+        TREASURY_RECIPIENTS.save(deps.storage, &treasury_recipients)?;
+    }
 
     check_name_and_url(&name, &url)?;
 
+    if let Some(merkle_root) = merkle_root {
+        MERKLE_ROOT.save(deps.storage, &parse_merkle_root(&merkle_root)?)?;
+    }
+    if let Some(position_nft) = position_nft {
+        if position_nft.name.is_empty() || position_nft.symbol.is_empty() {
+            return Err(ContractError::InvalidPositionNftConfig {});
+        }
+        POSITION_NFT.save(deps.storage, &position_nft)?;
+    }
+    for hook in hooks.unwrap_or_default() {
+        add_hook(deps.storage, deps.api.addr_validate(&hook)?)?;
+    }
+
     let stream_state = StreamState::new(
         env.block.time,
         out_asset.clone(),
@@ -96,6 +173,11 @@ pub fn instantiate(
         start_time,
         end_time,
         threshold,
+        min_average_price,
+        emission_curve,
+        max_in_per_address,
+        max_in_supply,
+        hard_cap,
     );
     STREAM_STATE.save(deps.storage, &stream_state)?;
 
@@ -107,14 +189,15 @@ pub fn instantiate(
     POST_STREAM.save(deps.storage, &post_stream_actions)?;
 
     TOS.save(deps.storage, &tos_version)?;
+    UNEXITED_POSITIONS.save(deps.storage, &0)?;
 
     let mut attrs = vec![
         attr("action", "instantiate"),
         attr("name", name),
         attr("treasury", treasury),
         attr("stream_admin", stream_admin),
-        attr("out_asset", out_asset.denom),
-        attr("in_denom", in_denom),
+        attr("out_asset", out_asset.denom.to_string()),
+        attr("in_denom", in_denom.to_string()),
         attr("start_time", start_time.to_string()),
         attr("end_time", end_time.to_string()),
         attr(
@@ -149,22 +232,81 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::SyncPosition {} => execute_sync_position(deps, env, info),
+        ExecuteMsg::ClaimPurchased {} => execute_claim_purchased(deps, env, info),
         ExecuteMsg::SyncStream {} => execute_sync_stream(deps, env),
-        ExecuteMsg::Subscribe {} => {
+        ExecuteMsg::Subscribe {
+            proof,
+            on_behalf_of,
+            referrer,
+        } => {
             let stream = STREAM_STATE.load(deps.storage)?;
-            execute_subscribe(deps, env, info, stream)
+            // cw20 subscriptions go through the receive hook
+            let in_amount = match &stream.in_denom {
+                CheckedDenom::Native(in_denom) => must_pay(&info, in_denom)?,
+                CheckedDenom::Cw20(_) => return Err(ContractError::InDenomIsNotAccepted {}),
+            };
+            execute_subscribe(
+                deps,
+                env,
+                info.sender,
+                on_behalf_of,
+                referrer,
+                in_amount,
+                stream,
+                proof,
+            )
         }
+        ExecuteMsg::Receive(receive_msg) => execute_receive(deps, env, info, receive_msg),
         ExecuteMsg::Withdraw { cap } => {
             let stream = STREAM_STATE.load(deps.storage)?;
             execute_withdraw(deps, env, info, stream, cap)
         }
+        ExecuteMsg::TransferPosition { recipient } => {
+            execute_transfer_position(deps, env, info, recipient)
+        }
+        ExecuteMsg::TransferNft {
+            recipient,
+            token_id,
+        } => nft::execute_transfer_nft(deps, env, info, recipient, token_id),
+        ExecuteMsg::SendNft {
+            contract,
+            token_id,
+            msg,
+        } => nft::execute_send_nft(deps, env, info, contract, token_id, msg),
+        ExecuteMsg::Approve {
+            spender,
+            token_id,
+            expires,
+        } => nft::execute_approve(deps, env, info, spender, token_id, expires),
+        ExecuteMsg::Revoke { spender, token_id } => {
+            nft::execute_revoke(deps, env, info, spender, token_id)
+        }
+        ExecuteMsg::ApproveAll { operator, expires } => {
+            nft::execute_approve_all(deps, env, info, operator, expires)
+        }
+        ExecuteMsg::RevokeAll { operator } => nft::execute_revoke_all(deps, info, operator),
         ExecuteMsg::FinalizeStream {
             new_treasury,
             create_pool,
             salt,
         } => execute_finalize_stream(deps, env, info, new_treasury, create_pool, salt),
         ExecuteMsg::ExitStream { salt } => execute_exit_stream(deps, env, info, salt),
+        ExecuteMsg::ExitPositions { start_after, limit } => {
+            execute_exit_positions(deps, env, start_after, limit)
+        }
+        ExecuteMsg::SweepDust {} => execute_sweep_dust(deps, env),
+        ExecuteMsg::MigratePositions { limit } => execute_migrate_positions(deps, limit),
+        ExecuteMsg::UpdateExitFee { exit_fee_override } => {
+            execute_update_exit_fee(deps, env, info, exit_fee_override)
+        }
+        ExecuteMsg::UpdateMerkleRoot { merkle_root } => {
+            execute_update_merkle_root(deps, env, info, merkle_root)
+        }
+        ExecuteMsg::AddHook { addr } => execute_update_hooks(deps, env, info, addr, true),
+        ExecuteMsg::RemoveHook { addr } => execute_update_hooks(deps, env, info, addr, false),
         ExecuteMsg::CancelStream {} => circuit_ops::execute_cancel_stream(deps, env, info),
+        ExecuteMsg::PauseStream {} => circuit_ops::execute_pause_stream(deps, env, info),
+        ExecuteMsg::ResumeStream {} => circuit_ops::execute_resume_stream(deps, env, info),
         ExecuteMsg::StreamAdminCancel {} => {
             circuit_ops::execute_stream_admin_cancel(deps, env, info)
         }
@@ -242,6 +384,68 @@ pub fn execute_sync_position(
     Ok(res)
 }
 
+pub fn execute_claim_purchased(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let mut position = POSITIONS.load(deps.storage, &info.sender)?;
+
+    let mut stream = STREAM_STATE.load(deps.storage)?;
+    sync_stream_status(&mut stream, env.block.time);
+    if !stream.is_active() {
+        return Err(ContractError::OperationNotAllowed {
+            current_status: stream.status_info.status.to_string(),
+        });
+    }
+    // Vested purchases are only released through the vesting contract on exit
+    if POST_STREAM
+        .may_load(deps.storage)?
+        .is_some_and(|post_stream| post_stream.subscriber_vesting.is_some())
+    {
+        return Err(ContractError::VestedClaimNotAllowed {});
+    }
+    // Average price is only known at the end of the stream, purchases can not be claimed before
+    if stream.min_average_price.is_some() {
+        return Err(ContractError::MinAveragePriceClaimNotAllowed {});
+    }
+
+    sync_stream(&mut stream, env.block.time);
+    // spent_in never decreases, once the threshold is reached the stream can not be refunded
+    // except by cancellation, which returns the claimed spent amount to the treasury
+    if !stream.check_threshold() {
+        return Err(ContractError::ThresholdNotReached {});
+    }
+    sync_position(
+        stream.dist_index,
+        stream.shares,
+        stream.status_info.last_updated,
+        stream.in_supply,
+        &mut position,
+    )?;
+
+    let claim_amount = position.purchased.checked_sub(position.claimed)?;
+    if claim_amount.is_zero() {
+        return Err(ContractError::NoDistribution {});
+    }
+    // Every purchase up to now is claimed, so all of the spent amount is settled
+    let claimed_spent = position.spent.checked_sub(position.claimed_spent)?;
+    position.claimed = position.purchased;
+    position.claimed_spent = position.spent;
+    POSITIONS.save(deps.storage, &position.owner, &position)?;
+
+    stream.claimed_out = stream.claimed_out.checked_add(claim_amount)?;
+    stream.claimed_spent_in = stream.claimed_spent_in.checked_add(claimed_spent)?;
+    STREAM_STATE.save(deps.storage, &stream)?;
+
+    let send_msg = build_u128_transfer_msg(&stream.out_asset.denom, &info.sender, claim_amount)?;
+    Ok(Response::new()
+        .add_message(send_msg)
+        .add_attribute("action", "claim_purchased")
+        .add_attribute("claimed", claim_amount)
+        .add_attribute("total_claimed", position.claimed))
+}
+
 // calculate the user purchase based on the positions index and the global index.
 // returns purchased out amount and spent in amount
 pub fn sync_position(
@@ -289,14 +493,57 @@ pub fn sync_position(
     Ok((uint256_purchased, spent))
 }
 
-pub fn execute_subscribe(
+pub fn execute_receive(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    receive_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let stream = STREAM_STATE.load(deps.storage)?;
+    // Only the in denom cw20 contract can call the hook
+    if !stream.in_denom.is_cw20(&info.sender) {
+        return Err(ContractError::InDenomIsNotAccepted {});
+    }
+    let sender = deps.api.addr_validate(&receive_msg.sender)?;
+
+    match from_json(&receive_msg.msg)? {
+        ReceiveMsg::Subscribe {
+            proof,
+            on_behalf_of,
+            referrer,
+        } => execute_subscribe(
+            deps,
+            env,
+            sender,
+            on_behalf_of,
+            referrer,
+            receive_msg.amount,
+            stream,
+            proof,
+        ),
+    }
+}
+
+pub fn execute_subscribe(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    on_behalf_of: Option<String>,
+    referrer: Option<String>,
+    in_amount: Uint128,
     mut stream_state: StreamState,
+    proof: Option<Vec<String>>,
 ) -> Result<Response, ContractError> {
+    // Position, allowlist and caps all belong to the subscriber, the sender only pays
+    let subscriber = match on_behalf_of {
+        Some(on_behalf_of) => deps.api.addr_validate(&on_behalf_of)?,
+        None => sender.clone(),
+    };
     // Update stream status
     sync_stream_status(&mut stream_state, env.block.time);
+    // incoming tokens should not participate in prev distribution, syncing can also end the
+    // stream if the hard cap is reached
+    sync_stream(&mut stream_state, env.block.time);
 
     if !(stream_state.is_active() || stream_state.is_bootstrapping()) {
         return Err(ContractError::OperationNotAllowed {
@@ -304,58 +551,101 @@ pub fn execute_subscribe(
         });
     }
 
-    let in_amount = must_pay(&info, &stream_state.in_denom)?;
     let uint256_in_amount = Uint256::from(in_amount.u128());
     let new_shares;
 
-    let position = POSITIONS.may_load(deps.storage, &info.sender)?;
-    match position {
+    check_max_in_supply(&stream_state, uint256_in_amount)?;
+
+    // Checked on every subscription, so that transferred positions can not be topped up by
+    // recipients outside of the allowlist
+    check_allowlist(deps.storage, &subscriber, proof)?;
+
+    let position = POSITIONS.may_load(deps.storage, &subscriber)?;
+    let delta = match position {
         None => {
-            // incoming tokens should not participate in prev distribution
-            sync_stream(&mut stream_state, env.block.time);
+            check_max_in_per_address(&stream_state, uint256_in_amount)?;
+            // Referrer is only recorded on the first subscription of the position
+            let referrer = maybe_addr(deps.api, referrer)?;
+            if let Some(referrer) = &referrer {
+                if referrer == subscriber {
+                    return Err(ContractError::InvalidReferrer {});
+                }
+                REFERRALS.update(deps.storage, referrer, |referral| -> StdResult<_> {
+                    let mut referral = referral.unwrap_or_default();
+                    referral.referred_positions += 1;
+                    Ok(referral)
+                })?;
+            }
             new_shares = compute_shares_amount(&stream_state, uint256_in_amount, false);
+            let token_id = nft::mint_position_token(deps.storage, &subscriber)?;
             // new positions do not update purchase as it has no effect on distribution
             let new_position = Position::new(
-                info.sender.clone(),
+                subscriber.clone(),
                 uint256_in_amount,
                 new_shares,
                 Some(stream_state.dist_index),
                 env.block.time,
+                token_id,
+                referrer,
             );
-            POSITIONS.save(deps.storage, &info.sender, &new_position)?;
-
-            // Save signed TOS
-            let tos_version = TOS.load(deps.storage)?;
-            TOS_SIGNED.save(deps.storage, &info.sender, &tos_version)?;
+            POSITIONS.save(deps.storage, &subscriber, &new_position)?;
+            UNEXITED_POSITIONS.update(deps.storage, |count| -> StdResult<_> { Ok(count + 1) })?;
+            PositionDelta::default()
         }
         Some(mut position) => {
-            if position.owner != info.sender {
-                return Err(ContractError::Unauthorized {});
-            }
-            // incoming tokens should not participate in prev distribution
-            sync_stream(&mut stream_state, env.block.time);
             new_shares = compute_shares_amount(&stream_state, uint256_in_amount, false);
-            sync_position(
+            let (purchased, spent) = sync_position(
                 stream_state.dist_index,
                 stream_state.shares,
                 stream_state.status_info.last_updated,
                 stream_state.in_supply,
                 &mut position,
             )?;
+            // in_balance + spent is the total amount the position subscribed with
+            check_max_in_per_address(
+                &stream_state,
+                position
+                    .in_balance
+                    .checked_add(position.spent)?
+                    .checked_add(uint256_in_amount)?,
+            )?;
 
+            // Positions withdrawn before spending are counted again once they hold funds
+            if !has_exit_balance(&position)? {
+                UNEXITED_POSITIONS
+                    .update(deps.storage, |count| -> StdResult<_> { Ok(count + 1) })?;
+            }
             position.in_balance = position.in_balance.checked_add(uint256_in_amount)?;
             position.shares = position.shares.checked_add(new_shares)?;
-            POSITIONS.save(deps.storage, &info.sender, &position)?;
+            POSITIONS.save(deps.storage, &subscriber, &position)?;
+            PositionDelta { purchased, spent }
         }
-    }
+    };
+
+    // Save signed TOS, only the sender signs by subscribing, a subscriber credited by another
+    // address signs once it subscribes itself
+    let tos_version = TOS.load(deps.storage)?;
+    TOS_SIGNED.save(deps.storage, &sender, &tos_version)?;
 
     // increase in supply and shares
     stream_state.in_supply = stream_state.in_supply.checked_add(uint256_in_amount)?;
     stream_state.shares = stream_state.shares.checked_add(new_shares)?;
     STREAM_STATE.save(deps.storage, &stream_state)?;
 
+    let hook_msgs = hook_msgs(
+        deps.storage,
+        StreamHookMsg::Subscribe {
+            subscriber: subscriber.to_string(),
+            in_amount: uint256_in_amount,
+            shares: new_shares,
+            delta,
+        },
+    )?;
     let res = Response::new()
+        .add_submessages(hook_msgs)
         .add_attribute("action", "subscribe")
+        .add_attribute("sender", sender)
+        .add_attribute("subscriber", subscriber)
         .add_attribute("status info", stream_state.status_info.status.to_string())
         .add_attribute("in_supply", stream_state.in_supply)
         .add_attribute("in_amount", in_amount)
@@ -366,6 +656,131 @@ pub fn execute_subscribe(
     Ok(res)
 }
 
+pub fn execute_update_merkle_root(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    merkle_root: Option<String>,
+) -> Result<Response, ContractError> {
+    let stream_info = STREAM_INFO.load(deps.storage)?;
+    if stream_info.stream_admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut stream_state = STREAM_STATE.load(deps.storage)?;
+    sync_stream_status(&mut stream_state, env.block.time);
+    if !(stream_state.is_waiting() || stream_state.is_bootstrapping()) {
+        return Err(ContractError::OperationNotAllowed {
+            current_status: stream_state.status_info.status.to_string(),
+        });
+    }
+
+    match merkle_root.clone() {
+        Some(merkle_root) => {
+            MERKLE_ROOT.save(deps.storage, &parse_merkle_root(&merkle_root)?)?;
+        }
+        None => MERKLE_ROOT.remove(deps.storage),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "update_merkle_root")
+        .add_attribute("merkle_root", merkle_root.unwrap_or_default()))
+}
+
+/// Applies the exit fee override of the stream set on the controller, only before the stream
+/// starts. Without an override the fee of the collected controller params applies.
+pub fn execute_update_exit_fee(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    exit_fee_override: Option<Decimal256>,
+) -> Result<Response, ContractError> {
+    if CONTROLLER.may_load(deps.storage)?.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut stream_state = STREAM_STATE.load(deps.storage)?;
+    sync_stream_status(&mut stream_state, env.block.time);
+    if !(stream_state.is_waiting() || stream_state.is_bootstrapping()) {
+        return Err(ContractError::OperationNotAllowed {
+            current_status: stream_state.status_info.status.to_string(),
+        });
+    }
+
+    let controller_params = CONTROLLER_PARAMS.load(deps.storage)?;
+    let exit_fee_percent = exit_fee_override.unwrap_or(controller_params.exit_fee_percent);
+    EXIT_FEE_PERCENT.save(deps.storage, &exit_fee_percent)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_exit_fee")
+        .add_attribute("exit_fee_percent", exit_fee_percent.to_string()))
+}
+
+pub fn execute_update_hooks(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    addr: String,
+    add: bool,
+) -> Result<Response, ContractError> {
+    let stream_info = STREAM_INFO.load(deps.storage)?;
+    if stream_info.stream_admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut stream_state = STREAM_STATE.load(deps.storage)?;
+    sync_stream_status(&mut stream_state, env.block.time);
+    if !stream_state.is_waiting() {
+        return Err(ContractError::OperationNotAllowed {
+            current_status: stream_state.status_info.status.to_string(),
+        });
+    }
+
+    let hook = deps.api.addr_validate(&addr)?;
+    let action = if add {
+        add_hook(deps.storage, hook)?;
+        "add_hook"
+    } else {
+        remove_hook(deps.storage, &hook)?;
+        "remove_hook"
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", action)
+        .add_attribute("hook", addr))
+}
+
+fn check_max_in_supply(
+    stream_state: &StreamState,
+    in_amount: Uint256,
+) -> Result<(), ContractError> {
+    // spent_in + in_supply is the total amount subscribed to the stream
+    if let Some(max_in_supply) = stream_state.max_in_supply {
+        let total_in = stream_state
+            .spent_in
+            .checked_add(stream_state.in_supply)?
+            .checked_add(in_amount)?;
+        if total_in > max_in_supply {
+            return Err(ContractError::MaxInSupplyExceeded { cap: max_in_supply });
+        }
+    }
+    Ok(())
+}
+
+fn check_max_in_per_address(
+    stream_state: &StreamState,
+    position_total_in: Uint256,
+) -> Result<(), ContractError> {
+    if let Some(max_in_per_address) = stream_state.max_in_per_address {
+        if position_total_in > max_in_per_address {
+            return Err(ContractError::MaxInPerAddressExceeded {
+                cap: max_in_per_address,
+            });
+        }
+    }
+    Ok(())
+}
+
 pub fn execute_withdraw(
     deps: DepsMut,
     env: Env,
@@ -383,7 +798,7 @@ pub fn execute_withdraw(
     let mut position = POSITIONS.load(deps.storage, &info.sender)?;
 
     sync_stream(&mut stream, env.block.time);
-    sync_position(
+    let (purchased, spent) = sync_position(
         stream.dist_index,
         stream.shares,
         stream.status_info.last_updated,
@@ -415,18 +830,25 @@ pub fn execute_withdraw(
 
     STREAM_STATE.save(deps.storage, &stream)?;
     POSITIONS.save(deps.storage, &position.owner, &position)?;
+    if !has_exit_balance(&position)? {
+        UNEXITED_POSITIONS.update(deps.storage, |count| -> StdResult<_> { Ok(count - 1) })?;
+    }
 
-    let uint128_withdraw_amount = Uint128::try_from(withdraw_amount)?;
-    let fund_transfer_msg: CosmosMsg = CosmosMsg::Bank(BankMsg::Send {
-        to_address: info.sender.to_string(),
-        amount: vec![Coin {
-            denom: stream.in_denom,
-            amount: uint128_withdraw_amount,
-        }],
-    });
+    let fund_transfer_msg =
+        build_u128_transfer_msg(&stream.in_denom, &info.sender, withdraw_amount)?;
+    let hook_msgs = hook_msgs(
+        deps.storage,
+        StreamHookMsg::Withdraw {
+            owner: position.owner.to_string(),
+            withdraw_amount,
+            shares: shares_amount,
+            delta: PositionDelta { purchased, spent },
+        },
+    )?;
     // send funds to withdraw address or to the sender
     let res = Response::new()
         .add_message(fund_transfer_msg)
+        .add_submessages(hook_msgs)
         .add_attribute("action", "withdraw")
         .add_attribute("withdraw_amount", withdraw_amount)
         .add_attribute("shares_amount", shares_amount)
@@ -434,6 +856,147 @@ pub fn execute_withdraw(
 
     Ok(res)
 }
+
+pub fn execute_transfer_position(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let position = transfer_position(deps, &env, &info.sender, &recipient)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "transfer_position")
+        .add_attribute("sender", info.sender)
+        .add_attribute("recipient", recipient)
+        .add_attribute("in_balance", position.in_balance)
+        .add_attribute("purchased", position.purchased))
+}
+
+/// Syncs the position of the owner and moves it to the recipient key, together with
+/// the signed terms of service and the position token. If the recipient already holds a
+/// position, the transferred position is merged into it and its token is burned.
+pub fn transfer_position(
+    deps: DepsMut,
+    env: &Env,
+    owner: &Addr,
+    recipient: &Addr,
+) -> Result<Position, ContractError> {
+    // A position merged into itself would double its balances
+    if owner == recipient {
+        return Err(ContractError::SelfTransfer {});
+    }
+    let mut position = POSITIONS.load(deps.storage, owner)?;
+    // Exited positions have nothing left to transfer, vesting contracts are bound to the owner
+    if position.exit_date != Timestamp::from_seconds(0) {
+        return Err(ContractError::SubscriberAlreadyExited {});
+    }
+    let recipient_position = POSITIONS.may_load(deps.storage, recipient)?;
+    // Exited positions can not receive new funds
+    if recipient_position
+        .as_ref()
+        .is_some_and(|position| position.exit_date != Timestamp::from_seconds(0))
+    {
+        return Err(ContractError::SubscriberAlreadyExited {});
+    }
+
+    let mut stream = STREAM_STATE.load(deps.storage)?;
+    sync_stream_status(&mut stream, env.block.time);
+    if stream.is_cancelled() {
+        return Err(ContractError::OperationNotAllowed {
+            current_status: stream.status_info.status.to_string(),
+        });
+    }
+    sync_stream(&mut stream, env.block.time);
+    STREAM_STATE.save(deps.storage, &stream)?;
+
+    sync_position(
+        stream.dist_index,
+        stream.shares,
+        stream.status_info.last_updated,
+        stream.in_supply,
+        &mut position,
+    )?;
+
+    POSITIONS.remove(deps.storage, owner);
+    let position = match recipient_position {
+        Some(mut recipient_position) => {
+            sync_position(
+                stream.dist_index,
+                stream.shares,
+                stream.status_info.last_updated,
+                stream.in_supply,
+                &mut recipient_position,
+            )?;
+            let counted = u64::from(has_exit_balance(&position)?)
+                + u64::from(has_exit_balance(&recipient_position)?);
+            merge_position(deps.storage, &stream, &mut recipient_position, position)?;
+            let merged = u64::from(has_exit_balance(&recipient_position)?);
+            UNEXITED_POSITIONS.update(deps.storage, |count| -> StdResult<_> {
+                Ok(count + merged - counted)
+            })?;
+            recipient_position
+        }
+        None => {
+            position.owner = recipient.clone();
+            if let Some(token_id) = &position.token_id {
+                nft::move_position_token(deps.storage, token_id, recipient)?;
+            }
+            position
+        }
+    };
+    POSITIONS.save(deps.storage, recipient, &position)?;
+
+    // The recipient keeps its own signed version if it already signed
+    if let Some(tos_version) = TOS_SIGNED.may_load(deps.storage, owner)? {
+        TOS_SIGNED.remove(deps.storage, owner);
+        if !TOS_SIGNED.has(deps.storage, recipient) {
+            TOS_SIGNED.save(deps.storage, recipient, &tos_version)?;
+        }
+    }
+
+    Ok(position)
+}
+
+/// Positions withdrawn before spending anything have nothing to exit, they are not counted as
+/// unexited positions so that they do not hold back the dust sweep.
+pub(crate) fn has_exit_balance(position: &Position) -> StdResult<bool> {
+    Ok(!position.in_balance.checked_add(position.spent)?.is_zero())
+}
+
+/// Adds the balances of a synced position to the synced position of the same stream it is
+/// merged into. The target keeps its owner, referrer and token, the token of the merged
+/// position is burned. The merged position is subject to the per address cap.
+fn merge_position(
+    storage: &mut dyn Storage,
+    stream_state: &StreamState,
+    target: &mut Position,
+    source: Position,
+) -> Result<(), ContractError> {
+    target.in_balance = target.in_balance.checked_add(source.in_balance)?;
+    target.shares = target.shares.checked_add(source.shares)?;
+    target.spent = target.spent.checked_add(source.spent)?;
+    check_max_in_per_address(stream_state, target.in_balance.checked_add(target.spent)?)?;
+    target.claimed = target.claimed.checked_add(source.claimed)?;
+    target.claimed_spent = target.claimed_spent.checked_add(source.claimed_spent)?;
+
+    // Both pending purchases are fractions, the whole part of their sum is purchased
+    let pending_purchase = target
+        .pending_purchase
+        .checked_add(source.pending_purchase)?;
+    target.purchased = target
+        .purchased
+        .checked_add(source.purchased)?
+        .checked_add(pending_purchase * Uint256::one())?;
+    target.pending_purchase = get_decimals(pending_purchase)?;
+
+    if let Some(token_id) = &source.token_id {
+        nft::burn_position_token(storage, token_id)?;
+    }
+    Ok(())
+}
+
 pub fn execute_finalize_stream(
     deps: DepsMut,
     env: Env,
@@ -457,8 +1020,9 @@ pub fn execute_finalize_stream(
     match (
         stream_state.status_info.clone().status,
         stream_state.check_threshold(),
+        stream_state.check_min_average_price(),
     ) {
-        (Status::Ended, true) => {
+        (Status::Ended, true, true) => {
             let mut messages = vec![];
             let mut attributes = vec![];
 
@@ -466,8 +1030,9 @@ pub fn execute_finalize_stream(
             let mut creator_revenue = stream_state.spent_in;
 
             // Stream's swap fee collected at fixed rate from accumulated spent_in of positions(ie stream.spent_in)
+            let exit_fee_percent = query_exit_fee_percent(deps.storage, &controller_params)?;
             let swap_fee = Decimal256::from_ratio(stream_state.spent_in, Uint128::one())
-                .checked_mul(controller_params.exit_fee_percent)?
+                .checked_mul(exit_fee_percent)?
                 * Uint256::one();
 
             // extract swap_fee from last amount
@@ -484,13 +1049,11 @@ pub fn execute_finalize_stream(
                     .out_asset
                     .amount
                     .checked_sub(uint128_remaining_out)?;
-                let remaining_msg = CosmosMsg::Bank(BankMsg::Send {
-                    to_address: treasury.to_string(),
-                    amount: vec![Coin {
-                        denom: stream_state.out_asset.denom.clone(),
-                        amount: uint128_remaining_out,
-                    }],
-                });
+                let remaining_msg = build_u128_transfer_msg(
+                    &stream_state.out_asset.denom,
+                    &treasury,
+                    remaining_out,
+                )?;
                 messages.push(remaining_msg);
             }
 
@@ -528,10 +1091,10 @@ pub fn execute_finalize_stream(
                         .checksum;
                     let (vesting_msgs, vesting_attributes, vesting_addr) = vesting_operations(
                         &deps,
-                        env.contract.address,
+                        env.contract.address.clone(),
                         vesting_checksum,
                         treasury.clone(),
-                        salt,
+                        salt.as_ref().map(creator_vesting_salt),
                         stream_state.status_info.end_time,
                         controller_params.vesting_code_id,
                         creator_revenue_u128,
@@ -545,31 +1108,109 @@ pub fn execute_finalize_stream(
                 }
             }
 
-            if !vesting_flag {
-                let send_msg = CosmosMsg::Bank(BankMsg::Send {
-                    to_address: treasury.to_string(),
-                    amount: vec![Coin {
-                        denom: stream_state.in_denom.clone(),
-                        amount: creator_revenue_u128,
-                    }],
-                });
+            // if treasury recipients are set, the creator revenue is split among them by weight
+            let treasury_recipients = TREASURY_RECIPIENTS.may_load(deps.storage)?;
+            if let Some(treasury_recipients) = treasury_recipients {
+                let vesting_checksum = if treasury_recipients.iter().any(|r| r.vesting.is_some()) {
+                    Some(
+                        deps.querier
+                            .query_wasm_code_info(controller_params.vesting_code_id)?
+                            .checksum,
+                    )
+                } else {
+                    None
+                };
+                let shares = split_by_weight(
+                    creator_revenue,
+                    treasury_recipients.into_iter().map(|r| {
+                        let weight = r.weight;
+                        (r, weight)
+                    }),
+                );
+                for (recipient, share) in shares {
+                    if share.is_zero() {
+                        continue;
+                    }
+                    match (recipient.vesting, vesting_checksum.clone()) {
+                        (Some(vesting_config), Some(vesting_checksum)) => {
+                            let (vesting_msgs, vesting_attributes, vesting_addr) =
+                                vesting_operations(
+                                    &deps,
+                                    env.contract.address.clone(),
+                                    vesting_checksum,
+                                    recipient.address.clone(),
+                                    Some(treasury_vesting_salt(&recipient.address)),
+                                    stream_state.status_info.end_time,
+                                    controller_params.vesting_code_id,
+                                    Uint128::try_from(share)?,
+                                    stream_state.in_denom.clone(),
+                                    vesting_config,
+                                )?;
+                            messages.extend(vesting_msgs);
+                            attributes.extend(vesting_attributes);
+                            TREASURY_VESTING.save(
+                                deps.storage,
+                                &recipient.address,
+                                &vesting_addr,
+                            )?;
+                        }
+                        _ => {
+                            let send_msg = build_u128_transfer_msg(
+                                &stream_state.in_denom,
+                                &recipient.address,
+                                share,
+                            )?;
+                            messages.push(send_msg);
+                        }
+                    }
+                }
+            } else if !vesting_flag {
+                let send_msg =
+                    build_u128_transfer_msg(&stream_state.in_denom, &treasury, creator_revenue)?;
                 messages.push(send_msg);
             }
 
-            let swap_fee_msg = build_u128_bank_send_msg(
-                stream_state.in_denom.clone(),
-                controller_params.fee_collector.to_string(),
-                swap_fee,
-            )?;
-            messages.push(swap_fee_msg);
+            // Referral fees are paid out of the swap fee when referred positions exit, the
+            // share of positions without a referrer is swept to the fee collectors afterwards
+            let referral_fee_reserve = if REFERRALS.is_empty(deps.storage) {
+                Uint256::zero()
+            } else {
+                Decimal256::from_ratio(swap_fee, Uint128::one())
+                    .checked_mul(controller_params.referral_fee_percent)?
+                    * Uint256::one()
+            };
+            let collected_swap_fee = swap_fee.checked_sub(referral_fee_reserve)?;
+            for (fee_collector, share) in controller_params.split_fee(collected_swap_fee) {
+                if share.is_zero() {
+                    continue;
+                }
+                let swap_fee_msg =
+                    build_u128_transfer_msg(&stream_state.in_denom, &fee_collector, share)?;
+                messages.push(swap_fee_msg);
+            }
 
             stream_state.status_info.status = Status::Finalized(FinalizedStatus::ThresholdReached);
             STREAM_STATE.save(deps.storage, &stream_state)?;
+            let hook_msgs = hook_msgs(
+                deps.storage,
+                StreamHookMsg::Finalize {
+                    status: FinalizedStatus::ThresholdReached,
+                    spent_in: stream_state.spent_in,
+                    out_remaining: stream_state.out_remaining,
+                },
+            )?;
+            let callback_msg = controller_callback_msg(
+                deps.storage,
+                StreamCallbackMsg::Finalized {
+                    status: FinalizedStatus::ThresholdReached,
+                    spent_in: stream_state.spent_in,
+                    swap_fee: collected_swap_fee,
+                },
+            )?;
 
             attributes.extend(vec![
                 attr("action", "finalize_stream"),
                 attr("treasury", treasury.to_string()),
-                attr("fee_collector", controller_params.fee_collector.to_string()),
                 attr("creators_revenue", creator_revenue),
                 attr(
                     "refunded_out_remaining",
@@ -582,6 +1223,7 @@ pub fn execute_finalize_stream(
                         .to_string(),
                 ),
                 attr("swap_fee", swap_fee),
+                attr("referral_fee_reserve", referral_fee_reserve),
                 attr(
                     "creation_fee_amount",
                     controller_params.stream_creation_fee.amount.to_string(),
@@ -590,47 +1232,65 @@ pub fn execute_finalize_stream(
 
             Ok(Response::new()
                 .add_messages(messages)
+                .add_submessages(hook_msgs)
+                .add_submessages(callback_msg)
                 .add_attributes(attributes))
         }
-        (Status::Ended, false) => {
-            // if stream is ended and threshold is not reached, return all in tokens to treasury
-            // Refund all out tokens to stream creator(treasury)
-            let mut refund_coins = NativeBalance::default() + stream_state.out_asset.clone();
+        (Status::Ended, threshold_reached, _) => {
+            // if stream is ended and threshold or min average price is not reached, return all
+            // in tokens to subscribers. Refund all out tokens to stream creator(treasury)
+            let (finalized_status, status_attr) = if threshold_reached {
+                (
+                    FinalizedStatus::MinAveragePriceNotReached,
+                    "min_average_price_not_reached",
+                )
+            } else {
+                (
+                    FinalizedStatus::ThresholdNotReached,
+                    "threshold_not_reached",
+                )
+            };
+            let mut refund_assets = vec![stream_state.out_asset.clone()];
 
             // refund pool creation if any
             let post_stream_ops = POST_STREAM.may_load(deps.storage)?;
             if let Some(post_stream_ops) = post_stream_ops {
-                let pool_refund_coins = pool_refund(
+                let pool_refund_assets = pool_refund(
                     &deps,
                     post_stream_ops.pool_config,
                     stream_state.out_asset.denom.clone(),
                 )?;
-                for coin in pool_refund_coins {
-                    refund_coins += coin;
-                }
+                refund_assets.extend(pool_refund_assets);
             }
-            refund_coins.normalize();
-
-            let funds_msgs: Vec<CosmosMsg> = refund_coins
-                .into_vec()
-                .iter()
-                .map(|coin| {
-                    CosmosMsg::Bank(BankMsg::Send {
-                        to_address: stream_info.treasury.to_string(),
-                        amount: vec![coin.clone()],
-                    })
-                })
-                .collect();
-
-            stream_state.status_info.status =
-                Status::Finalized(FinalizedStatus::ThresholdNotReached);
+
+            let funds_msgs = build_refund_msgs(refund_assets, &stream_info.treasury)?;
+
+            stream_state.status_info.status = Status::Finalized(finalized_status.clone());
             STREAM_STATE.save(deps.storage, &stream_state)?;
+            let hook_msgs = hook_msgs(
+                deps.storage,
+                StreamHookMsg::Finalize {
+                    status: finalized_status.clone(),
+                    spent_in: stream_state.spent_in,
+                    out_remaining: stream_state.out_remaining,
+                },
+            )?;
+            let callback_msg = controller_callback_msg(
+                deps.storage,
+                StreamCallbackMsg::Finalized {
+                    status: finalized_status,
+                    spent_in: stream_state.spent_in,
+                    swap_fee: Uint256::zero(),
+                },
+            )?;
 
             Ok(Response::new()
                 .add_attribute("action", "finalize_stream")
-                .add_attribute("status", "threshold_not_reached")
+                .add_attribute("status", status_attr)
                 .add_attribute("treasury", stream_info.treasury.to_string())
-                .add_messages(funds_msgs))
+                .add_messages(funds_msgs)
+                .add_submessages(hook_msgs)
+                .add_submessages(callback_msg))
         }
         _ => Err(ContractError::OperationNotAllowed {
             current_status: stream_state.status_info.status.to_string(),
@@ -659,30 +1319,253 @@ pub fn execute_exit_stream(
     sync_stream_status(&mut stream_state, env.block.time);
     sync_stream(&mut stream_state, env.block.time);
 
-    // Match on stream status and threshold to determine exit behavior
+    exit_position(
+        deps,
+        env,
+        salt,
+        &mut stream_state,
+        &controller_params,
+        &mut position,
+    )
+}
+
+/// Exits a page of positions on behalf of their owners once the stream is over.
+/// Anyone can call this method. Subscriber vesting contracts are created with a salt
+/// derived from the owner address.
+pub fn execute_exit_positions(
+    mut deps: DepsMut,
+    env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    const MAX_LIMIT: u32 = 30;
+    let mut stream_state = STREAM_STATE.load(deps.storage)?;
+    let controller_params = CONTROLLER_PARAMS.load(deps.storage)?;
+
+    sync_stream_status(&mut stream_state, env.block.time);
+    if !(stream_state.is_ended() || stream_state.is_finalized() || stream_state.is_cancelled()) {
+        return Err(ContractError::OperationNotAllowed {
+            current_status: stream_state.status_info.status.to_string(),
+        });
+    }
+    sync_stream(&mut stream_state, env.block.time);
+
+    let start_addr = maybe_addr(deps.api, start_after)?;
+    let start = start_addr.as_ref().map(Bound::exclusive);
+    let limit = limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT) as usize;
+    let positions = POSITIONS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, position)| position))
+        .collect::<StdResult<Vec<Position>>>()?;
+    let last_owner = positions
+        .last()
+        .map(|position| position.owner.to_string())
+        .unwrap_or_default();
+
+    let mut messages = vec![];
+    let mut events = vec![];
+    let mut exited = 0u64;
+    for mut position in positions {
+        // Already exited positions and positions withdrawn before spending have nothing to pay
+        if position.exit_date != Timestamp::from_seconds(0) || !has_exit_balance(&position)? {
+            continue;
+        }
+        // Vesting salts are namespaced by the owner, an empty salt yields the owner's default
+        let res = exit_position(
+            deps.branch(),
+            env.clone(),
+            Some(Binary::default()),
+            &mut stream_state,
+            &controller_params,
+            &mut position,
+        )?;
+        messages.extend(res.messages);
+        // Each exit reports its attributes in an event of the exited owner
+        events.push(
+            Event::new("exit_position")
+                .add_attribute("owner", position.owner.to_string())
+                .add_attributes(res.attributes),
+        );
+        exited += 1;
+    }
+
+    Ok(Response::new()
+        .add_submessages(messages)
+        .add_events(events)
+        .add_attribute("action", "exit_positions")
+        .add_attribute("exited", exited.to_string())
+        .add_attribute("last_owner", last_owner))
+}
+
+/// Sends the leftover balances of the stream to the fee collector once the stream is over
+/// and every position exited, or once the grace period after the end time has passed.
+/// Amounts still owed to the unexited positions and their referrers stay in the stream, dust
+/// is swept once.
+pub fn execute_sweep_dust(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let mut stream_state = STREAM_STATE.load(deps.storage)?;
+    let controller_params = CONTROLLER_PARAMS.load(deps.storage)?;
+
+    sync_stream_status(&mut stream_state, env.block.time);
+    if !(stream_state.is_finalized() || stream_state.is_cancelled()) {
+        return Err(ContractError::OperationNotAllowed {
+            current_status: stream_state.status_info.status.to_string(),
+        });
+    }
+    let unexited_positions = UNEXITED_POSITIONS.load(deps.storage)?;
+    let grace_period_end = stream_state
+        .status_info
+        .end_time
+        .plus_seconds(controller_params.dust_sweep_grace_period);
+    if unexited_positions > 0 && env.block.time < grace_period_end {
+        return Err(ContractError::DustSweepNotAllowed {});
+    }
+    // Unexited legacy positions are counted as they are migrated
+    if POSITIONS_MIGRATION.exists(deps.storage) {
+        return Err(ContractError::PositionsMigrationPending {});
+    }
+    if DUST_SWEPT.may_load(deps.storage)?.unwrap_or_default() {
+        return Err(ContractError::DustAlreadySwept {});
+    }
+    DUST_SWEPT.save(deps.storage, &true)?;
+
+    let (in_owed, out_owed) = if unexited_positions == 0 {
+        (Uint256::zero(), Uint256::zero())
+    } else {
+        exit_obligations(deps.storage, &stream_state, &controller_params)?
+    };
+    let in_dust = to_uint256(
+        stream_state
+            .in_denom
+            .query_balance(&deps.querier, &env.contract.address)?,
+    )
+    .saturating_sub(in_owed);
+    let out_dust = to_uint256(
+        stream_state
+            .out_asset
+            .denom
+            .query_balance(&deps.querier, &env.contract.address)?,
+    )
+    .saturating_sub(out_owed);
+    // Dust is split among the fee collectors like the protocol fees
+    let in_shares = controller_params.split_fee(in_dust);
+    let out_shares = controller_params.split_fee(out_dust);
+    let mut messages = vec![];
+    for ((fee_collector, in_share), (_, out_share)) in in_shares.into_iter().zip(out_shares) {
+        let dust_assets = vec![
+            Asset::new(stream_state.in_denom.clone(), Uint128::try_from(in_share)?),
+            Asset::new(
+                stream_state.out_asset.denom.clone(),
+                Uint128::try_from(out_share)?,
+            ),
+        ];
+        messages.extend(build_refund_msgs(dust_assets, &fee_collector)?);
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "sweep_dust")
+        .add_attribute("in_dust", in_dust)
+        .add_attribute("out_dust", out_dust)
+        .add_attribute("in_supply", stream_state.in_supply)
+        .add_attribute("out_remaining", stream_state.out_remaining)
+        .add_attribute("unexited_positions", unexited_positions.to_string())
+        .add_attribute("in_owed", in_owed)
+        .add_attribute("out_owed", out_owed))
+}
+
+/// Migrates a page of the positions stored by a legacy stream, callable by anyone until every
+/// legacy position is migrated.
+pub fn execute_migrate_positions(
+    deps: DepsMut,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    const MAX_LIMIT: u32 = 30;
+    let limit = limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT) as usize;
+    let (migrated, completed) = migrate_positions(deps.storage, limit)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate_positions")
+        .add_attribute("migrated", migrated.to_string())
+        .add_attribute("completed", completed.to_string()))
+}
+
+/// Returns the `token_in` and `token_out` amounts the stream still owes to the positions that
+/// did not exit, including the referral fees reserved at finalize and not paid yet
+fn exit_obligations(
+    storage: &dyn Storage,
+    stream_state: &StreamState,
+    controller_params: &ControllerParams,
+) -> Result<(Uint256, Uint256), ContractError> {
+    let exit_totals = EXIT_TOTALS.may_load(storage)?.unwrap_or_default();
+    if stream_state.status_info.status == Status::Finalized(FinalizedStatus::ThresholdReached) {
+        let exit_fee_percent = query_exit_fee_percent(storage, controller_params)?;
+        let referral_fee_reserve = if REFERRALS.is_empty(storage) {
+            Uint256::zero()
+        } else {
+            Decimal256::from_ratio(stream_state.spent_in, Uint128::one())
+                .checked_mul(exit_fee_percent)?
+                .checked_mul(controller_params.referral_fee_percent)?
+                * Uint256::one()
+        };
+        let in_owed = stream_state
+            .in_supply
+            .saturating_sub(exit_totals.in_paid)
+            .checked_add(referral_fee_reserve.saturating_sub(exit_totals.referral_fees_paid))?;
+        let out_owed = to_uint256(stream_state.out_asset.amount)
+            .saturating_sub(stream_state.claimed_out)
+            .saturating_sub(exit_totals.out_paid);
+        Ok((in_owed, out_owed))
+    } else {
+        // Refunded positions get back their balance and the spent amount of unclaimed purchases
+        let in_owed = stream_state
+            .in_supply
+            .checked_add(stream_state.spent_in)?
+            .saturating_sub(stream_state.claimed_spent_in)
+            .saturating_sub(exit_totals.in_paid);
+        Ok((in_owed, Uint256::zero()))
+    }
+}
+
+/// Adds the amounts paid by an exit to the exit totals
+fn record_exit_payment(
+    storage: &mut dyn Storage,
+    in_paid: Uint256,
+    out_paid: Uint256,
+    referral_fee_paid: Uint256,
+) -> StdResult<()> {
+    let mut exit_totals = EXIT_TOTALS.may_load(storage)?.unwrap_or_default();
+    exit_totals.in_paid += in_paid;
+    exit_totals.out_paid += out_paid;
+    exit_totals.referral_fees_paid += referral_fee_paid;
+    EXIT_TOTALS.save(storage, &exit_totals)
+}
+
+/// Exits the position based on the stream status, threshold and min average price
+fn exit_position(
+    deps: DepsMut,
+    env: Env,
+    salt: Option<Binary>,
+    stream_state: &mut StreamState,
+    controller_params: &ControllerParams,
+    position: &mut Position,
+) -> Result<Response, ContractError> {
+    // Match on stream status and finalization conditions to determine exit behavior
     match (
         stream_state.status_info.clone().status,
-        stream_state.check_threshold(),
+        stream_state.check_threshold() && stream_state.check_min_average_price(),
     ) {
-        // Normal exit scenario: stream is ended and threshold is reached
+        // Normal exit scenario: stream is ended and threshold and min average price are reached
         (Status::Ended, true) | (Status::Finalized(FinalizedStatus::ThresholdReached), _) => {
-            handle_normal_exit(
-                deps,
-                env,
-                info,
-                salt,
-                &mut stream_state,
-                &controller_params,
-                &mut position,
-            )
+            handle_normal_exit(deps, env, salt, stream_state, controller_params, position)
         }
 
-        // Full refund exit scenario: stream ended with threshold not reached or cancelled
+        // Full refund exit scenario: stream ended with threshold or min average price not reached
+        // or cancelled
         (Status::Ended, false)
         | (Status::Finalized(FinalizedStatus::ThresholdNotReached), _)
-        | (Status::Cancelled, _) => {
-            handle_full_refund_exit(deps, env, info, &mut stream_state, &mut position)
-        }
+        | (Status::Finalized(FinalizedStatus::MinAveragePriceNotReached), _)
+        | (Status::Cancelled, _) => handle_full_refund_exit(deps, env, stream_state, position),
 
         // Error case: operation not allowed
         _ => Err(ContractError::OperationNotAllowed {
@@ -695,13 +1578,12 @@ pub fn execute_exit_stream(
 fn handle_normal_exit(
     deps: DepsMut,
     env: Env,
-    info: MessageInfo,
     salt: Option<Binary>,
     stream_state: &mut StreamState,
     controller_params: &ControllerParams,
     position: &mut Position,
 ) -> Result<Response, ContractError> {
-    sync_position(
+    let (purchased, spent) = sync_position(
         stream_state.dist_index,
         stream_state.shares,
         stream_state.status_info.last_updated,
@@ -714,17 +1596,24 @@ fn handle_normal_exit(
     STREAM_STATE.save(deps.storage, stream_state)?;
     position.exit_date = env.block.time;
     POSITIONS.save(deps.storage, &position.owner, position)?;
+    // Positions withdrawn before spending are not counted
+    if has_exit_balance(position)? {
+        UNEXITED_POSITIONS.update(deps.storage, |count| -> StdResult<_> { Ok(count - 1) })?;
+    }
 
     // Calculate exit fee
+    let exit_fee_percent = query_exit_fee_percent(deps.storage, controller_params)?;
     let swap_fee = Decimal256::from_ratio(position.spent, Uint128::one())
-        .checked_mul(controller_params.exit_fee_percent)?
+        .checked_mul(exit_fee_percent)?
         * Uint256::one();
 
     // Prepare messages and attributes for the response
     let mut messages = vec![];
     let mut attributes = vec![];
 
-    let uint128_purchased = Uint128::try_from(position.purchased)?;
+    // Purchases claimed during the stream are already paid out
+    let unclaimed = position.purchased.checked_sub(position.claimed)?;
+    let uint128_unclaimed = Uint128::try_from(unclaimed)?;
     let mut vesting_flag = false;
 
     if let Some(post_stream_actions) = POST_STREAM.may_load(deps.storage)? {
@@ -738,93 +1627,162 @@ fn handle_normal_exit(
                 &deps,
                 env.contract.address,
                 vesting_checksum,
-                info.sender.clone(),
-                salt,
+                position.owner.clone(),
+                salt.map(|salt| subscriber_vesting_salt(&position.owner, &salt)),
                 stream_state.status_info.end_time,
                 controller_params.vesting_code_id,
-                uint128_purchased,
+                uint128_unclaimed,
                 stream_state.out_asset.denom.clone(),
                 vesting_config,
             )?;
             messages.extend(vesting_msgs);
             attributes.extend(vesting_attributes);
-            SUBSCRIBER_VESTING.save(deps.storage, info.sender.clone(), &vesting_addr)?;
+            SUBSCRIBER_VESTING.save(deps.storage, position.owner.clone(), &vesting_addr)?;
             vesting_flag = true;
         }
     }
 
     if !vesting_flag {
-        let send_msg = CosmosMsg::Bank(BankMsg::Send {
-            to_address: info.sender.to_string(),
-            amount: vec![Coin {
-                denom: stream_state.out_asset.denom.to_string(),
-                amount: uint128_purchased,
-            }],
-        });
+        let send_msg =
+            build_u128_transfer_msg(&stream_state.out_asset.denom, &position.owner, unclaimed)?;
         messages.push(send_msg);
     }
 
     if !position.in_balance.is_zero() {
-        let unspent = position.in_balance;
-        let uint128_unspent = Uint128::try_from(unspent)?;
-        let unspent_msg = CosmosMsg::Bank(BankMsg::Send {
-            to_address: info.sender.to_string(),
-            amount: vec![Coin {
-                denom: stream_state.in_denom.clone(),
-                amount: uint128_unspent,
-            }],
-        });
+        let unspent_msg =
+            build_u128_transfer_msg(&stream_state.in_denom, &position.owner, position.in_balance)?;
         messages.push(unspent_msg);
     }
 
+    // Referrer share is carved out of the exit fee, reserved at finalize
+    let mut referral_fee_paid = Uint256::zero();
+    if let Some(referrer) = &position.referrer {
+        let referral_fee = Decimal256::from_ratio(swap_fee, Uint128::one())
+            .checked_mul(controller_params.referral_fee_percent)?
+            * Uint256::one();
+        if !referral_fee.is_zero() {
+            REFERRALS.update(deps.storage, referrer, |referral| -> StdResult<_> {
+                let mut referral = referral.unwrap_or_default();
+                referral.fees_paid += referral_fee;
+                Ok(referral)
+            })?;
+            let referral_msg =
+                build_u128_transfer_msg(&stream_state.in_denom, referrer, referral_fee)?;
+            messages.push(referral_msg);
+            attributes.push(attr("referrer", referrer.to_string()));
+            attributes.push(attr("referral_fee", referral_fee));
+            referral_fee_paid = referral_fee;
+        }
+    }
+    record_exit_payment(
+        deps.storage,
+        position.in_balance,
+        unclaimed,
+        referral_fee_paid,
+    )?;
+
     attributes.extend(vec![
         attr("action", "exit_stream"),
         attr("status_info", stream_state.status_info.status.to_string()),
         attr("spent", position.spent.checked_sub(swap_fee)?),
         attr("purchased", position.purchased),
+        attr("unclaimed", unclaimed),
         attr("swap_fee_paid", swap_fee),
     ]);
 
+    let hook_msgs = hook_msgs(
+        deps.storage,
+        StreamHookMsg::Exit {
+            owner: position.owner.to_string(),
+            purchased: position.purchased,
+            spent: position.spent,
+            refunded: false,
+            delta: PositionDelta { purchased, spent },
+        },
+    )?;
     Ok(Response::new()
         .add_messages(messages)
+        .add_submessages(hook_msgs)
         .add_attributes(attributes))
 }
 
-/// Handles the full refund exit scenario, where the stream is cancelled or threshold is not reached
+/// Handles the full refund exit scenario, where the stream is cancelled or threshold or min average
+/// price is not reached
 fn handle_full_refund_exit(
     deps: DepsMut,
     env: Env,
-    info: MessageInfo,
     stream_state: &mut StreamState,
     position: &mut Position,
 ) -> Result<Response, ContractError> {
-    // Calculate total balance and update exit date
-    let total_balance = position.in_balance + position.spent;
+    // Calculate total balance and update exit date, spent amount of claimed purchases is not refunded
+    let total_balance = position.in_balance + position.spent - position.claimed_spent;
     position.exit_date = env.block.time;
     position.last_updated = env.block.time;
     POSITIONS.save(deps.storage, &position.owner, position)?;
+    // Positions withdrawn before spending are not counted
+    if has_exit_balance(position)? {
+        UNEXITED_POSITIONS.update(deps.storage, |count| -> StdResult<_> { Ok(count - 1) })?;
+    }
 
-    let send_msg = build_u128_bank_send_msg(
-        stream_state.in_denom.clone(),
-        info.sender.to_string(),
+    record_exit_payment(
+        deps.storage,
         total_balance,
+        Uint256::zero(),
+        Uint256::zero(),
     )?;
+
+    let send_msg = build_u128_transfer_msg(&stream_state.in_denom, &position.owner, total_balance)?;
     let attributes = vec![
         attr("action", "exit_stream"),
         attr("total_balance", total_balance),
         attr("status_info", stream_state.status_info.status.to_string()),
     ];
+    let hook_msgs = hook_msgs(
+        deps.storage,
+        StreamHookMsg::Exit {
+            owner: position.owner.to_string(),
+            purchased: position.purchased,
+            spent: position.spent,
+            refunded: true,
+            delta: PositionDelta::default(),
+        },
+    )?;
 
     // Return the funds to the sender
     Ok(Response::new()
         .add_message(send_msg)
+        .add_submessages(hook_msgs)
         .add_attributes(attributes))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
-    ensure_from_older_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
-    Ok(Response::default())
+pub fn reply(_deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        // Failed hooks are reverted without affecting the stream operation
+        HOOK_REPLY_ID => Ok(Response::new()
+            .add_attribute("action", "hook_failed")
+            .add_attribute("error", msg.result.into_result().err().unwrap_or_default())),
+        // Streams keep working if the controller does not accept the callback
+        CONTROLLER_CALLBACK_REPLY_ID => Ok(Response::new()
+            .add_attribute("action", "controller_callback_failed")
+            .add_attribute("error", msg.result.into_result().err().unwrap_or_default())),
+        id => Err(ContractError::UnknownReplyId { id }),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(mut deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored_version = ensure_from_older_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    let applied = migrate_state(deps.branch(), &env, &stored_version)?;
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", stored_version.to_string())
+        .add_attribute("to_version", CONTRACT_VERSION)
+        .add_attributes(
+            applied
+                .into_iter()
+                .map(|version| attr("applied_migration", version)),
+        ))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -832,9 +1790,24 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Params {} => to_json_binary(&query_params(deps)?),
         QueryMsg::Stream {} => to_json_binary(&query_stream(deps, env)?),
-        QueryMsg::Position { owner } => to_json_binary(&query_position(deps, env, owner)?),
-        QueryMsg::ListPositions { start_after, limit } => {
-            to_json_binary(&list_positions(deps, start_after, limit)?)
+        QueryMsg::Position { owner, at_time } => {
+            to_json_binary(&query_position(deps, env, owner, at_time)?)
+        }
+        QueryMsg::ListPositions {
+            start_after,
+            limit,
+            at_time,
+        } => to_json_binary(&list_positions(deps, env, start_after, limit, at_time)?),
+        QueryMsg::SimulateSubscribe {
+            amount,
+            at_time,
+            subscriber,
+            proof,
+        } => to_json_binary(&query_simulate_subscribe(
+            deps, env, amount, at_time, subscriber, proof,
+        )?),
+        QueryMsg::SimulateWithdraw { owner, cap } => {
+            to_json_binary(&query_simulate_withdraw(deps, env, owner, cap)?)
         }
         QueryMsg::AveragePrice {} => to_json_binary(&query_average_price(deps, env)?),
         QueryMsg::LastStreamedPrice {} => to_json_binary(&query_last_streamed_price(deps, env)?),
@@ -854,6 +1827,21 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
                 .unwrap_or(Addr::unchecked("".to_string()));
             to_json_binary(&creator_vesting)
         }
+        QueryMsg::Hooks {} => to_json_binary(&HooksResponse {
+            hooks: HOOKS
+                .may_load(deps.storage)?
+                .unwrap_or_default()
+                .into_iter()
+                .map(|hook| hook.to_string())
+                .collect(),
+        }),
+        QueryMsg::TreasuryVesting { addr } => {
+            let addr = deps.api.addr_validate(&addr)?;
+            let treasury_vesting = TREASURY_VESTING
+                .load(deps.storage, &addr)
+                .unwrap_or(Addr::unchecked("".to_string()));
+            to_json_binary(&treasury_vesting)
+        }
         QueryMsg::SubscriberVesting { addr } => {
             let addr = deps.api.addr_validate(&addr)?;
             let subscriber_vesting = SUBSCRIBER_VESTING
@@ -861,6 +1849,75 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
                 .unwrap_or(Addr::unchecked("".to_string()));
             to_json_binary(&subscriber_vesting)
         }
+        QueryMsg::AllowlistVerified { addr } => {
+            let addr = deps.api.addr_validate(&addr)?;
+            to_json_binary(&is_allowlist_verified(deps.storage, &addr)?)
+        }
+        QueryMsg::OwnerOf {
+            token_id,
+            include_expired,
+        } => to_json_binary(&nft::query_owner_of(
+            deps,
+            env,
+            token_id,
+            include_expired.unwrap_or(false),
+        )?),
+        QueryMsg::Approval {
+            token_id,
+            spender,
+            include_expired,
+        } => to_json_binary(&nft::query_approval(
+            deps,
+            env,
+            token_id,
+            spender,
+            include_expired.unwrap_or(false),
+        )?),
+        QueryMsg::Approvals {
+            token_id,
+            include_expired,
+        } => to_json_binary(&nft::query_approvals(
+            deps,
+            env,
+            token_id,
+            include_expired.unwrap_or(false),
+        )?),
+        QueryMsg::AllOperators {
+            owner,
+            include_expired,
+            start_after,
+            limit,
+        } => to_json_binary(&nft::query_all_operators(
+            deps,
+            env,
+            owner,
+            include_expired.unwrap_or(false),
+            start_after,
+            limit,
+        )?),
+        QueryMsg::NftInfo { token_id } => to_json_binary(&nft::query_nft_info(deps, token_id)?),
+        QueryMsg::AllNftInfo {
+            token_id,
+            include_expired,
+        } => to_json_binary(&nft::query_all_nft_info(
+            deps,
+            env,
+            token_id,
+            include_expired.unwrap_or(false),
+        )?),
+        QueryMsg::NumTokens {} => to_json_binary(&nft::query_num_tokens(deps)?),
+        QueryMsg::ContractInfo {} => to_json_binary(&nft::query_contract_info(deps)?),
+        QueryMsg::Tokens {
+            owner,
+            start_after,
+            limit,
+        } => to_json_binary(&nft::query_tokens(deps, owner, start_after, limit)?),
+        QueryMsg::AllTokens { start_after, limit } => {
+            to_json_binary(&nft::query_all_tokens(deps, start_after, limit)?)
+        }
+        QueryMsg::ListReferrals { start_after, limit } => {
+            to_json_binary(&list_referrals(deps, start_after, limit)?)
+        }
     }
 }
 pub fn query_params(deps: Deps) -> StdResult<ControllerParams> {
@@ -871,9 +1928,13 @@ pub fn query_params(deps: Deps) -> StdResult<ControllerParams> {
 pub fn query_stream(deps: Deps, _env: Env) -> StdResult<StreamResponse> {
     let stream = STREAM_STATE.load(deps.storage)?;
     let stream_info = STREAM_INFO.load(deps.storage)?;
+    let controller_params = CONTROLLER_PARAMS.load(deps.storage)?;
     let stream = StreamResponse {
         name: stream_info.name,
         treasury: stream_info.treasury.to_string(),
+        treasury_recipients: TREASURY_RECIPIENTS
+            .may_load(deps.storage)?
+            .unwrap_or_default(),
         in_denom: stream.in_denom,
         out_asset: stream.out_asset,
         start_time: stream.status_info.start_time,
@@ -889,57 +1950,272 @@ pub fn query_stream(deps: Deps, _env: Env) -> StdResult<StreamResponse> {
         current_streamed_price: stream.current_streamed_price,
         stream_admin: stream_info.stream_admin.into_string(),
         threshold: stream.threshold,
+        min_average_price: stream.min_average_price,
+        emission_curve: stream.emission_curve,
+        max_in_per_address: stream.max_in_per_address,
+        max_in_supply: stream.max_in_supply,
+        hard_cap: stream.hard_cap,
+        merkle_root: MERKLE_ROOT
+            .may_load(deps.storage)?
+            .map(|merkle_root| merkle_root.to_hex()),
+        exit_fee_percent: query_exit_fee_percent(deps.storage, &controller_params)?,
     };
     Ok(stream)
 }
 
-pub fn query_position(deps: Deps, _env: Env, owner: String) -> StdResult<PositionResponse> {
+pub fn query_position(
+    deps: Deps,
+    env: Env,
+    owner: String,
+    at_time: Option<Timestamp>,
+) -> StdResult<PositionResponse> {
     let owner = deps.api.addr_validate(&owner)?;
     let position = POSITIONS.load(deps.storage, &owner)?;
-    let res = PositionResponse {
-        owner: owner.to_string(),
+    let stream = simulate_stream(deps, &env, at_time)?;
+    let exit_fee_percent =
+        query_exit_fee_percent(deps.storage, &CONTROLLER_PARAMS.load(deps.storage)?)?;
+    simulate_position(&stream, position, exit_fee_percent)
+}
+
+pub fn list_positions(
+    deps: Deps,
+    env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    at_time: Option<Timestamp>,
+) -> StdResult<PositionsResponse> {
+    const MAX_LIMIT: u32 = 30;
+    let start_addr = maybe_addr(deps.api, start_after)?;
+    let start = start_addr.as_ref().map(Bound::exclusive);
+    let limit = limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT) as usize;
+    let stream = simulate_stream(deps, &env, at_time)?;
+    let exit_fee_percent =
+        query_exit_fee_percent(deps.storage, &CONTROLLER_PARAMS.load(deps.storage)?)?;
+    let positions = POSITIONS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (_, position) = item?;
+            simulate_position(&stream, position, exit_fee_percent)
+        })
+        .collect::<StdResult<Vec<PositionResponse>>>()?;
+    Ok(PositionsResponse { positions })
+}
+
+pub fn query_simulate_subscribe(
+    deps: Deps,
+    env: Env,
+    amount: Uint256,
+    at_time: Option<Timestamp>,
+    subscriber: Option<String>,
+    proof: Option<Vec<String>>,
+) -> StdResult<SimulateSubscribeResponse> {
+    let stream = simulate_stream(deps, &env, at_time)?;
+    if !(stream.is_active() || stream.is_bootstrapping()) {
+        return Err(StdError::generic_err(format!(
+            "Subscription not allowed, current status: {}",
+            stream.status_info.status
+        )));
+    }
+
+    // Same caps and allowlist as the subscription, a new position is assumed without subscriber
+    let check_subscription = || -> Result<(), ContractError> {
+        check_max_in_supply(&stream, amount)?;
+        let position_total_in = match maybe_addr(deps.api, subscriber)? {
+            Some(subscriber) => {
+                verify_allowlist(deps.storage, &subscriber, proof.as_deref())?;
+                POSITIONS
+                    .may_load(deps.storage, &subscriber)?
+                    .map(|position| position.in_balance.checked_add(position.spent))
+                    .transpose()?
+                    .unwrap_or_default()
+            }
+            None => Uint256::zero(),
+        };
+        check_max_in_per_address(&stream, position_total_in.checked_add(amount)?)
+    };
+    check_subscription().map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    let shares = compute_shares_amount(&stream, amount, false);
+    let rounding = if stream.shares.is_zero() || amount.is_zero() {
+        ShareRounding::Exact
+    } else {
+        ShareRounding::Down
+    };
+    let projected_out = if stream.current_streamed_price.is_zero() {
+        Uint256::zero()
+    } else {
+        Decimal256::from_ratio(amount, Uint256::one())
+            .checked_div(stream.current_streamed_price)
+            .map_err(|err| StdError::generic_err(err.to_string()))?
+            * Uint256::one()
+    };
+
+    Ok(SimulateSubscribeResponse {
+        shares,
+        rounding,
+        in_supply: stream.in_supply.checked_add(amount)?,
+        total_shares: stream.shares.checked_add(shares)?,
+        projected_out,
+        hard_cap_remaining: stream
+            .hard_cap
+            .map(|hard_cap| hard_cap.saturating_sub(stream.spent_in)),
+    })
+}
+
+pub fn query_simulate_withdraw(
+    deps: Deps,
+    env: Env,
+    owner: String,
+    cap: Option<Uint256>,
+) -> StdResult<SimulateWithdrawResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let mut position = POSITIONS.load(deps.storage, &owner)?;
+    let stream = simulate_stream(deps, &env, None)?;
+    if !(stream.is_active() || stream.is_bootstrapping()) {
+        return Err(StdError::generic_err(format!(
+            "Withdraw not allowed, current status: {}",
+            stream.status_info.status
+        )));
+    }
+    sync_position(
+        stream.dist_index,
+        stream.shares,
+        stream.status_info.last_updated,
+        stream.in_supply,
+        &mut position,
+    )
+    .map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    let withdraw_amount = cap.unwrap_or(position.in_balance);
+    if withdraw_amount.is_zero() || withdraw_amount > position.in_balance {
+        return Err(StdError::generic_err(format!(
+            "Invalid withdraw amount: {}",
+            withdraw_amount
+        )));
+    }
+    // Full withdrawals burn every share of the position, partial ones are rounded up
+    let (shares, rounding) = if withdraw_amount == position.in_balance {
+        (position.shares, ShareRounding::Exact)
+    } else {
+        (
+            compute_shares_amount(&stream, withdraw_amount, true),
+            ShareRounding::Up,
+        )
+    };
+
+    Ok(SimulateWithdrawResponse {
+        withdraw_amount,
+        shares,
+        rounding,
+        in_balance: position.in_balance.checked_sub(withdraw_amount)?,
+        remaining_shares: position.shares.checked_sub(shares)?,
+        purchased: position.purchased,
+        spent: position.spent,
+    })
+}
+
+/// Syncs a copy of the stream at the given time, defaults to the current block time
+fn simulate_stream(deps: Deps, env: &Env, at_time: Option<Timestamp>) -> StdResult<StreamState> {
+    let mut stream = STREAM_STATE.load(deps.storage)?;
+    let now = at_time.unwrap_or(env.block.time);
+    if now < stream.status_info.last_updated {
+        return Err(StdError::generic_err(
+            "Simulation time can not be before the last stream update",
+        ));
+    }
+    sync_stream_status(&mut stream, now);
+    // Cancelled streams are not distributed anymore
+    if !stream.is_cancelled() {
+        sync_stream(&mut stream, now);
+    }
+    Ok(stream)
+}
+
+/// Syncs the position against the simulated stream and estimates its exit payouts as if it
+/// exited at the simulated time
+fn simulate_position(
+    stream: &StreamState,
+    mut position: Position,
+    exit_fee_percent: Decimal256,
+) -> StdResult<PositionResponse> {
+    let exited = position.exit_date != Timestamp::from_seconds(0);
+    if !exited && !stream.is_cancelled() {
+        sync_position(
+            stream.dist_index,
+            stream.shares,
+            stream.status_info.last_updated,
+            stream.in_supply,
+            &mut position,
+        )
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    }
+
+    let refunded = match &stream.status_info.status {
+        Status::Cancelled
+        | Status::Finalized(FinalizedStatus::ThresholdNotReached)
+        | Status::Finalized(FinalizedStatus::MinAveragePriceNotReached) => true,
+        Status::Ended => !(stream.check_threshold() && stream.check_min_average_price()),
+        _ => false,
+    };
+    let (exit_fee, net_out_payout, net_in_payout) = if exited {
+        (Uint256::zero(), Uint256::zero(), Uint256::zero())
+    } else if refunded {
+        // Spent amount of claimed purchases is not refunded
+        let total_balance = position.in_balance + position.spent - position.claimed_spent;
+        (Uint256::zero(), Uint256::zero(), total_balance)
+    } else {
+        let exit_fee = Decimal256::from_ratio(position.spent, Uint128::one())
+            .checked_mul(exit_fee_percent)?
+            * Uint256::one();
+        (
+            exit_fee,
+            position.purchased.checked_sub(position.claimed)?,
+            position.in_balance,
+        )
+    };
+
+    Ok(PositionResponse {
+        owner: position.owner.to_string(),
         in_balance: position.in_balance,
         purchased: position.purchased,
         index: position.index,
         spent: position.spent,
+        claimed: position.claimed,
         shares: position.shares,
         last_updated: position.last_updated,
         pending_purchase: position.pending_purchase,
         exit_date: position.exit_date,
-    };
-    Ok(res)
+        token_id: position.token_id,
+        referrer: position.referrer.map(|referrer| referrer.to_string()),
+        exit_fee,
+        net_out_payout,
+        net_in_payout,
+    })
 }
 
-pub fn list_positions(
+pub fn list_referrals(
     deps: Deps,
     start_after: Option<String>,
     limit: Option<u32>,
-) -> StdResult<PositionsResponse> {
+) -> StdResult<ReferralsResponse> {
     const MAX_LIMIT: u32 = 30;
     let start_addr = maybe_addr(deps.api, start_after)?;
     let start = start_addr.as_ref().map(Bound::exclusive);
     let limit = limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT) as usize;
-    let positions: StdResult<Vec<PositionResponse>> = POSITIONS
+    let referrals = REFERRALS
         .range(deps.storage, start, None, Order::Ascending)
         .take(limit)
         .map(|item| {
-            let (owner, position) = item?;
-            let position = PositionResponse {
-                owner: owner.to_string(),
-                in_balance: position.in_balance,
-                purchased: position.purchased,
-                index: position.index,
-                spent: position.spent,
-                shares: position.shares,
-                last_updated: position.last_updated,
-                pending_purchase: position.pending_purchase,
-                exit_date: position.exit_date,
-            };
-            Ok(position)
+            let (referrer, referral) = item?;
+            Ok(ReferralResponse {
+                referrer: referrer.to_string(),
+                referred_positions: referral.referred_positions,
+                fees_paid: referral.fees_paid,
+            })
         })
-        .collect();
-    let positions = positions?;
-    Ok(PositionsResponse { positions })
+        .collect::<StdResult<Vec<ReferralResponse>>>()?;
+    Ok(ReferralsResponse { referrals })
 }
 
 pub fn query_average_price(deps: Deps, _env: Env) -> StdResult<AveragePriceResponse> {
@@ -113,6 +113,9 @@ pub enum ContractError {
     #[error("Salt not provided for vesting creation")]
     InvalidSalt {},
 
+    #[error("Treasury recipients must be unique with positive weights summing up to 100%")]
+    InvalidTreasuryRecipients {},
+
     #[error("Stream URL too short")]
     StreamUrlTooShort {},
 
@@ -155,6 +158,78 @@ pub enum ContractError {
     #[error("Threshold must be greater than zero")]
     InvalidThreshold {},
 
+    #[error("Min average price must be greater than zero")]
+    InvalidMinAveragePrice {},
+
+    #[error("Hard cap must be greater than zero and not lower than the threshold")]
+    InvalidHardCap {},
+
+    #[error("Subscriber can not refer itself")]
+    InvalidReferrer {},
+
+    #[error("Invalid emission curve")]
+    InvalidEmissionCurve {},
+
+    #[error("Subscription cap must be greater than zero")]
+    InvalidSubscriptionCap {},
+
+    #[error("Subscription exceeds the per address cap of {cap}")]
+    MaxInPerAddressExceeded { cap: Uint256 },
+
+    #[error("Subscription exceeds the stream cap of {cap}")]
+    MaxInSupplyExceeded { cap: Uint256 },
+
+    #[error("Merkle root must be a hex encoded sha256 hash")]
+    InvalidMerkleRoot {},
+
+    #[error("Merkle proof must be a list of hex encoded sha256 hashes")]
+    InvalidMerkleProof {},
+
+    #[error("Address is not allowlisted")]
+    NotAllowlisted {},
+
+    #[error("Position nfts are not enabled for this stream")]
+    PositionNftNotEnabled {},
+
+    #[error("Position nft name and symbol can not be empty")]
+    InvalidPositionNftConfig {},
+
+    #[error("Approval has already expired")]
+    ApprovalExpired {},
+
+    #[error("Recipient already holds a position token")]
+    RecipientHoldsPosition {},
+
+    #[error("Threshold not reached")]
+    ThresholdNotReached {},
+
+    #[error("Purchased tokens are vested, claims are not allowed")]
+    VestedClaimNotAllowed {},
+
+    #[error("Purchased tokens can not be claimed before the min average price is evaluated")]
+    MinAveragePriceClaimNotAllowed {},
+
+    #[error("Dust can not be swept before every position exits or the grace period ends")]
+    DustSweepNotAllowed {},
+
+    #[error("Dust of the stream is already swept")]
+    DustAlreadySwept {},
+
+    #[error("Dust can not be swept before the legacy positions are migrated")]
+    PositionsMigrationPending {},
+
+    #[error("Positions of the stream are already migrated")]
+    PositionsAlreadyMigrated {},
+
+    #[error("Position can not be transferred to its owner")]
+    SelfTransfer {},
+
     #[error("Vesting contract not found")]
     VestingContractNotFound {},
+
+    #[error("Hook is already registered, not registered or the hook limit is reached")]
+    InvalidHook {},
+
+    #[error("Unknown reply id: {id}")]
+    UnknownReplyId { id: u64 },
 }
@@ -1,7 +1,15 @@
+use crate::state::{CONTROLLER, EXIT_FEE_PERCENT};
 use crate::ContractError;
-use cosmwasm_std::{BankMsg, Coin, CosmosMsg, Decimal256, Timestamp, Uint128, Uint256};
+use cosmwasm_std::{
+    Addr, Api, CosmosMsg, Decimal256, Deps, Env, StdResult, Storage, Timestamp, Uint128, Uint256,
+};
+use cw_denom::CheckedDenom;
 use std::str::FromStr;
-use streamswap_types::controller::Params as ControllerParams;
+use streamswap_types::controller::{
+    Params as ControllerParams, QueryMsg as ControllerQueryMsg, Roles, RolesResponse,
+    TreasuryRecipientMsg,
+};
+use streamswap_types::stream::{Asset, EmissionCurve, TreasuryRecipient};
 
 /// Stream validation related constants
 const MIN_NAME_LENGTH: usize = 2;
@@ -13,6 +21,11 @@ const MAX_URL_LENGTH: usize = 128;
 const SAFE_TEXT_CHARS: &str = "<>$!&?#()*+'-./\"";
 const SAFE_URL_CHARS: &str = "-_:/?#@!$&()*+,;=.~[]'%";
 
+/// Emission curve validation related constants
+const MAX_CURVE_BREAKPOINTS: usize = 32;
+const MAX_DECAY_EPOCHS: u32 = 100;
+const MAX_CURVE_EXPONENT: u32 = 10;
+
 // calculate the reward with decimal
 pub fn get_decimals(value: Decimal256) -> Result<Decimal256, ContractError> {
     let stringed: &str = &value.to_string();
@@ -63,6 +76,7 @@ pub fn validate_stream_times(
     bootstrapping_start_time: Timestamp,
     start_time: Timestamp,
     end_time: Timestamp,
+    emission_curve: &EmissionCurve,
     params: &ControllerParams,
 ) -> Result<(), ContractError> {
     if now > bootstrapping_start_time {
@@ -99,21 +113,146 @@ pub fn validate_stream_times(
     if waiting_duration < params.min_waiting_duration {
         return Err(ContractError::StreamWaitingDurationTooShort {});
     }
+
+    validate_emission_curve(emission_curve)?;
+    Ok(())
+}
+
+// Emission curves must be monotonic and release everything by the end time
+pub fn validate_emission_curve(emission_curve: &EmissionCurve) -> Result<(), ContractError> {
+    match emission_curve {
+        EmissionCurve::Linear => {}
+        EmissionCurve::PiecewiseLinear { breakpoints } => {
+            if breakpoints.is_empty() || breakpoints.len() > MAX_CURVE_BREAKPOINTS {
+                return Err(ContractError::InvalidEmissionCurve {});
+            }
+            let mut prev_time = Decimal256::zero();
+            let mut prev_released = Decimal256::zero();
+            for breakpoint in breakpoints {
+                if breakpoint.time <= prev_time
+                    || breakpoint.time >= Decimal256::one()
+                    || breakpoint.released < prev_released
+                    || breakpoint.released > Decimal256::one()
+                {
+                    return Err(ContractError::InvalidEmissionCurve {});
+                }
+                prev_time = breakpoint.time;
+                prev_released = breakpoint.released;
+            }
+        }
+        EmissionCurve::ExponentialDecay { epochs, decay } => {
+            if *epochs == 0
+                || *epochs > MAX_DECAY_EPOCHS
+                || decay.is_zero()
+                || *decay >= Decimal256::one()
+            {
+                return Err(ContractError::InvalidEmissionCurve {});
+            }
+        }
+        EmissionCurve::BackLoaded { exponent } => {
+            if *exponent < 2 || *exponent > MAX_CURVE_EXPONENT {
+                return Err(ContractError::InvalidEmissionCurve {});
+            }
+        }
+    }
     Ok(())
 }
 
-pub fn build_u128_bank_send_msg(
-    denom: String,
-    to_addr: String,
+pub fn validate_treasury_recipients(
+    api: &dyn Api,
+    treasury_recipients: Vec<TreasuryRecipientMsg>,
+) -> Result<Vec<TreasuryRecipient>, ContractError> {
+    let mut validated: Vec<TreasuryRecipient> = Vec::with_capacity(treasury_recipients.len());
+    let mut total_weight = Decimal256::zero();
+    for recipient in treasury_recipients {
+        let address = api.addr_validate(&recipient.address)?;
+        if recipient.weight.is_zero() || validated.iter().any(|r| r.address == address) {
+            return Err(ContractError::InvalidTreasuryRecipients {});
+        }
+        total_weight = total_weight.checked_add(recipient.weight)?;
+        validated.push(TreasuryRecipient {
+            address,
+            weight: recipient.weight,
+            vesting: recipient.vesting,
+        });
+    }
+    if total_weight != Decimal256::one() {
+        return Err(ContractError::InvalidTreasuryRecipients {});
+    }
+    Ok(validated)
+}
+
+pub fn build_u128_transfer_msg(
+    denom: &CheckedDenom,
+    to_addr: &Addr,
     amount: Uint256,
 ) -> Result<CosmosMsg, ContractError> {
     let u128_amount = Uint128::try_from(amount)?;
-    let bank_send = CosmosMsg::Bank(BankMsg::Send {
-        to_address: to_addr,
-        amount: vec![Coin {
-            denom,
-            amount: u128_amount,
-        }],
-    });
-    Ok(bank_send)
+    let transfer_msg = denom.get_transfer_to_message(to_addr, u128_amount)?;
+    Ok(transfer_msg)
+}
+
+/// Returns the exit fee of the stream, resolved with the controller overrides at instantiate.
+/// Streams created before the overrides apply the fee of the collected controller params.
+pub fn query_exit_fee_percent(
+    storage: &dyn Storage,
+    params: &ControllerParams,
+) -> StdResult<Decimal256> {
+    Ok(EXIT_FEE_PERCENT
+        .may_load(storage)?
+        .unwrap_or(params.exit_fee_percent))
+}
+
+/// Resolves the exit fee of a new stream with the override set on the controller, if any
+pub fn resolve_exit_fee_percent(
+    deps: Deps,
+    env: &Env,
+    controller: &Addr,
+    params: &ControllerParams,
+) -> StdResult<Decimal256> {
+    let exit_fee_override: Option<Decimal256> = deps.querier.query_wasm_smart(
+        controller,
+        &ControllerQueryMsg::ExitFeeOverride {
+            stream: env.contract.address.to_string(),
+        },
+    )?;
+    Ok(exit_fee_override.unwrap_or(params.exit_fee_percent))
+}
+
+/// Queries the protocol roles from the controller, so that role changes apply to existing streams.
+/// Streams without a controller fall back to the protocol admin of the controller params.
+pub fn query_protocol_roles(deps: Deps, params: &ControllerParams) -> StdResult<Roles> {
+    let Some(controller) = CONTROLLER.may_load(deps.storage)? else {
+        return Ok(Roles::all(params.protocol_admin.clone()));
+    };
+    let roles: RolesResponse = deps
+        .querier
+        .query_wasm_smart(controller, &ControllerQueryMsg::Roles {})?;
+    Ok(Roles {
+        params_manager: Addr::unchecked(roles.params_manager),
+        pauser: Addr::unchecked(roles.pauser),
+        stream_canceller: Addr::unchecked(roles.stream_canceller),
+        fee_manager: Addr::unchecked(roles.fee_manager),
+    })
+}
+
+// Builds a transfer message per denom, amounts of the same denom are merged and zero amounts are skipped.
+// Messages are ordered by denom.
+pub fn build_refund_msgs(
+    assets: Vec<Asset>,
+    to_addr: &Addr,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    let mut merged: Vec<Asset> = vec![];
+    for asset in assets {
+        match merged.iter_mut().find(|a| a.denom == asset.denom) {
+            Some(existing) => existing.amount = existing.amount.checked_add(asset.amount)?,
+            None => merged.push(asset),
+        }
+    }
+    merged.sort_by_key(|a| a.denom.to_string());
+    let mut msgs = vec![];
+    for asset in merged.into_iter().filter(|a| !a.amount.is_zero()) {
+        msgs.push(asset.denom.get_transfer_to_message(to_addr, asset.amount)?);
+    }
+    Ok(msgs)
 }
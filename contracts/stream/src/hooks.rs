@@ -0,0 +1,80 @@
+use crate::state::{CONTROLLER, HOOKS};
+use crate::ContractError;
+use cosmwasm_std::{to_json_binary, Addr, StdResult, Storage, SubMsg, WasmMsg};
+use streamswap_types::controller::{ExecuteMsg as ControllerExecuteMsg, StreamCallbackMsg};
+use streamswap_types::stream::{StreamHookExecuteMsg, StreamHookMsg};
+
+/// Reply id of the hook messages, failures are caught so hooks can not block the stream
+pub const HOOK_REPLY_ID: u64 = 1;
+
+/// Reply id of the controller callback, failures are caught so the controller can not block the
+/// stream
+pub const CONTROLLER_CALLBACK_REPLY_ID: u64 = 2;
+
+/// Maximum number of hooks registered on a stream
+const MAX_HOOKS: usize = 10;
+
+/// Gas limit of each hook message, so that hooks can not exhaust the gas of the stream operation
+pub const HOOK_GAS_LIMIT: u64 = 300_000;
+
+pub fn add_hook(storage: &mut dyn Storage, hook: Addr) -> Result<(), ContractError> {
+    let mut hooks = HOOKS.may_load(storage)?.unwrap_or_default();
+    if hooks.contains(&hook) || hooks.len() >= MAX_HOOKS {
+        return Err(ContractError::InvalidHook {});
+    }
+    hooks.push(hook);
+    HOOKS.save(storage, &hooks)?;
+    Ok(())
+}
+
+pub fn remove_hook(storage: &mut dyn Storage, hook: &Addr) -> Result<(), ContractError> {
+    let mut hooks = HOOKS.may_load(storage)?.unwrap_or_default();
+    if !hooks.contains(hook) {
+        return Err(ContractError::InvalidHook {});
+    }
+    hooks.retain(|h| h != hook);
+    HOOKS.save(storage, &hooks)?;
+    Ok(())
+}
+
+/// Builds the hook messages of every registered hook, executed with reply on error and a bounded
+/// gas limit
+pub fn hook_msgs(storage: &dyn Storage, msg: StreamHookMsg) -> StdResult<Vec<SubMsg>> {
+    let hooks = HOOKS.may_load(storage)?.unwrap_or_default();
+    if hooks.is_empty() {
+        return Ok(vec![]);
+    }
+    let msg = to_json_binary(&StreamHookExecuteMsg::StreamHook(msg))?;
+    Ok(hooks
+        .into_iter()
+        .map(|hook| {
+            SubMsg::reply_on_error(
+                WasmMsg::Execute {
+                    contract_addr: hook.to_string(),
+                    msg: msg.clone(),
+                    funds: vec![],
+                },
+                HOOK_REPLY_ID,
+            )
+            .with_gas_limit(HOOK_GAS_LIMIT)
+        })
+        .collect())
+}
+
+/// Builds the status callback to the controller that created the stream
+pub fn controller_callback_msg(
+    storage: &dyn Storage,
+    msg: StreamCallbackMsg,
+) -> StdResult<Option<SubMsg>> {
+    let Some(controller) = CONTROLLER.may_load(storage)? else {
+        return Ok(None);
+    };
+    Ok(Some(SubMsg::reply_on_error(
+        WasmMsg::Execute {
+            contract_addr: controller.to_string(),
+            msg: to_json_binary(&ControllerExecuteMsg::StreamCallback { msg })?,
+            funds: vec![],
+        },
+        CONTROLLER_CALLBACK_REPLY_ID,
+    )))
+}
@@ -1,10 +1,14 @@
 extern crate core;
 
 pub use crate::error::ContractError;
+mod allowlist;
 mod circuit_ops;
 pub mod contract;
 mod error;
 mod helpers;
+mod hooks;
+mod migrations;
+mod nft;
 mod pool;
 pub mod state;
 pub mod stream;
@@ -0,0 +1,228 @@
+use crate::contract::has_exit_balance;
+use crate::state::{
+    CONTROLLER, CONTROLLER_PARAMS, POSITIONS, POSITIONS_MIGRATION, STREAM_STATE, UNEXITED_POSITIONS,
+};
+use crate::ContractError;
+use cosmwasm_std::{
+    Addr, Coin, Decimal256, DepsMut, Env, Order, StdError, StdResult, Storage, Timestamp, Uint256,
+};
+use cw_denom::CheckedDenom;
+use cw_storage_plus::{Bound, Item, Map};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use streamswap_types::controller::{LegacyParams, Params};
+use streamswap_types::stream::{Asset, EmissionCurve, Position, Status, StatusInfo, StreamState};
+
+/// State migration of streams stored with a version older than `version`
+struct Migration {
+    version: &'static str,
+    migrate: fn(DepsMut, &Env) -> Result<(), ContractError>,
+}
+
+/// Migrations in ascending version order
+const MIGRATIONS: &[Migration] = &[Migration {
+    version: "0.2.0-beta4",
+    migrate: migrate_from_beta3,
+}];
+
+/// Applies the migrations newer than the stored version, returns the applied versions
+pub fn migrate_state(
+    mut deps: DepsMut,
+    env: &Env,
+    stored_version: &Version,
+) -> Result<Vec<&'static str>, ContractError> {
+    let mut applied = vec![];
+    for migration in MIGRATIONS {
+        let version: Version = migration
+            .version
+            .parse()
+            .map_err(|err: semver::Error| StdError::generic_err(err.to_string()))?;
+        if *stored_version < version {
+            (migration.migrate)(deps.branch(), env)?;
+            applied.push(migration.version);
+        }
+    }
+    Ok(applied)
+}
+
+/// Rewrites the state stored by the 0.2.0-beta3 streams in the current format, positions are
+/// migrated in pages by `MigratePositions`
+fn migrate_from_beta3(mut deps: DepsMut, env: &Env) -> Result<(), ContractError> {
+    store_controller(deps.branch(), env)?;
+    migrate_controller_params(deps.storage)?;
+    migrate_stream_state(deps.storage)?;
+    UNEXITED_POSITIONS.save(deps.storage, &0)?;
+    POSITIONS_MIGRATION.save(deps.storage, &None)?;
+    Ok(())
+}
+
+/// Streams created before the controller address was stored are instantiated by the controller
+fn store_controller(deps: DepsMut, env: &Env) -> Result<(), ContractError> {
+    if CONTROLLER.may_load(deps.storage)?.is_none() {
+        let contract_info = deps
+            .querier
+            .query_wasm_contract_info(env.contract.address.clone())?;
+        let controller = deps.api.addr_validate(&contract_info.creator)?;
+        CONTROLLER.save(deps.storage, &controller)?;
+    }
+    Ok(())
+}
+
+/// Values stored either in the current or in the 0.2.0-beta3 format
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum Stored<T, L> {
+    Current(T),
+    Legacy(L),
+}
+
+/// Controller params copied by the streams created before the weighted fee collectors
+fn migrate_controller_params(storage: &mut dyn Storage) -> Result<(), ContractError> {
+    let stored: Item<Stored<Params, LegacyParams>> = Item::new("params");
+    if let Stored::Legacy(legacy) = stored.load(storage)? {
+        CONTROLLER_PARAMS.save(storage, &legacy.into_params())?;
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize)]
+struct LegacyStatusInfo {
+    status: Status,
+    bootstrapping_start_time: Timestamp,
+    start_time: Timestamp,
+    end_time: Timestamp,
+    last_updated: Timestamp,
+}
+
+/// Stream state of the streams created before the cw20 support, denoms are native
+#[derive(Serialize, Deserialize)]
+struct LegacyStreamState {
+    dist_index: Decimal256,
+    out_remaining: Uint256,
+    in_denom: String,
+    in_supply: Uint256,
+    spent_in: Uint256,
+    shares: Uint256,
+    current_streamed_price: Decimal256,
+    out_asset: Coin,
+    status_info: LegacyStatusInfo,
+    threshold: Option<Uint256>,
+}
+
+/// Legacy streams are linear, uncapped and have nothing claimed or paused
+fn migrate_stream_state(storage: &mut dyn Storage) -> Result<(), ContractError> {
+    let stored: Item<Stored<StreamState, LegacyStreamState>> = Item::new("ss");
+    let legacy = match stored.load(storage)? {
+        Stored::Current(_) => return Ok(()),
+        Stored::Legacy(legacy) => legacy,
+    };
+    let stream_state = StreamState {
+        dist_index: legacy.dist_index,
+        out_remaining: legacy.out_remaining,
+        in_denom: CheckedDenom::Native(legacy.in_denom),
+        in_supply: legacy.in_supply,
+        spent_in: legacy.spent_in,
+        shares: legacy.shares,
+        current_streamed_price: legacy.current_streamed_price,
+        out_asset: Asset::new(
+            CheckedDenom::Native(legacy.out_asset.denom),
+            legacy.out_asset.amount,
+        ),
+        status_info: StatusInfo {
+            status: legacy.status_info.status,
+            bootstrapping_start_time: legacy.status_info.bootstrapping_start_time,
+            start_time: legacy.status_info.start_time,
+            end_time: legacy.status_info.end_time,
+            last_updated: legacy.status_info.last_updated,
+            paused_at: None,
+            paused_nanos: 0,
+        },
+        threshold: legacy.threshold,
+        min_average_price: None,
+        emission_curve: EmissionCurve::Linear,
+        max_in_per_address: None,
+        max_in_supply: None,
+        hard_cap: None,
+        claimed_out: Uint256::zero(),
+        claimed_spent_in: Uint256::zero(),
+    };
+    STREAM_STATE.save(storage, &stream_state)?;
+    Ok(())
+}
+
+/// Position of the streams created before the claims, referrals and position tokens
+#[derive(Serialize, Deserialize)]
+struct LegacyPosition {
+    owner: Addr,
+    in_balance: Uint256,
+    shares: Uint256,
+    index: Decimal256,
+    last_updated: Timestamp,
+    purchased: Uint256,
+    pending_purchase: Decimal256,
+    spent: Uint256,
+    exit_date: Timestamp,
+}
+
+/// Rewrites a page of legacy positions after the last migrated owner and counts the positions
+/// that did not exit yet. Positions stored in the current format after the stream migration are
+/// counted when they are stored. Returns the number of migrated positions and whether the
+/// migration is completed.
+pub fn migrate_positions(
+    storage: &mut dyn Storage,
+    limit: usize,
+) -> Result<(u64, bool), ContractError> {
+    let last_owner = POSITIONS_MIGRATION
+        .may_load(storage)?
+        .ok_or(ContractError::PositionsAlreadyMigrated {})?;
+    let stored: Map<&Addr, Stored<Position, LegacyPosition>> = Map::new("positions");
+    let positions = stored
+        .range(
+            storage,
+            last_owner.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    let completed = positions.len() < limit;
+    let last_owner = positions.last().map(|(owner, _)| owner.clone());
+
+    let mut migrated = 0u64;
+    let mut unexited_positions = 0u64;
+    for (owner, position) in positions {
+        let Stored::Legacy(legacy) = position else {
+            continue;
+        };
+        let position = Position {
+            owner: legacy.owner,
+            in_balance: legacy.in_balance,
+            shares: legacy.shares,
+            index: legacy.index,
+            last_updated: legacy.last_updated,
+            purchased: legacy.purchased,
+            pending_purchase: legacy.pending_purchase,
+            spent: legacy.spent,
+            claimed: Uint256::zero(),
+            claimed_spent: Uint256::zero(),
+            exit_date: legacy.exit_date,
+            token_id: None,
+            referrer: None,
+        };
+        POSITIONS.save(storage, &owner, &position)?;
+        migrated += 1;
+        if position.exit_date == Timestamp::from_seconds(0) && has_exit_balance(&position)? {
+            unexited_positions += 1;
+        }
+    }
+    let unexited_positions =
+        UNEXITED_POSITIONS.may_load(storage)?.unwrap_or_default() + unexited_positions;
+    UNEXITED_POSITIONS.save(storage, &unexited_positions)?;
+
+    if completed {
+        POSITIONS_MIGRATION.remove(storage);
+    } else {
+        POSITIONS_MIGRATION.save(storage, &last_owner)?;
+    }
+    Ok((migrated, completed))
+}
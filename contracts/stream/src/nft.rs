@@ -0,0 +1,427 @@
+use crate::contract::transfer_position;
+use crate::state::{
+    POSITIONS, POSITION_NFT, POSITION_TOKENS, POSITION_TOKENS_BURNED, POSITION_TOKEN_APPROVALS,
+    POSITION_TOKEN_COUNT, POSITION_TOKEN_OPERATORS,
+};
+use crate::ContractError;
+use cosmwasm_std::{
+    Addr, Binary, BlockInfo, Deps, DepsMut, Empty, Env, MessageInfo, Order, Response, StdError,
+    StdResult, Storage,
+};
+use cw_storage_plus::Bound;
+use cw_utils::Expiration;
+use streamswap_types::stream::{
+    AllNftInfoResponse, Approval, ApprovalResponse, ApprovalsResponse, ContractInfoResponse,
+    Cw721ReceiveMsg, NftInfoResponse, NumTokensResponse, OperatorsResponse, OwnerOfResponse,
+    TokensResponse,
+};
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+/// Mints a position token for the owner if the stream has position nfts enabled.
+/// Returns the token id of the minted token.
+pub fn mint_position_token(
+    storage: &mut dyn Storage,
+    owner: &Addr,
+) -> Result<Option<String>, ContractError> {
+    if !POSITION_NFT.exists(storage) {
+        return Ok(None);
+    }
+    let token_count = POSITION_TOKEN_COUNT.may_load(storage)?.unwrap_or_default() + 1;
+    let token_id = token_count.to_string();
+    POSITION_TOKEN_COUNT.save(storage, &token_count)?;
+    POSITION_TOKENS.save(storage, &token_id, owner)?;
+    Ok(Some(token_id))
+}
+
+/// Moves the token to the new owner of the position and clears its approvals.
+pub fn move_position_token(
+    storage: &mut dyn Storage,
+    token_id: &str,
+    recipient: &Addr,
+) -> Result<(), ContractError> {
+    POSITION_TOKENS.save(storage, token_id, recipient)?;
+    clear_approvals(storage, token_id)
+}
+
+/// Burns the token of a position merged into another position.
+pub fn burn_position_token(storage: &mut dyn Storage, token_id: &str) -> Result<(), ContractError> {
+    POSITION_TOKENS.remove(storage, token_id);
+    let burned = POSITION_TOKENS_BURNED
+        .may_load(storage)?
+        .unwrap_or_default()
+        + 1;
+    POSITION_TOKENS_BURNED.save(storage, &burned)?;
+    clear_approvals(storage, token_id)
+}
+
+fn clear_approvals(storage: &mut dyn Storage, token_id: &str) -> Result<(), ContractError> {
+    let spenders = POSITION_TOKEN_APPROVALS
+        .prefix(token_id)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<Addr>>>()?;
+    for spender in spenders {
+        POSITION_TOKEN_APPROVALS.remove(storage, (token_id, &spender));
+    }
+    Ok(())
+}
+
+/// Returns the owner of the token if the sender is the owner or one of its operators.
+fn check_can_approve(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    token_id: &str,
+    sender: &Addr,
+) -> Result<Addr, ContractError> {
+    if !POSITION_NFT.exists(storage) {
+        return Err(ContractError::PositionNftNotEnabled {});
+    }
+    let owner = POSITION_TOKENS.load(storage, token_id)?;
+    if owner == sender {
+        return Ok(owner);
+    }
+    match POSITION_TOKEN_OPERATORS.may_load(storage, (&owner, sender))? {
+        Some(expires) if !expires.is_expired(block) => Ok(owner),
+        _ => Err(ContractError::Unauthorized {}),
+    }
+}
+
+/// Returns the owner of the token if the sender is the owner, one of its operators or holds
+/// a valid approval.
+fn check_can_transfer(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    token_id: &str,
+    sender: &Addr,
+) -> Result<Addr, ContractError> {
+    match check_can_approve(storage, block, token_id, sender) {
+        Err(ContractError::Unauthorized {}) => {}
+        res => return res,
+    }
+    match POSITION_TOKEN_APPROVALS.may_load(storage, (token_id, sender))? {
+        Some(expires) if !expires.is_expired(block) => {
+            POSITION_TOKENS.load(storage, token_id).map_err(Into::into)
+        }
+        _ => Err(ContractError::Unauthorized {}),
+    }
+}
+
+/// Positions are keyed by owner, a token can only be transferred to an address without a
+/// position so that every token keeps its own position.
+fn check_recipient(storage: &dyn Storage, recipient: &Addr) -> Result<(), ContractError> {
+    if POSITIONS.has(storage, recipient) {
+        return Err(ContractError::RecipientHoldsPosition {});
+    }
+    Ok(())
+}
+
+pub fn execute_transfer_nft(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let owner = check_can_transfer(deps.storage, &env.block, &token_id, &info.sender)?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+    check_recipient(deps.storage, &recipient)?;
+    transfer_position(deps, &env, &owner, &recipient)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "transfer_nft")
+        .add_attribute("sender", info.sender)
+        .add_attribute("recipient", recipient)
+        .add_attribute("token_id", token_id))
+}
+
+pub fn execute_send_nft(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: String,
+    token_id: String,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    let owner = check_can_transfer(deps.storage, &env.block, &token_id, &info.sender)?;
+    let contract = deps.api.addr_validate(&contract)?;
+    check_recipient(deps.storage, &contract)?;
+    transfer_position(deps, &env, &owner, &contract)?;
+
+    let receive_msg = Cw721ReceiveMsg {
+        sender: info.sender.to_string(),
+        token_id: token_id.clone(),
+        msg,
+    }
+    .into_cosmos_msg(contract.to_string())?;
+
+    Ok(Response::new()
+        .add_message(receive_msg)
+        .add_attribute("action", "send_nft")
+        .add_attribute("sender", info.sender)
+        .add_attribute("recipient", contract)
+        .add_attribute("token_id", token_id))
+}
+
+pub fn execute_approve(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: String,
+    token_id: String,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    check_can_approve(deps.storage, &env.block, &token_id, &info.sender)?;
+    let spender = deps.api.addr_validate(&spender)?;
+    let expires = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::ApprovalExpired {});
+    }
+    POSITION_TOKEN_APPROVALS.save(deps.storage, (&token_id, &spender), &expires)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "approve")
+        .add_attribute("sender", info.sender)
+        .add_attribute("spender", spender)
+        .add_attribute("token_id", token_id))
+}
+
+pub fn execute_revoke(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    check_can_approve(deps.storage, &env.block, &token_id, &info.sender)?;
+    let spender = deps.api.addr_validate(&spender)?;
+    POSITION_TOKEN_APPROVALS.remove(deps.storage, (&token_id, &spender));
+
+    Ok(Response::new()
+        .add_attribute("action", "revoke")
+        .add_attribute("sender", info.sender)
+        .add_attribute("spender", spender)
+        .add_attribute("token_id", token_id))
+}
+
+pub fn execute_approve_all(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operator: String,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    if !POSITION_NFT.exists(deps.storage) {
+        return Err(ContractError::PositionNftNotEnabled {});
+    }
+    let operator = deps.api.addr_validate(&operator)?;
+    let expires = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::ApprovalExpired {});
+    }
+    POSITION_TOKEN_OPERATORS.save(deps.storage, (&info.sender, &operator), &expires)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "approve_all")
+        .add_attribute("sender", info.sender)
+        .add_attribute("operator", operator))
+}
+
+pub fn execute_revoke_all(
+    deps: DepsMut,
+    info: MessageInfo,
+    operator: String,
+) -> Result<Response, ContractError> {
+    if !POSITION_NFT.exists(deps.storage) {
+        return Err(ContractError::PositionNftNotEnabled {});
+    }
+    let operator = deps.api.addr_validate(&operator)?;
+    POSITION_TOKEN_OPERATORS.remove(deps.storage, (&info.sender, &operator));
+
+    Ok(Response::new()
+        .add_attribute("action", "revoke_all")
+        .add_attribute("sender", info.sender)
+        .add_attribute("operator", operator))
+}
+
+fn token_approvals(
+    deps: Deps,
+    block: &BlockInfo,
+    token_id: &str,
+    include_expired: bool,
+) -> StdResult<Vec<Approval>> {
+    POSITION_TOKEN_APPROVALS
+        .prefix(token_id)
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter(|item| {
+            include_expired
+                || item
+                    .as_ref()
+                    .map_or(true, |(_, expires)| !expires.is_expired(block))
+        })
+        .map(|item| {
+            let (spender, expires) = item?;
+            Ok(Approval {
+                spender: spender.to_string(),
+                expires,
+            })
+        })
+        .collect()
+}
+
+pub fn query_owner_of(
+    deps: Deps,
+    env: Env,
+    token_id: String,
+    include_expired: bool,
+) -> StdResult<OwnerOfResponse> {
+    let owner = POSITION_TOKENS.load(deps.storage, &token_id)?;
+    let approvals = token_approvals(deps, &env.block, &token_id, include_expired)?;
+    Ok(OwnerOfResponse {
+        owner: owner.to_string(),
+        approvals,
+    })
+}
+
+pub fn query_approval(
+    deps: Deps,
+    env: Env,
+    token_id: String,
+    spender: String,
+    include_expired: bool,
+) -> StdResult<ApprovalResponse> {
+    POSITION_TOKENS.load(deps.storage, &token_id)?;
+    let spender = deps.api.addr_validate(&spender)?;
+    match POSITION_TOKEN_APPROVALS.may_load(deps.storage, (&token_id, &spender))? {
+        Some(expires) if include_expired || !expires.is_expired(&env.block) => {
+            Ok(ApprovalResponse {
+                approval: Approval {
+                    spender: spender.to_string(),
+                    expires,
+                },
+            })
+        }
+        _ => Err(StdError::not_found("Approval")),
+    }
+}
+
+pub fn query_approvals(
+    deps: Deps,
+    env: Env,
+    token_id: String,
+    include_expired: bool,
+) -> StdResult<ApprovalsResponse> {
+    POSITION_TOKENS.load(deps.storage, &token_id)?;
+    let approvals = token_approvals(deps, &env.block, &token_id, include_expired)?;
+    Ok(ApprovalsResponse { approvals })
+}
+
+pub fn query_all_operators(
+    deps: Deps,
+    env: Env,
+    owner: String,
+    include_expired: bool,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<OperatorsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let owner = deps.api.addr_validate(&owner)?;
+    let start_after = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let operators = POSITION_TOKEN_OPERATORS
+        .prefix(&owner)
+        .range(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .filter(|item| {
+            include_expired
+                || item
+                    .as_ref()
+                    .map_or(true, |(_, expires)| !expires.is_expired(&env.block))
+        })
+        .take(limit)
+        .map(|item| {
+            let (operator, expires) = item?;
+            Ok(Approval {
+                spender: operator.to_string(),
+                expires,
+            })
+        })
+        .collect::<StdResult<Vec<Approval>>>()?;
+    Ok(OperatorsResponse { operators })
+}
+
+pub fn query_all_nft_info(
+    deps: Deps,
+    env: Env,
+    token_id: String,
+    include_expired: bool,
+) -> StdResult<AllNftInfoResponse> {
+    let info = query_nft_info(deps, token_id.clone())?;
+    let access = query_owner_of(deps, env, token_id, include_expired)?;
+    Ok(AllNftInfoResponse { access, info })
+}
+
+pub fn query_nft_info(deps: Deps, token_id: String) -> StdResult<NftInfoResponse> {
+    POSITION_TOKENS.load(deps.storage, &token_id)?;
+    Ok(NftInfoResponse {
+        token_uri: None,
+        extension: Empty {},
+    })
+}
+
+pub fn query_num_tokens(deps: Deps) -> StdResult<NumTokensResponse> {
+    let minted = POSITION_TOKEN_COUNT
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    let burned = POSITION_TOKENS_BURNED
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    let count = minted - burned;
+    Ok(NumTokensResponse { count })
+}
+
+pub fn query_contract_info(deps: Deps) -> StdResult<ContractInfoResponse> {
+    let config = POSITION_NFT.load(deps.storage)?;
+    Ok(ContractInfoResponse {
+        name: config.name,
+        symbol: config.symbol,
+    })
+}
+
+/// Positions are keyed by owner, so an owner holds at most one position token.
+pub fn query_tokens(
+    deps: Deps,
+    owner: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TokensResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let owner = deps.api.addr_validate(&owner)?;
+    let tokens = POSITIONS
+        .may_load(deps.storage, &owner)?
+        .and_then(|position| position.token_id)
+        .filter(|token_id| start_after.iter().all(|start| token_id > start))
+        .into_iter()
+        .take(limit)
+        .collect();
+    Ok(TokensResponse { tokens })
+}
+
+pub fn query_all_tokens(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TokensResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+    let tokens = POSITION_TOKENS
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<String>>>()?;
+    Ok(TokensResponse { tokens })
+}
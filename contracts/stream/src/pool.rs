@@ -2,22 +2,29 @@ use std::str::FromStr;
 
 use crate::ContractError;
 use cosmwasm_std::{attr, Addr, Attribute, Coin, CosmosMsg, Decimal256, DepsMut, Uint128, Uint256};
+use cw_denom::CheckedDenom;
 use osmosis_std::types::osmosis::concentratedliquidity::poolmodel::concentrated::v1beta1::MsgCreateConcentratedPool;
 use osmosis_std::types::osmosis::concentratedliquidity::v1beta1::MsgCreatePosition;
 use osmosis_std::types::osmosis::poolmanager::v1beta1::PoolmanagerQuerier;
 use streamswap_types::controller::{CreatePool, PoolConfig};
+use streamswap_types::stream::Asset;
 use streamswap_utils::to_uint256;
 
 pub fn pool_operations(
     deps: &DepsMut,
     create_pool: CreatePool,
     stream_addr: Addr,
-    in_denom: String,
-    out_denom: String,
+    in_denom: CheckedDenom,
+    out_denom: CheckedDenom,
     out_amount: Uint128,
     mut creator_revenue: Uint256,
     pool_config: PoolConfig,
 ) -> Result<(Vec<CosmosMsg>, Vec<Attribute>, Uint256), ContractError> {
+    // Pools can only be created with native denoms
+    let (in_denom, out_denom) = match (in_denom, out_denom) {
+        (CheckedDenom::Native(in_denom), CheckedDenom::Native(out_denom)) => (in_denom, out_denom),
+        _ => return Err(ContractError::InvalidPoolConfig {}),
+    };
     let PoolConfig::ConcentratedLiquidity { out_amount_clp } = pool_config;
     let CreatePool::ConcentratedLiquidity {
         lower_tick,
@@ -148,6 +155,30 @@ pub fn get_pool_creation_fee(deps: &DepsMut) -> Result<Vec<Coin>, ContractError>
 
     Ok(cosmwasm_std_coin_vec)
 }
+
+pub fn pool_refund(
+    deps: &DepsMut,
+    pool_config: Option<PoolConfig>,
+    out_denom: CheckedDenom,
+) -> Result<Vec<Asset>, ContractError> {
+    if let Some(pool_config) = pool_config {
+        match pool_config {
+            PoolConfig::ConcentratedLiquidity { out_amount_clp } => {
+                let refund = vec![Asset::new(out_denom, Uint128::try_from(out_amount_clp)?)];
+                let mut pool_refund_vec: Vec<Asset> = get_pool_creation_fee(deps)?
+                    .into_iter()
+                    .map(Asset::from)
+                    .collect();
+                pool_refund_vec.extend(refund);
+
+                Ok(pool_refund_vec)
+            }
+        }
+    } else {
+        Ok(vec![])
+    }
+}
+
 #[cfg(test)]
 mod pool_test {
     use super::*;
@@ -207,26 +238,3 @@ mod pool_test {
         assert_eq!(result, expected);
     }
 }
-
-pub fn pool_refund(
-    deps: &DepsMut,
-    pool_config: Option<PoolConfig>,
-    out_denom: String,
-) -> Result<Vec<Coin>, ContractError> {
-    if let Some(pool_config) = pool_config {
-        match pool_config {
-            PoolConfig::ConcentratedLiquidity { out_amount_clp } => {
-                let refund = vec![Coin {
-                    denom: out_denom,
-                    amount: Uint128::try_from(out_amount_clp)?,
-                }];
-                let mut pool_creation_fee_vec = get_pool_creation_fee(deps)?;
-                pool_creation_fee_vec.extend(refund);
-
-                Ok(pool_creation_fee_vec)
-            }
-        }
-    } else {
-        Ok(vec![])
-    }
-}
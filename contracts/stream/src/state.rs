@@ -1,9 +1,17 @@
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Decimal256, HexBinary};
 use cw_storage_plus::{Item, Map};
+use cw_utils::Expiration;
 use streamswap_types::controller::Params;
-use streamswap_types::stream::{Position, PostStreamActions, StreamInfo, StreamState};
+use streamswap_types::stream::{
+    ExitTotals, Position, PositionNftConfig, PostStreamActions, Referral, StreamInfo, StreamState,
+    TreasuryRecipient,
+};
 
 pub const CONTROLLER_PARAMS: Item<Params> = Item::new("params");
+// Controller that instantiated the stream, the only sender of exit fee overrides
+pub const CONTROLLER: Item<Addr> = Item::new("controller");
+// Exit fee resolved with the controller overrides at instantiate, fixed once the stream starts
+pub const EXIT_FEE_PERCENT: Item<Decimal256> = Item::new("exit_fee_percent");
 
 // Stream State Related data
 pub const STREAM_STATE: Item<StreamState> = Item::new("ss");
@@ -20,10 +28,51 @@ pub const SUBSCRIBER_VESTING: Map<Addr, Addr> = Map::new("sub_vest");
 // Creator Vesting adrress
 pub const CREATOR_VESTING: Item<Addr> = Item::new("cr_vest");
 
+// Weighted recipients of the creator revenue, if not set the revenue goes to the treasury
+pub const TREASURY_RECIPIENTS: Item<Vec<TreasuryRecipient>> = Item::new("treasury_recipients");
+
+// Treasury recipient vesting (recipient_addr) -> (contract_addr)
+pub const TREASURY_VESTING: Map<&Addr, Addr> = Map::new("tr_vest");
+
 // Position (stream_id, owner_addr) -> Position
 pub const POSITIONS: Map<&Addr, Position> = Map::new("positions");
 
+// Number of positions that did not exit the stream yet
+pub const UNEXITED_POSITIONS: Item<u64> = Item::new("unexited_positions");
+// Last owner of the migrated legacy positions, set until every legacy position is migrated
+pub const POSITIONS_MIGRATION: Item<Option<Addr>> = Item::new("positions_migration");
+// Amounts paid by the exits of the positions
+pub const EXIT_TOTALS: Item<ExitTotals> = Item::new("exit_totals");
+// Set once the dust of the stream is swept
+pub const DUST_SWEPT: Item<bool> = Item::new("dust_swept");
+
+// Contracts notified of the stream activity
+pub const HOOKS: Item<Vec<Addr>> = Item::new("hooks");
+
+// Referral totals per referrer
+pub const REFERRALS: Map<&Addr, Referral> = Map::new("referrals");
+
 /// Terms and services ipfs link
 pub const TOS: Item<String> = Item::new("tos");
 /// Both for creator and subscriber
 pub const TOS_SIGNED: Map<&Addr, String> = Map::new("tos_signed");
+
+/// Merkle root of the subscription allowlist
+pub const MERKLE_ROOT: Item<HexBinary> = Item::new("merkle_root");
+/// Addresses that proved their allowlist membership, with the merkle root they were verified against
+pub const ALLOWLIST_VERIFIED: Map<&Addr, HexBinary> = Map::new("allowlist_verified");
+
+/// Cw721 representation of the positions, positions are not tokenized if not set
+pub const POSITION_NFT: Item<PositionNftConfig> = Item::new("position_nft");
+/// Number of minted position tokens, also used as the next token id
+pub const POSITION_TOKEN_COUNT: Item<u64> = Item::new("position_token_count");
+/// Number of position tokens burned by merging positions
+pub const POSITION_TOKENS_BURNED: Item<u64> = Item::new("position_tokens_burned");
+/// Position token (token_id) -> owner_addr
+pub const POSITION_TOKENS: Map<&str, Addr> = Map::new("position_tokens");
+/// Position token approvals (token_id, spender_addr) -> expiration
+pub const POSITION_TOKEN_APPROVALS: Map<(&str, &Addr), Expiration> =
+    Map::new("position_token_approvals");
+/// Operators allowed to transfer every position token of the owner (owner_addr, operator_addr) -> expiration
+pub const POSITION_TOKEN_OPERATORS: Map<(&Addr, &Addr), Expiration> =
+    Map::new("position_token_operators");
@@ -1,17 +1,16 @@
-use cosmwasm_std::{Decimal, Decimal256, Fraction, Timestamp, Uint256};
+use cosmwasm_std::{Decimal, Decimal256, Fraction, Timestamp, Uint128, Uint256};
 use std::ops::Mul;
-use streamswap_types::stream::{FinalizedStatus, Status, StreamState};
+use streamswap_types::stream::{CurveBreakpoint, EmissionCurve, Status, StreamState};
 
 pub fn sync_stream_status(stream: &mut StreamState, now: Timestamp) {
     if matches!(
         stream.status_info.status,
-        Status::Cancelled
-            | Status::Finalized(FinalizedStatus::ThresholdNotReached)
-            | Status::Finalized(FinalizedStatus::ThresholdReached)
+        Status::Cancelled | Status::Paused | Status::Finalized(_)
     ) {
         return;
     }
     stream.status_info.status = match now {
+        _ if stream.is_hard_cap_reached() => Status::Ended,
         _ if now < stream.status_info.bootstrapping_start_time => Status::Waiting,
         _ if now >= stream.status_info.bootstrapping_start_time
             && now < stream.status_info.start_time =>
@@ -38,21 +37,45 @@ pub fn compute_shares_amount(stream: &StreamState, amount_in: Uint256, round_up:
     }
 }
 pub fn sync_stream(stream: &mut StreamState, now: Timestamp) {
+    // Paused streams do not distribute, last updated is moved to the resume time instead
+    if stream.is_paused() {
+        return;
+    }
+    // End time is extended by the paused duration, so start time is shifted by the same amount
+    // to keep the emission curve shape intact
     let diff = calculate_diff(
-        stream.status_info.start_time,
+        &stream.emission_curve,
+        stream
+            .status_info
+            .start_time
+            .plus_nanos(stream.status_info.paused_nanos),
         stream.status_info.end_time,
         stream.status_info.last_updated,
         now,
     );
 
     if !stream.shares.is_zero() && !diff.is_zero() {
-        let new_distribution_balance = stream
+        let mut new_distribution_balance = stream
             .out_remaining
             .multiply_ratio(diff.numerator(), diff.denominator());
-        let spent_in = stream
+        let mut spent_in = stream
             .in_supply
             .multiply_ratio(diff.numerator(), diff.denominator());
 
+        // Clip the distribution so exactly the hard cap is spent and end the stream early,
+        // out_remaining is refunded to the treasury at finalize
+        if let Some(hard_cap) = stream.hard_cap {
+            let cap_remaining = hard_cap.saturating_sub(stream.spent_in);
+            if !spent_in.is_zero() && spent_in >= cap_remaining {
+                new_distribution_balance = stream
+                    .out_remaining
+                    .multiply_ratio(cap_remaining, stream.in_supply);
+                spent_in = cap_remaining;
+                stream.status_info.end_time = stream.status_info.end_time.min(now);
+                stream.status_info.status = Status::Ended;
+            }
+        }
+
         stream.spent_in += spent_in;
         stream.in_supply -= spent_in;
 
@@ -68,14 +91,15 @@ pub fn sync_stream(stream: &mut StreamState, now: Timestamp) {
 }
 
 fn calculate_diff(
+    emission_curve: &EmissionCurve,
     start_time: Timestamp,
     end_time: Timestamp,
     mut last_updated: Timestamp,
     now: Timestamp,
-) -> Decimal {
+) -> Decimal256 {
     // If the stream is not started yet or already ended, return 0
     if now < start_time || last_updated >= end_time {
-        return Decimal::zero();
+        return Decimal256::zero();
     }
     // If we are here, the stream is active. If the last update time is before the start time,
     // This means stream is updated before start time, in order to calculate the diff, we should
@@ -91,12 +115,89 @@ fn calculate_diff(
     // That is why we are taking the minimum of now and end time.
     let now = if now > end_time { end_time } else { now };
 
-    let numerator = now.nanos().saturating_sub(last_updated.nanos());
-    let denominator = end_time.nanos().saturating_sub(last_updated.nanos());
+    match emission_curve {
+        EmissionCurve::Linear => {
+            let numerator = now.nanos().saturating_sub(last_updated.nanos());
+            let denominator = end_time.nanos().saturating_sub(last_updated.nanos());
 
-    if denominator == 0 || numerator == 0 {
-        Decimal::zero()
-    } else {
-        Decimal::from_ratio(numerator, denominator)
+            if denominator == 0 || numerator == 0 {
+                Decimal256::zero()
+            } else {
+                Decimal::from_ratio(numerator, denominator).into()
+            }
+        }
+        _ => {
+            if now == last_updated {
+                return Decimal256::zero();
+            }
+            // Whatever is left is released at the end, so that rounding never locks out tokens
+            if now == end_time {
+                return Decimal256::one();
+            }
+            let released_last = released_fraction(
+                emission_curve,
+                elapsed_fraction(start_time, end_time, last_updated),
+            );
+            let released_now =
+                released_fraction(emission_curve, elapsed_fraction(start_time, end_time, now));
+            let unreleased = Decimal256::one() - released_last;
+            // Diff is applied to the remaining amounts, so the released portion of the curve
+            // is converted to a ratio of what has not been released yet.
+            if unreleased.is_zero() || released_now <= released_last {
+                Decimal256::zero()
+            } else {
+                (released_now - released_last) / unreleased
+            }
+        }
+    }
+}
+
+fn elapsed_fraction(start_time: Timestamp, end_time: Timestamp, time: Timestamp) -> Decimal256 {
+    Decimal256::from_ratio(
+        time.nanos().saturating_sub(start_time.nanos()),
+        end_time.nanos().saturating_sub(start_time.nanos()),
+    )
+}
+
+/// Returns cumulative fraction of the out asset released by the curve at elapsed fraction `t`
+pub fn released_fraction(emission_curve: &EmissionCurve, t: Decimal256) -> Decimal256 {
+    if t >= Decimal256::one() {
+        return Decimal256::one();
+    }
+    match emission_curve {
+        EmissionCurve::Linear => t,
+        EmissionCurve::PiecewiseLinear { breakpoints } => {
+            let mut prev = CurveBreakpoint {
+                time: Decimal256::zero(),
+                released: Decimal256::zero(),
+            };
+            let last = CurveBreakpoint {
+                time: Decimal256::one(),
+                released: Decimal256::one(),
+            };
+            for next in breakpoints.iter().chain(std::iter::once(&last)) {
+                if t <= next.time {
+                    let segment = (t - prev.time) / (next.time - prev.time);
+                    return prev.released + (next.released - prev.released) * segment;
+                }
+                prev = next.clone();
+            }
+            Decimal256::one()
+        }
+        EmissionCurve::ExponentialDecay { epochs, decay } => {
+            // Epoch k releases decay^k * (1 - decay) / (1 - decay^epochs) of the total,
+            // amounts are released linearly within an epoch.
+            let position = t * Decimal256::from_ratio(*epochs, 1u8);
+            let epoch = Uint128::try_from(position.to_uint_floor())
+                .map(|e| e.u128() as u32)
+                .unwrap_or(*epochs)
+                .min(*epochs);
+            let within_epoch = position - Decimal256::from_ratio(epoch, 1u8);
+            let total = Decimal256::one() - decay.pow(*epochs);
+            let released = (Decimal256::one() - decay.pow(epoch)) / total;
+            let epoch_release = decay.pow(epoch) * (Decimal256::one() - *decay) / total;
+            (released + epoch_release * within_epoch).min(Decimal256::one())
+        }
+        EmissionCurve::BackLoaded { exponent } => t.pow(*exponent),
     }
 }
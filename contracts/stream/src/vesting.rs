@@ -3,10 +3,40 @@ use cosmwasm_std::{
     attr, coin, to_json_binary, Addr, Attribute, Binary, CosmosMsg, DepsMut, HexBinary, Timestamp,
     Uint128, WasmMsg,
 };
-use cw_vesting::msg::InstantiateMsg as VestingInstantiateMsg;
-use cw_vesting::UncheckedDenom;
+use cw20::Cw20ExecuteMsg;
+use cw_denom::{CheckedDenom, UncheckedDenom};
+use cw_vesting::msg::{InstantiateMsg as VestingInstantiateMsg, ReceiveMsg as VestingReceiveMsg};
+use sha2::{Digest, Sha256};
 use streamswap_types::controller::VestingConfig;
 
+/// Salt of the subscriber vesting contract. The salt given by the subscriber is namespaced by
+/// the position owner, so that it can not match the salt of any other vesting contract of the
+/// stream.
+pub fn subscriber_vesting_salt(owner: &Addr, salt: &Binary) -> Binary {
+    let mut hasher = Sha256::new();
+    hasher.update(b"subscriber_vesting");
+    hasher.update((owner.as_str().len() as u64).to_be_bytes());
+    hasher.update(owner.as_bytes());
+    hasher.update(salt.as_slice());
+    Binary::from(hasher.finalize().as_slice())
+}
+
+/// Salt of the creator vesting contract, namespaced like the subscriber salts.
+pub fn creator_vesting_salt(salt: &Binary) -> Binary {
+    let mut hasher = Sha256::new();
+    hasher.update(b"creator_vesting");
+    hasher.update(salt.as_slice());
+    Binary::from(hasher.finalize().as_slice())
+}
+
+/// Deterministic salt of the vesting contract of a treasury recipient.
+pub fn treasury_vesting_salt(recipient: &Addr) -> Binary {
+    let mut hasher = Sha256::new();
+    hasher.update(b"treasury_vesting");
+    hasher.update(recipient.as_bytes());
+    Binary::from(hasher.finalize().as_slice())
+}
+
 pub fn vesting_operations(
     deps: &DepsMut,
     stream_addr: Addr,
@@ -16,7 +46,7 @@ pub fn vesting_operations(
     start_time: Timestamp,
     vesting_code_id: u64,
     amount: Uint128,
-    denom: String,
+    denom: CheckedDenom,
     vesting_config: VestingConfig,
 ) -> Result<(Vec<CosmosMsg>, Vec<Attribute>, Addr), ContractError> {
     let salt = salt.ok_or(ContractError::InvalidSalt {})?;
@@ -28,7 +58,10 @@ pub fn vesting_operations(
         recipient: recipient.to_string(),
         description: None,
         total: amount,
-        denom: UncheckedDenom::Native(denom.clone()),
+        denom: match &denom {
+            CheckedDenom::Native(native) => UncheckedDenom::Native(native.clone()),
+            CheckedDenom::Cw20(cw20) => UncheckedDenom::Cw20(cw20.to_string()),
+        },
         schedule: vesting_config.schedule,
         start_time: Some(start_time),
         vesting_duration_seconds: vesting_config.vesting_duration_seconds,
@@ -42,16 +75,33 @@ pub fn vesting_operations(
         &salt,
     )?)?;
 
+    // Native vesting contracts are funded on instantiation, cw20 ones through the receive hook
+    let funds = match &denom {
+        CheckedDenom::Native(native) => vec![coin(amount.u128(), native)],
+        CheckedDenom::Cw20(_) => vec![],
+    };
     let vesting_instantiate_msg = WasmMsg::Instantiate2 {
         admin: None,
         code_id: vesting_code_id,
-        label: format!("{}-{}", denom.clone(), recipient),
+        label: format!("{}-{}", denom, recipient),
         msg: to_json_binary(&vesting_instantiate_msg)?,
-        funds: vec![coin(amount.u128(), denom.clone())],
+        funds,
         salt,
     };
 
-    let messages = vec![vesting_instantiate_msg.into()];
+    let mut messages: Vec<CosmosMsg> = vec![vesting_instantiate_msg.into()];
+    if let CheckedDenom::Cw20(cw20) = denom {
+        let fund_msg = WasmMsg::Execute {
+            contract_addr: cw20.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                contract: vesting_address.to_string(),
+                amount,
+                msg: to_json_binary(&VestingReceiveMsg::Fund {})?,
+            })?,
+            funds: vec![],
+        };
+        messages.push(fund_msg.into());
+    }
     let attributes = vec![attr("vesting_address", vesting_address.clone())];
 
     Ok((messages, attributes, vesting_address))
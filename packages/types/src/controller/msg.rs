@@ -1,5 +1,7 @@
+use crate::stream::{Asset, EmissionCurve, FinalizedStatus, PositionNftConfig, Status};
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Binary, Coin, Decimal256, Timestamp, Uint256};
+use cosmwasm_std::{Addr, Binary, Coin, Decimal256, Timestamp, Uint256};
+use cw_denom::CheckedDenom;
 use cw_vesting::vesting::Schedule;
 
 #[cw_serde]
@@ -11,12 +13,14 @@ pub struct InstantiateMsg {
     pub vesting_code_id: u64,
     /// The optional address of the protocol admin. Defaults to the sender.
     pub protocol_admin: Option<String>,
-    /// The optional address of the fee collector. Defaults to the protocol admin.
-    pub fee_collector: Option<String>,
+    /// The optional weighted fee collectors, weights must sum up to 100%. Defaults to the sender.
+    pub fee_collectors: Option<Vec<FeeCollectorMsg>>,
     /// The fee required to create a stream. Collected from the stream creator upon stream creation.
     pub stream_creation_fee: Coin,
     /// The percentage fee charged when a user exits a stream.
     pub exit_fee_percent: Decimal256,
+    /// The percentage of the exit fee paid to the referrer of a position.
+    pub referral_fee_percent: Decimal256,
     /// The list of accepted denominations for the stream.
     pub accepted_in_denoms: Vec<String>,
     // Minumum time of a stream end_time - start_time
@@ -27,31 +31,129 @@ pub struct InstantiateMsg {
     pub min_waiting_duration: u64,
     // Tos version
     pub tos_version: String,
+    // Seconds after the stream end time, after which dust can be swept even if some positions did not exit
+    pub dust_sweep_grace_period: u64,
 }
 
 #[cw_serde]
 #[cfg_attr(feature = "interface", derive(cw_orch::ExecuteFns))]
+#[allow(clippy::large_enum_variant)]
 pub enum ExecuteMsg {
     UpdateParams {
         min_stream_duration: Option<u64>,
         min_bootstrapping_duration: Option<u64>,
         min_waiting_duration: Option<u64>,
         stream_creation_fee: Option<Coin>,
-        fee_collector: Option<String>,
+        fee_collectors: Option<Vec<FeeCollectorMsg>>,
         accepted_in_denoms: Option<Vec<String>>,
         exit_fee_percent: Option<Decimal256>,
+        referral_fee_percent: Option<Decimal256>,
+        dust_sweep_grace_period: Option<u64>,
+        /// Code id of the streams created afterwards, existing streams are migrated with
+        /// `MigrateStreams`
+        stream_contract_code_id: Option<u64>,
+        vesting_code_id: Option<u64>,
+        tos_version: Option<String>,
     },
     CreateStream {
         msg: Box<CreateStreamMsg>,
     },
     Freeze {},
     Unfreeze {},
+    /// Sets the exit fee override of the target, `None` removes the override.
+    /// Stream overrides take precedence over creator overrides, which take precedence over
+    /// in denom overrides. Overrides are resolved when a stream is created, creator and in denom
+    /// overrides apply to the streams created afterwards. Stream overrides are sent to the
+    /// registered stream and can only be changed before the stream starts.
+    SetExitFeeOverride {
+        target: ExitFeeOverrideTarget,
+        exit_fee_percent: Option<Decimal256>,
+    },
+    /// Status update sent by a stream created by the controller.
+    StreamCallback {
+        msg: StreamCallbackMsg,
+    },
+    /// Migrates the selected streams to the new stream code id. Streams already running the new
+    /// code are skipped. Streams created before 0.2.0-beta4 are administered by the protocol
+    /// admin, which sets the controller as their admin first.
+    MigrateStreams {
+        new_code_id: u64,
+        selection: StreamSelection,
+    },
+    /// Moves a page of the streams registered by a 0.2.0-beta3 controller to the stream records.
+    /// Can be called by anyone until every legacy stream is moved, legacy streams are not listed
+    /// and can not be selected before they are moved.
+    BackfillStreams {
+        limit: Option<u32>,
+    },
+    /// Proposes a new protocol admin, the transfer completes once the new admin accepts it.
+    ProposeAdmin {
+        new_admin: String,
+    },
+    /// Accepts the pending protocol admin transfer, only callable by the proposed admin. Roles held
+    /// by the previous admin move to the new admin, roles assigned to other addresses are kept.
+    AcceptAdmin {},
+    /// Cancels the pending protocol admin transfer.
+    CancelAdminProposal {},
+    /// Assigns the protocol roles, only callable by the protocol admin.
+    UpdateRoles {
+        params_manager: Option<String>,
+        pauser: Option<String>,
+        stream_canceller: Option<String>,
+        fee_manager: Option<String>,
+    },
+}
+
+#[cw_serde]
+pub enum StreamSelection {
+    /// Streams with the given ids
+    Ids { stream_ids: Vec<u64> },
+    /// Streams paginated by id
+    Range {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+/// Status changes reported by streams to their controller
+#[cw_serde]
+pub enum StreamCallbackMsg {
+    /// Stream is finalized. `spent_in` is the total in amount spent by the positions and
+    /// `swap_fee` is the part of the swap fee sent to the fee collectors.
+    Finalized {
+        status: FinalizedStatus,
+        spent_in: Uint256,
+        swap_fee: Uint256,
+    },
+    /// Stream is cancelled by the protocol admin or the stream admin
+    Cancelled {},
+}
+
+#[cw_serde]
+pub enum ExitFeeOverrideTarget {
+    /// Applies to a single stream
+    Stream { address: String },
+    /// Applies to the streams created by the address afterwards
+    Creator { address: String },
+    /// Applies to the streams with the in denom created afterwards
+    InDenom { denom: String },
+}
+
+#[cw_serde]
+pub struct FeeCollectorMsg {
+    pub address: String,
+    /// Share of the protocol fees sent to the address
+    pub weight: Decimal256,
 }
 
 #[cw_serde]
 pub struct CreateStreamMsg {
-    /// Treasury address, where the stream creator can withdraw the in assets at the end of the stream
+    /// Treasury address, where the stream creator can withdraw the in assets at the end of the stream.
+    /// If treasury recipients are set, it is the primary treasury receiving the refunds of the stream
     pub treasury: String,
+    /// Optional weighted split of the creator revenue, weights must sum up to 100%.
+    /// Defaults to sending the whole revenue to the treasury
+    pub treasury_recipients: Option<Vec<TreasuryRecipientMsg>>,
     /// Stream admin address, where the stream creator can manage the stream, like canceling it in waiting status
     /// or finalizing it in ended status
     pub stream_admin: String,
@@ -59,10 +161,11 @@ pub struct CreateStreamMsg {
     pub name: String,
     /// URL of the stream
     pub url: Option<String>,
-    /// Out asset of the stream
-    pub out_asset: Coin,
+    /// Out asset of the stream. Cw20 out assets are transferred from the creator,
+    /// an allowance for the controller is required
+    pub out_asset: Asset,
     /// In denom of the stream
-    pub in_denom: String,
+    pub in_denom: CheckedDenom,
     /// Bootstrapping start time
     pub bootstraping_start_time: Timestamp,
     /// Stream start time
@@ -71,6 +174,23 @@ pub struct CreateStreamMsg {
     pub end_time: Timestamp,
     /// Optional threshold for the stream, if set, the stream will be cancelled if the threshold is not reached
     pub threshold: Option<Uint256>,
+    /// Optional minimum average price of the out asset in in asset, if set and not reached at the end
+    /// of the stream, subscribers are fully refunded
+    pub min_average_price: Option<Decimal256>,
+    /// Optional emission curve of the out asset, defaults to linear emission
+    pub emission_curve: Option<EmissionCurve>,
+    /// Optional maximum amount of in asset a single address can subscribe with
+    pub max_in_per_address: Option<Uint256>,
+    /// Optional hard cap on the total amount of in asset subscribed to the stream
+    pub max_in_supply: Option<Uint256>,
+    /// Optional hard cap on the spent in asset, the stream ends as soon as it is reached
+    pub hard_cap: Option<Uint256>,
+    /// Optional hex encoded merkle root of the addresses allowed to subscribe
+    pub merkle_root: Option<String>,
+    /// Optional cw721 representation of the positions
+    pub position_nft: Option<PositionNftConfig>,
+    /// Optional contracts notified on subscribe, withdraw, exit and finalize
+    pub hooks: Option<Vec<String>>,
     /// Pool Configuration for the pre stream
     pub pool_config: Option<PoolConfig>,
     /// Subscriber Vesting configuration
@@ -84,6 +204,16 @@ pub struct CreateStreamMsg {
     pub tos_version: String,
 }
 
+#[cw_serde]
+pub struct TreasuryRecipientMsg {
+    /// Address receiving its share of the creator revenue
+    pub address: String,
+    /// Share of the creator revenue sent to the address
+    pub weight: Decimal256,
+    /// Optional vesting configuration, used to create a vesting contract for the recipient's share
+    pub vesting: Option<VestingConfig>,
+}
+
 #[cw_serde]
 pub struct VestingConfig {
     pub schedule: Schedule,
@@ -117,12 +247,76 @@ pub enum QueryMsg {
     Freezestate {},
     #[returns(u64)]
     LastStreamId {},
-    /// Returns list of streams paginated by `start_after` and `limit`.
+    /// Returns list of streams paginated by `start_after` and `limit`, optionally filtered.
     #[returns(StreamsResponse)]
     ListStreams {
         start_after: Option<u64>,
         limit: Option<u32>,
+        filter: Option<StreamsFilter>,
     },
+    /// Returns the stream registered with the given contract address.
+    #[returns(StreamResponse)]
+    StreamByAddress { address: String },
+    /// Returns the exit fee override that applies to the stream, if any.
+    #[returns(Option<Decimal256>)]
+    ExitFeeOverride { stream: String },
+    /// Returns the statistics aggregated from the stream callbacks.
+    #[returns(ProtocolStatsResponse)]
+    ProtocolStats {},
+    /// Returns the protocol admin, the pending admin and the protocol roles.
+    #[returns(RolesResponse)]
+    Roles {},
+}
+
+#[cw_serde]
+pub struct RolesResponse {
+    pub protocol_admin: String,
+    /// Proposed protocol admin waiting to accept the transfer
+    pub pending_admin: Option<String>,
+    pub params_manager: String,
+    pub pauser: String,
+    pub stream_canceller: String,
+    pub fee_manager: String,
+}
+
+/// Counters of the stream outcomes reported to the controller
+#[cw_serde]
+#[derive(Default)]
+pub struct ProtocolStats {
+    /// Streams finalized with threshold and min average price reached
+    pub streams_finalized: u64,
+    pub streams_cancelled: u64,
+    /// Streams finalized without reaching their threshold
+    pub thresholds_missed: u64,
+    /// Streams finalized without reaching their min average price
+    pub min_average_prices_missed: u64,
+}
+
+/// Volume and fees of the finalized streams of an in denom
+#[cw_serde]
+#[derive(Default)]
+pub struct DenomStats {
+    /// Total in amount spent in finalized streams
+    pub volume: Uint256,
+    /// Total swap fee sent to the fee collectors
+    pub fees_collected: Uint256,
+}
+
+#[cw_serde]
+pub struct ProtocolStatsResponse {
+    pub streams_created: u64,
+    pub streams_finalized: u64,
+    pub streams_cancelled: u64,
+    pub thresholds_missed: u64,
+    pub min_average_prices_missed: u64,
+    pub denoms: Vec<DenomStatsResponse>,
+}
+
+#[cw_serde]
+pub struct DenomStatsResponse {
+    pub denom: String,
+    pub volume: Uint256,
+    pub fees_collected: Uint256,
 }
 
 #[cw_serde]
@@ -134,8 +328,70 @@ pub struct StreamsResponse {
 pub struct StreamResponse {
     pub id: u64,
     pub address: String,
+    /// Code id the stream contract is running
+    pub code_id: u64,
+    pub creator: String,
+    pub in_denom: String,
+    pub out_denom: String,
+    pub name: String,
+    pub start_time: Timestamp,
+    pub end_time: Timestamp,
+    /// Latest stream status known to the controller
+    pub status: Status,
+}
+
+/// Filters of the stream listing, all set filters must match
+#[cw_serde]
+#[derive(Default)]
+pub struct StreamsFilter {
+    pub creator: Option<String>,
+    pub in_denom: Option<String>,
+    pub out_denom: Option<String>,
+    /// Time window of the stream relative to the current block time
+    pub window: Option<StreamWindow>,
+}
+
+#[cw_serde]
+pub enum StreamWindow {
+    /// Streams that have not started yet
+    Upcoming,
+    /// Streams between their start and end time
+    Active,
+    /// Streams past their end time
+    Ended,
+}
+
+/// Stream registered by the controller at creation
+#[cw_serde]
+pub struct StreamRecord {
+    pub address: Addr,
+    pub code_id: u64,
+    pub creator: Addr,
+    pub in_denom: String,
+    pub out_denom: String,
+    pub name: String,
+    pub start_time: Timestamp,
+    pub end_time: Timestamp,
+    /// Latest stream status known to the controller
+    pub status: Status,
+}
+
+impl StreamRecord {
+    pub fn into_response(self, id: u64) -> StreamResponse {
+        StreamResponse {
+            id,
+            address: self.address.to_string(),
+            code_id: self.code_id,
+            creator: self.creator.to_string(),
+            in_denom: self.in_denom,
+            out_denom: self.out_denom,
+            name: self.name,
+            start_time: self.start_time,
+            end_time: self.end_time,
+            status: self.status,
+        }
+    }
 }
 
 #[cw_serde]
-#[cfg_attr(feature = "interface", derive(cw_orch::MigrateFns))]
-pub enum MigrateMsg {}
+pub struct MigrateMsg {}
@@ -1,16 +1,18 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Attribute, Coin, Decimal256};
+use cosmwasm_std::{Addr, Attribute, Coin, Decimal256, Uint256};
 
 #[cw_serde]
 pub struct Params {
-    // Protocol admin, Have power to update the params and cancel streams
+    // Protocol admin, assigns the protocol roles and migrates streams. Transferred in two steps
     pub protocol_admin: Addr,
-    // Fee collector, address that will receive the stream creation fee
-    pub fee_collector: Addr,
+    // Fee collectors, addresses that will receive the protocol fees by weight, weights sum up to 100%
+    pub fee_collectors: Vec<FeeCollector>,
     // Stream creation fee collected from stream creator when a stream is created
     pub stream_creation_fee: Coin,
     // Exit fee percent, fee that will be charged when a user exit a stream
     pub exit_fee_percent: Decimal256,
+    // Referral fee percent, share of the exit fee paid to the referrer of a position
+    pub referral_fee_percent: Decimal256,
     pub stream_contract_code_id: u64,
     // Vesting contract code id
     pub vesting_code_id: u64,
@@ -24,16 +26,127 @@ pub struct Params {
     pub min_waiting_duration: u64,
     /// Version or hash of current terms and condition document
     pub tos_version: String,
+    // Seconds after the stream end time, after which dust can be swept even if some positions did not exit
+    pub dust_sweep_grace_period: u64,
+}
+
+#[cw_serde]
+pub struct FeeCollector {
+    pub address: Addr,
+    pub weight: Decimal256,
+}
+
+#[cw_serde]
+pub struct Roles {
+    // Updates stream durations, accepted denoms, code ids and tos version
+    pub params_manager: Addr,
+    // Freezes the controller, pauses and resumes streams
+    pub pauser: Addr,
+    // Cancels streams through the kill switch
+    pub stream_canceller: Addr,
+    // Updates fees, fee collectors and exit fee overrides
+    pub fee_manager: Addr,
+}
+
+impl Roles {
+    // Assigns every role to the address, used until the protocol admin sets the roles
+    pub fn all(address: Addr) -> Self {
+        Roles {
+            params_manager: address.clone(),
+            pauser: address.clone(),
+            stream_canceller: address.clone(),
+            fee_manager: address,
+        }
+    }
+
+    // Reassigns the roles held by `from` to `to`, roles assigned to other addresses are kept
+    pub fn reassign(&mut self, from: &Addr, to: &Addr) {
+        for role in [
+            &mut self.params_manager,
+            &mut self.pauser,
+            &mut self.stream_canceller,
+            &mut self.fee_manager,
+        ] {
+            if role == from {
+                *role = to.clone();
+            }
+        }
+    }
+}
+
+// Grace period given to the dust sweep of the params migrated from the previous versions
+pub const LEGACY_DUST_SWEEP_GRACE_PERIOD: u64 = 30 * 24 * 60 * 60;
+
+// Params stored by the versions before the weighted fee collectors, referral fees and dust sweep
+#[cw_serde]
+pub struct LegacyParams {
+    pub protocol_admin: Addr,
+    pub fee_collector: Addr,
+    pub stream_creation_fee: Coin,
+    pub exit_fee_percent: Decimal256,
+    pub stream_contract_code_id: u64,
+    pub vesting_code_id: u64,
+    pub accepted_in_denoms: Vec<String>,
+    pub min_stream_duration: u64,
+    pub min_bootstrapping_duration: u64,
+    pub min_waiting_duration: u64,
+    pub tos_version: String,
+}
+
+impl LegacyParams {
+    // The fee collector receives all the fees, referral fees are disabled and the dust sweep waits
+    // for the legacy grace period
+    pub fn into_params(self) -> Params {
+        Params {
+            protocol_admin: self.protocol_admin,
+            fee_collectors: vec![FeeCollector {
+                address: self.fee_collector,
+                weight: Decimal256::one(),
+            }],
+            stream_creation_fee: self.stream_creation_fee,
+            exit_fee_percent: self.exit_fee_percent,
+            referral_fee_percent: Decimal256::zero(),
+            stream_contract_code_id: self.stream_contract_code_id,
+            vesting_code_id: self.vesting_code_id,
+            accepted_in_denoms: self.accepted_in_denoms,
+            min_stream_duration: self.min_stream_duration,
+            min_bootstrapping_duration: self.min_bootstrapping_duration,
+            min_waiting_duration: self.min_waiting_duration,
+            tos_version: self.tos_version,
+            dust_sweep_grace_period: LEGACY_DUST_SWEEP_GRACE_PERIOD,
+        }
+    }
 }
 
 impl Params {
+    // Splits the fee among the fee collectors by weight, rounding remainder goes to the first collector
+    pub fn split_fee(&self, amount: Uint256) -> Vec<(Addr, Uint256)> {
+        split_by_weight(
+            amount,
+            self.fee_collectors
+                .iter()
+                .map(|collector| (collector.address.clone(), collector.weight)),
+        )
+    }
+
     // Converts Params to attributes
     pub fn to_attributes(&self) -> Vec<Attribute> {
         vec![
             Attribute::new("protocol_admin", self.protocol_admin.to_string()),
-            Attribute::new("fee_collector", self.fee_collector.to_string()),
+            Attribute::new(
+                "fee_collectors",
+                self.fee_collectors
+                    .iter()
+                    .map(|collector| format!("{}:{}", collector.address, collector.weight))
+                    .collect::<Vec<String>>()
+                    .join(","),
+            ),
             Attribute::new("stream_creation_fee", self.stream_creation_fee.to_string()),
             Attribute::new("exit_fee_percent", self.exit_fee_percent.to_string()),
+            Attribute::new(
+                "referral_fee_percent",
+                self.referral_fee_percent.to_string(),
+            ),
             Attribute::new(
                 "stream_contract_code_id",
                 self.stream_contract_code_id.to_string(),
@@ -49,6 +162,33 @@ impl Params {
                 "min_waiting_duration",
                 self.min_waiting_duration.to_string(),
             ),
+            Attribute::new(
+                "dust_sweep_grace_period",
+                self.dust_sweep_grace_period.to_string(),
+            ),
+            Attribute::new("tos_version", self.tos_version.clone()),
         ]
     }
 }
+
+// Splits the amount by weight, weights are expected to sum up to 100%. Rounding remainder goes to
+// the first entry
+pub fn split_by_weight<T>(
+    amount: Uint256,
+    weighted: impl IntoIterator<Item = (T, Decimal256)>,
+) -> Vec<(T, Uint256)> {
+    let mut shares: Vec<(T, Uint256)> = weighted
+        .into_iter()
+        .map(|(item, weight)| {
+            let share = amount.multiply_ratio(weight.atomics(), Decimal256::one().atomics());
+            (item, share)
+        })
+        .collect();
+    let distributed = shares
+        .iter()
+        .fold(Uint256::zero(), |total, (_, share)| total + share);
+    if let Some((_, first_share)) = shares.first_mut() {
+        *first_share += amount - distributed;
+    }
+    shares
+}
@@ -0,0 +1,31 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Coin, Uint128};
+use cw_denom::CheckedDenom;
+
+/// Asset is either a native coin or a cw20 token with an amount.
+#[cw_serde]
+pub struct Asset {
+    pub denom: CheckedDenom,
+    pub amount: Uint128,
+}
+
+impl Asset {
+    pub fn new(denom: CheckedDenom, amount: Uint128) -> Self {
+        Asset { denom, amount }
+    }
+}
+
+impl From<Coin> for Asset {
+    fn from(coin: Coin) -> Self {
+        Asset {
+            denom: CheckedDenom::Native(coin.denom),
+            amount: coin.amount,
+        }
+    }
+}
+
+impl std::fmt::Display for Asset {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}{}", self.amount, self.denom)
+    }
+}
@@ -0,0 +1,28 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Decimal256;
+
+/// Emission curve determines how the out asset is released over the active period of the stream.
+/// Spending of the in asset follows the same curve, so the streamed price is not distorted by the shape.
+#[cw_serde]
+#[derive(Default)]
+pub enum EmissionCurve {
+    /// Out asset is released at a constant rate.
+    #[default]
+    Linear,
+    /// Cumulative release follows the given breakpoints and is interpolated linearly in between.
+    /// The curve implicitly starts at (0, 0) and ends at (1, 1).
+    PiecewiseLinear { breakpoints: Vec<CurveBreakpoint> },
+    /// Front-loaded release. Active period is split into `epochs` equal periods and each period
+    /// releases `decay` times the amount released in the previous one.
+    ExponentialDecay { epochs: u32, decay: Decimal256 },
+    /// Back-loaded release, cumulative release follows `t^exponent`.
+    BackLoaded { exponent: u32 },
+}
+
+#[cw_serde]
+pub struct CurveBreakpoint {
+    /// Elapsed fraction of the active period, between 0 and 1.
+    pub time: Decimal256,
+    /// Cumulative fraction of the out asset released at `time`, between 0 and 1.
+    pub released: Decimal256,
+}
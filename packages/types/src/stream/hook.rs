@@ -0,0 +1,63 @@
+use crate::stream::FinalizedStatus;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Uint256;
+
+/// Message executed on hooked contracts, serialized as `{"stream_hook": {..}}`.
+#[cw_serde]
+pub enum StreamHookExecuteMsg {
+    StreamHook(StreamHookMsg),
+}
+
+/// Stream activity sent to hooked contracts.
+#[cw_serde]
+pub enum StreamHookMsg {
+    /// A position subscribed to the stream.
+    Subscribe {
+        subscriber: String,
+        in_amount: Uint256,
+        /// Shares minted for the subscription
+        shares: Uint256,
+        /// Position change since its last sync, zero for new positions
+        delta: PositionDelta,
+    },
+    /// A position withdrew its unspent in tokens.
+    Withdraw {
+        owner: String,
+        withdraw_amount: Uint256,
+        /// Shares burned for the withdrawal
+        shares: Uint256,
+        /// Position change since its last sync
+        delta: PositionDelta,
+    },
+    /// A position exited the stream.
+    Exit {
+        owner: String,
+        /// Total purchased out tokens of the position
+        purchased: Uint256,
+        /// Total spent in tokens of the position
+        spent: Uint256,
+        /// True if the position was fully refunded
+        refunded: bool,
+        /// Position change since its last sync, zero for refunded positions
+        delta: PositionDelta,
+    },
+    /// The stream was finalized.
+    Finalize {
+        status: FinalizedStatus,
+        spent_in: Uint256,
+        out_remaining: Uint256,
+    },
+}
+
+/// Purchased and spent amounts computed by syncing a position.
+#[cw_serde]
+#[derive(Default)]
+pub struct PositionDelta {
+    pub purchased: Uint256,
+    pub spent: Uint256,
+}
+
+#[cw_serde]
+pub struct HooksResponse {
+    pub hooks: Vec<String>,
+}
@@ -1,7 +1,15 @@
+mod asset;
+mod curve;
 mod error;
+mod hook;
 mod msg;
+mod nft;
 mod position;
 mod stream;
+pub use asset::*;
+pub use curve::*;
+pub use hook::*;
 pub use msg::*;
+pub use nft::*;
 pub use position::*;
 pub use stream::*;
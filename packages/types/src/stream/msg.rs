@@ -1,21 +1,91 @@
 use crate::controller::CreatePool;
-use crate::stream::Status;
+use crate::stream::{
+    AllNftInfoResponse, ApprovalResponse, ApprovalsResponse, Asset, ContractInfoResponse,
+    EmissionCurve, HooksResponse, NftInfoResponse, NumTokensResponse, OperatorsResponse,
+    OwnerOfResponse, Status, TokensResponse, TreasuryRecipient,
+};
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Binary, Coin, Decimal256, Timestamp, Uint128, Uint256};
+use cosmwasm_std::{Binary, Decimal256, Timestamp, Uint128, Uint256};
+use cw20::Cw20ReceiveMsg;
+use cw_denom::CheckedDenom;
+use cw_utils::Expiration;
 
 #[cw_serde]
 #[cfg_attr(feature = "interface", derive(cw_orch::ExecuteFns))]
 pub enum ExecuteMsg {
     /// SyncStream synchronizes stream and distribution to reflect the current state of the stream.
     SyncStream {},
-    Subscribe {},
+    /// Subscribe to the stream with the sent in tokens.
+    Subscribe {
+        /// Merkle proof of the subscriber, required until the subscriber is verified if the stream has
+        /// an allowlist
+        proof: Option<Vec<String>>,
+        /// Credits the position to this address instead of the sender. The position is fully
+        /// owned by this address, only it can withdraw, exit or transfer the position. The terms
+        /// of service are signed by the sender, not by this address.
+        on_behalf_of: Option<String>,
+        /// Address that referred the subscriber, only recorded on the first subscription.
+        /// The referrer receives a share of the exit fee of the position.
+        referrer: Option<String>,
+    },
+    /// Receive is the cw20 hook, used to subscribe to streams with a cw20 in denom.
+    Receive(Cw20ReceiveMsg),
     /// Withdraw unspent tokens in balance.
     Withdraw {
         cap: Option<Uint256>,
     },
+    /// ClaimPurchased sends the purchased tokens_out of the sender accrued so far.
+    /// Only available for active streams that reached their threshold and have no subscriber vesting.
+    ClaimPurchased {},
     /// SyncPosition sync the position of the user.
     /// syncs position index to the current state of the stream.
     SyncPosition {},
+    /// TransferPosition moves the position of the sender to the recipient. If the recipient
+    /// already holds a position, the transferred position is merged into it, keeping the
+    /// recipient's referrer and token. The merged position is subject to the per address cap.
+    /// Positions are transferable in streams with an allowlist, but the recipient needs a
+    /// proof to subscribe more.
+    TransferPosition {
+        recipient: String,
+    },
+    //
+    // Cw721 interface, only available if the stream has position nfts enabled.
+    // Transferring a token transfers the position it represents.
+    /// TransferNft transfers the position token to the recipient. Positions are keyed by owner,
+    /// so the recipient can not already hold a position.
+    TransferNft {
+        recipient: String,
+        token_id: String,
+    },
+    /// SendNft transfers the position token to a contract and triggers its `ReceiveNft` hook.
+    /// The contract can not already hold a position.
+    SendNft {
+        contract: String,
+        token_id: String,
+        msg: Binary,
+    },
+    /// Approve allows the spender to transfer the position token. Approvals are cleared on transfer.
+    /// Callable by the owner or its operators.
+    Approve {
+        spender: String,
+        token_id: String,
+        expires: Option<Expiration>,
+    },
+    /// Revoke removes a previously granted approval.
+    Revoke {
+        spender: String,
+        token_id: String,
+    },
+    /// ApproveAll allows the operator to transfer every position token of the sender.
+    ApproveAll {
+        operator: String,
+        expires: Option<Expiration>,
+    },
+    /// RevokeAll removes a previously granted operator.
+    RevokeAll {
+        operator: String,
+    },
+    //
     /// FinalizeStream clean ups the stream and sends income (earned tokens_in) to the
     /// Stream recipient. Returns error if called before the Stream end. Anyone can
     /// call this method.
@@ -28,15 +98,86 @@ pub enum ExecuteMsg {
     /// tokens_out from the pool and remained tokens_in. Must be called after
     /// the stream ends.
     ExitStream {
-        /// Salt is required for vested address generation
+        /// Salt is required for vested address generation. It is namespaced by the position
+        /// owner, so it can not collide with the vesting contracts of other addresses.
         salt: Option<Binary>,
     },
+    /// ExitPositions exits a page of positions on behalf of their owners and sends the funds
+    /// to them. Can be called by anyone once the stream is ended, finalized or cancelled.
+    /// Subscriber vesting contracts are created with an empty salt, namespaced by the owner
+    /// address like the salts given to `ExitStream`. Each exit is reported in an `exit_position`
+    /// event with the owner and the attributes of the exit.
+    ExitPositions {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// SweepDust sends the rounding leftovers of the stream to the fee collector. Can be called
+    /// by anyone once the stream is finalized or cancelled and every position exited, or once
+    /// the dust sweep grace period after the end time has passed. Positions withdrawn before
+    /// spending anything do not need to exit. Amounts owed to the positions that did not exit by
+    /// the end of the grace period stay in the stream and can still be exited. Dust is swept once.
+    SweepDust {},
+    /// MigratePositions rewrites a page of the positions stored by a 0.2.0-beta3 stream in the
+    /// current format, continuing after the last migrated position. Can be called by anyone after
+    /// the stream migration until every legacy position is migrated. Legacy positions can not be
+    /// used and dust can not be swept before they are migrated.
+    MigratePositions {
+        limit: Option<u32>,
+    },
+    //
+    /// UpdateExitFee applies the exit fee override of the stream set on the controller. Only the
+    /// controller can call this method, and only before the stream is active. `None` applies
+    /// the exit fee of the controller params collected at instantiate.
+    UpdateExitFee {
+        exit_fee_override: Option<Decimal256>,
+    },
+    //
+    /// UpdateMerkleRoot rotates the subscription allowlist. Only stream admin can call
+    /// this method, and only before the stream is active. `None` removes the allowlist.
+    /// Addresses verified against the previous root need a proof of the new one.
+    UpdateMerkleRoot {
+        merkle_root: Option<String>,
+    },
+    /// AddHook registers a contract notified of the stream activity. Only stream admin can call
+    /// this method, and only in waiting status. Hooks run with a bounded gas limit and their
+    /// failures are ignored.
+    AddHook {
+        addr: String,
+    },
+    /// RemoveHook unregisters a hooked contract. Only stream admin can call this method, and
+    /// only in waiting status.
+    RemoveHook {
+        addr: String,
+    },
     //
     // Circuit features
     CancelStream {},
+    /// PauseStream stops the distribution of an active stream. Only protocol admin can call this method.
+    PauseStream {},
+    /// ResumeStream resumes a paused stream and extends its end time by the paused duration.
+    /// Only protocol admin can call this method.
+    ResumeStream {},
     StreamAdminCancel {},
 }
 
+/// Messages that can be attached to a cw20 `Send` to the stream.
+#[cw_serde]
+pub enum ReceiveMsg {
+    /// Subscribe to the stream with the sent cw20 tokens.
+    Subscribe {
+        /// Merkle proof of the subscriber, required until the subscriber is verified if the stream has
+        /// an allowlist
+        proof: Option<Vec<String>>,
+        /// Credits the position to this address instead of the sender. The position is fully
+        /// owned by this address, only it can withdraw, exit or transfer the position. The terms
+        /// of service are signed by the cw20 sender, not by this address.
+        on_behalf_of: Option<String>,
+        /// Address that referred the subscriber, only recorded on the first subscription.
+        /// The referrer receives a share of the exit fee of the position.
+        referrer: Option<String>,
+    },
+}
+
 #[cw_serde]
 #[derive(QueryResponses)]
 #[cfg_attr(feature = "interface", derive(cw_orch::QueryFns))]
@@ -47,15 +188,34 @@ pub enum QueryMsg {
     /// Returns a stream's current state.
     #[returns(StreamResponse)]
     Stream {},
-    /// Returns current state of a position.
+    /// Returns the state of a position, simulated as synced at `at_time` or the current block time.
     #[returns(PositionResponse)]
-    Position { owner: String },
-    /// Returns list of positions paginated by `start_after` and `limit`.
+    Position {
+        owner: String,
+        at_time: Option<Timestamp>,
+    },
+    /// Returns list of positions paginated by `start_after` and `limit`, simulated as synced at
+    /// `at_time` or the current block time.
     #[returns(PositionsResponse)]
     ListPositions {
         start_after: Option<String>,
         limit: Option<u32>,
+        at_time: Option<Timestamp>,
     },
+    /// Simulates a subscription of `amount` at `at_time` or the current block time. Fails like
+    /// the subscription if the stream or subscriber caps are exceeded. The allowlist is only
+    /// checked if `subscriber` is set, a new position is assumed otherwise.
+    #[returns(SimulateSubscribeResponse)]
+    SimulateSubscribe {
+        amount: Uint256,
+        at_time: Option<Timestamp>,
+        subscriber: Option<String>,
+        /// Merkle proof of the subscriber, see `Subscribe`
+        proof: Option<Vec<String>>,
+    },
+    /// Simulates a withdrawal of `cap`, or the whole in balance, of a position at the current block time.
+    #[returns(SimulateWithdrawResponse)]
+    SimulateWithdraw { owner: String, cap: Option<Uint256> },
     /// Returns average price of a stream sale.
     #[returns(AveragePriceResponse)]
     AveragePrice {},
@@ -68,9 +228,81 @@ pub enum QueryMsg {
     /// Returns the instantiated vesting contract for the creator.
     #[returns(String)]
     CreatorVesting {},
+    /// Returns the instantiated vesting contract of a treasury recipient.
+    #[returns(String)]
+    TreasuryVesting { addr: String },
     /// Returns the instantiated contract contract of the subscriber.
     #[returns(String)]
     SubscriberVesting { addr: String },
+    /// Returns the contracts notified of the stream activity.
+    #[returns(HooksResponse)]
+    Hooks {},
+    /// Returns true if the address has already proved its membership of the current allowlist.
+    #[returns(bool)]
+    AllowlistVerified { addr: String },
+    //
+    // Cw721 queries, only available if the stream has position nfts enabled.
+    /// Returns the owner of the position token and its approvals.
+    #[returns(OwnerOfResponse)]
+    OwnerOf {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    /// Returns the approval of the spender for the position token.
+    #[returns(ApprovalResponse)]
+    Approval {
+        token_id: String,
+        spender: String,
+        include_expired: Option<bool>,
+    },
+    /// Returns the approvals of the position token.
+    #[returns(ApprovalsResponse)]
+    Approvals {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    /// Returns the operators of the owner paginated by `start_after` and `limit`.
+    #[returns(OperatorsResponse)]
+    AllOperators {
+        owner: String,
+        include_expired: Option<bool>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns the metadata of the position token.
+    #[returns(NftInfoResponse)]
+    NftInfo { token_id: String },
+    /// Returns the owner, approvals and metadata of the position token.
+    #[returns(AllNftInfoResponse)]
+    AllNftInfo {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    /// Returns the number of minted position tokens.
+    #[returns(NumTokensResponse)]
+    NumTokens {},
+    /// Returns the name and symbol of the position collection.
+    #[returns(ContractInfoResponse)]
+    ContractInfo {},
+    /// Returns the position tokens owned by the address.
+    #[returns(TokensResponse)]
+    Tokens {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns all position tokens paginated by `start_after` and `limit`.
+    #[returns(TokensResponse)]
+    AllTokens {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns the referred positions and paid referral fees per referrer.
+    #[returns(ReferralsResponse)]
+    ListReferrals {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 }
 
 #[cw_serde]
@@ -98,6 +330,8 @@ pub struct StreamResponse {
     pub name: String,
     /// Address of the treasury where the stream earnings will be sent.
     pub treasury: String,
+    /// Weighted recipients of the stream earnings, empty if the earnings go to the treasury.
+    pub treasury_recipients: Vec<TreasuryRecipient>,
     /// URL of the stream.
     pub url: Option<String>,
     /// Proportional distribution variable to calculate the distribution of in token_out to buyers.
@@ -105,11 +339,11 @@ pub struct StreamResponse {
     /// Last updated time of stream.
     pub last_updated: Timestamp,
     /// Out asset of the stream.
-    pub out_asset: Coin,
+    pub out_asset: Asset,
     /// Total number of remaining out tokens at the time of update.
     pub out_remaining: Uint256,
     /// Denom of the `token_in`.
-    pub in_denom: String,
+    pub in_denom: CheckedDenom,
     /// Total number of `token_in` on the buy side at latest state.
     pub in_supply: Uint256,
     /// Total number of `token_in` spent at latest state.
@@ -128,6 +362,20 @@ pub struct StreamResponse {
     pub stream_admin: String,
     /// Threshold for the stream to be finalized.
     pub threshold: Option<Uint256>,
+    /// Minimum average price for the stream to be finalized.
+    pub min_average_price: Option<Decimal256>,
+    /// Emission curve of the out asset.
+    pub emission_curve: EmissionCurve,
+    /// Maximum amount of in asset a single address can subscribe with.
+    pub max_in_per_address: Option<Uint256>,
+    /// Maximum amount of in asset that can be subscribed to the stream.
+    pub max_in_supply: Option<Uint256>,
+    /// Amount of spent in asset that ends the stream early.
+    pub hard_cap: Option<Uint256>,
+    /// Hex encoded merkle root of the subscription allowlist.
+    pub merkle_root: Option<String>,
+    /// Exit fee applied to the stream, including controller overrides.
+    pub exit_fee_percent: Decimal256,
 }
 
 #[cw_serde]
@@ -153,8 +401,20 @@ pub struct PositionResponse {
     pub pending_purchase: Decimal256,
     /// Total amount of `token_in` spent tokens at latest calculation
     pub spent: Uint256,
+    /// Total amount of `token_out` claimed during the stream
+    pub claimed: Uint256,
     /// Exit date of the position
     pub exit_date: Timestamp,
+    /// Token id of the position if positions are represented as cw721 tokens
+    pub token_id: Option<String>,
+    /// Address that referred the position
+    pub referrer: Option<String>,
+    /// Estimated exit fee of the position in `token_in`, zero if the position is refunded
+    pub exit_fee: Uint256,
+    /// Estimated `token_out` paid to the position on exit
+    pub net_out_payout: Uint256,
+    /// Estimated `token_in` paid to the position on exit
+    pub net_in_payout: Uint256,
 }
 
 #[cw_serde]
@@ -162,6 +422,64 @@ pub struct PositionsResponse {
     pub positions: Vec<PositionResponse>,
 }
 
+#[cw_serde]
+pub struct ReferralResponse {
+    /// Address of the referrer
+    pub referrer: String,
+    /// Number of positions referred
+    pub referred_positions: u64,
+    /// Total referral fees paid to the referrer in `token_in`
+    pub fees_paid: Uint256,
+}
+
+#[cw_serde]
+pub struct ReferralsResponse {
+    pub referrals: Vec<ReferralResponse>,
+}
+
+/// Rounding applied when converting in amounts to shares.
+#[cw_serde]
+pub enum ShareRounding {
+    /// Shares are rounded down, applied on subscriptions
+    Down,
+    /// Shares are rounded up, applied on partial withdrawals
+    Up,
+    /// No rounding is applied
+    Exact,
+}
+
+#[cw_serde]
+pub struct SimulateSubscribeResponse {
+    /// Shares minted for the subscription
+    pub shares: Uint256,
+    pub rounding: ShareRounding,
+    /// In supply of the stream after the subscription
+    pub in_supply: Uint256,
+    /// Total shares of the stream after the subscription
+    pub total_shares: Uint256,
+    /// Projected `token_out` allocation of the amount at the current streamed price,
+    /// zero if nothing has been streamed yet
+    pub projected_out: Uint256,
+    /// In amount left to spend before the hard cap ends the stream, if the stream has one
+    pub hard_cap_remaining: Option<Uint256>,
+}
+
+#[cw_serde]
+pub struct SimulateWithdrawResponse {
+    pub withdraw_amount: Uint256,
+    /// Shares burned for the withdrawal
+    pub shares: Uint256,
+    pub rounding: ShareRounding,
+    /// In balance of the position after the withdrawal
+    pub in_balance: Uint256,
+    /// Shares of the position after the withdrawal
+    pub remaining_shares: Uint256,
+    /// Total purchased `token_out` of the synced position
+    pub purchased: Uint256,
+    /// Total spent `token_in` of the synced position
+    pub spent: Uint256,
+}
+
 #[cw_serde]
 pub struct AveragePriceResponse {
     pub average_price: Decimal256,
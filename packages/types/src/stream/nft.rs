@@ -0,0 +1,99 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{to_json_binary, Binary, CosmosMsg, Empty, StdResult, WasmMsg};
+use cw_utils::Expiration;
+
+/// Enables cw721 positions. Each position is represented by a token minted on the first
+/// subscription, and transferring the token transfers the position.
+#[cw_serde]
+pub struct PositionNftConfig {
+    /// Name of the position collection
+    pub name: String,
+    /// Symbol of the position collection
+    pub symbol: String,
+}
+
+/// Cw721ReceiveMsg is sent to the recipient contract of a `SendNft`.
+#[cw_serde]
+pub struct Cw721ReceiveMsg {
+    pub sender: String,
+    pub token_id: String,
+    pub msg: Binary,
+}
+
+impl Cw721ReceiveMsg {
+    pub fn into_cosmos_msg(self, contract_addr: String) -> StdResult<CosmosMsg> {
+        Ok(WasmMsg::Execute {
+            contract_addr,
+            msg: to_json_binary(&ReceiverExecuteMsg::ReceiveNft(self))?,
+            funds: vec![],
+        }
+        .into())
+    }
+}
+
+#[cw_serde]
+enum ReceiverExecuteMsg {
+    ReceiveNft(Cw721ReceiveMsg),
+}
+
+#[cw_serde]
+pub struct Approval {
+    /// Account that can transfer the token
+    pub spender: String,
+    /// When the approval expires
+    pub expires: Expiration,
+}
+
+#[cw_serde]
+pub struct OwnerOfResponse {
+    /// Owner of the token, which is also the owner of the position
+    pub owner: String,
+    /// Accounts that can transfer the token
+    pub approvals: Vec<Approval>,
+}
+
+#[cw_serde]
+pub struct ApprovalResponse {
+    pub approval: Approval,
+}
+
+#[cw_serde]
+pub struct ApprovalsResponse {
+    pub approvals: Vec<Approval>,
+}
+
+#[cw_serde]
+pub struct OperatorsResponse {
+    /// Accounts that can transfer every token of the owner
+    pub operators: Vec<Approval>,
+}
+
+#[cw_serde]
+pub struct AllNftInfoResponse {
+    /// Owner and approvals of the token
+    pub access: OwnerOfResponse,
+    /// Metadata of the token
+    pub info: NftInfoResponse,
+}
+
+#[cw_serde]
+pub struct NftInfoResponse {
+    pub token_uri: Option<String>,
+    pub extension: Empty,
+}
+
+#[cw_serde]
+pub struct NumTokensResponse {
+    pub count: u64,
+}
+
+#[cw_serde]
+pub struct ContractInfoResponse {
+    pub name: String,
+    pub symbol: String,
+}
+
+#[cw_serde]
+pub struct TokensResponse {
+    pub tokens: Vec<String>,
+}
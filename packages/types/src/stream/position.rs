@@ -18,8 +18,16 @@ pub struct Position {
     pub pending_purchase: Decimal256,
     // Total amount of `token_in` spent tokens at latest calculation
     pub spent: Uint256,
+    // Total amount of `token_out` claimed during the stream
+    pub claimed: Uint256,
+    // Amount of `token_in` spent for the claimed `token_out`
+    pub claimed_spent: Uint256,
     // Exit date of the position
     pub exit_date: Timestamp,
+    // Token id of the position, set if the stream represents positions as cw721 tokens
+    pub token_id: Option<String>,
+    // Address that referred the position, receives a share of the exit fee
+    pub referrer: Option<Addr>,
 }
 
 impl Position {
@@ -29,6 +37,8 @@ impl Position {
         shares: Uint256,
         index: Option<Decimal256>,
         last_updated: Timestamp,
+        token_id: Option<String>,
+        referrer: Option<Addr>,
     ) -> Self {
         Position {
             owner,
@@ -39,7 +49,34 @@ impl Position {
             purchased: Uint256::zero(),
             pending_purchase: Decimal256::zero(),
             spent: Uint256::zero(),
+            claimed: Uint256::zero(),
+            claimed_spent: Uint256::zero(),
             exit_date: Timestamp::from_nanos(0),
+            token_id,
+            referrer,
         }
     }
 }
+
+/// Amounts paid to the exited positions and their referrers, the dust sweep keeps the amounts
+/// still owed to the positions that did not exit
+#[cw_serde]
+#[derive(Default)]
+pub struct ExitTotals {
+    // Total amount of `token_in` paid to the exited positions
+    pub in_paid: Uint256,
+    // Total amount of `token_out` paid or vested to the exited positions
+    pub out_paid: Uint256,
+    // Total referral fees paid in `token_in`
+    pub referral_fees_paid: Uint256,
+}
+
+/// Referral totals of a referrer
+#[cw_serde]
+#[derive(Default)]
+pub struct Referral {
+    // Number of positions referred
+    pub referred_positions: u64,
+    // Total referral fees paid in `token_in`
+    pub fees_paid: Uint256,
+}
@@ -1,6 +1,8 @@
 use crate::controller::{PoolConfig, VestingConfig};
+use crate::stream::{Asset, EmissionCurve};
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Coin, Decimal256, Timestamp, Uint256};
+use cosmwasm_std::{Addr, Decimal256, Timestamp, Uint256};
+use cw_denom::CheckedDenom;
 use streamswap_utils::to_uint256;
 
 /// Active stream status information
@@ -10,8 +12,8 @@ pub struct StreamState {
     pub dist_index: Decimal256,
     /// Remaining out asset to be distributed
     pub out_remaining: Uint256,
-    /// In denom of the stream
-    pub in_denom: String,
+    /// In denom of the stream, native or cw20
+    pub in_denom: CheckedDenom,
     /// In supply of the stream
     pub in_supply: Uint256,
     /// Spent in of the stream, the total amount of in assets spent
@@ -21,23 +23,42 @@ pub struct StreamState {
     pub shares: Uint256,
     /// Current streamed price, the price of in asset in out asset
     pub current_streamed_price: Decimal256,
-    /// Out asset of the stream
-    pub out_asset: Coin,
+    /// Out asset of the stream, native or cw20
+    pub out_asset: Asset,
     /// Status info of the stream
     pub status_info: StatusInfo,
     /// Threshold amount of the stream
     pub threshold: Option<Uint256>,
+    /// Minimum average price of the stream, spent_in / sold out asset
+    pub min_average_price: Option<Decimal256>,
+    /// Emission curve of the out asset
+    pub emission_curve: EmissionCurve,
+    /// Maximum amount of in asset a single address can subscribe with
+    pub max_in_per_address: Option<Uint256>,
+    /// Maximum amount of in asset subscribed to the stream, spent_in + in_supply can not exceed it
+    pub max_in_supply: Option<Uint256>,
+    /// Hard cap on spent_in, the stream is ended as soon as it is reached
+    pub hard_cap: Option<Uint256>,
+    /// Out asset already claimed by subscribers during the stream
+    pub claimed_out: Uint256,
+    /// In asset spent for the claimed out asset, owed to the treasury even if the stream is cancelled
+    pub claimed_spent_in: Uint256,
 }
 
 impl StreamState {
     pub fn new(
         now: Timestamp,
-        out_asset: Coin,
-        in_denom: String,
+        out_asset: Asset,
+        in_denom: CheckedDenom,
         bootstrapping_start_time: Timestamp,
         start_time: Timestamp,
         end_time: Timestamp,
         threshold: Option<Uint256>,
+        min_average_price: Option<Decimal256>,
+        emission_curve: EmissionCurve,
+        max_in_per_address: Option<Uint256>,
+        max_in_supply: Option<Uint256>,
+        hard_cap: Option<Uint256>,
     ) -> Self {
         StreamState {
             dist_index: Decimal256::zero(),
@@ -50,6 +71,13 @@ impl StreamState {
             current_streamed_price: Decimal256::zero(),
             status_info: StatusInfo::new(now, bootstrapping_start_time, start_time, end_time),
             threshold,
+            min_average_price,
+            emission_curve,
+            max_in_per_address,
+            max_in_supply,
+            hard_cap,
+            claimed_out: Uint256::zero(),
+            claimed_spent_in: Uint256::zero(),
         }
     }
 
@@ -58,8 +86,7 @@ impl StreamState {
     }
 
     pub fn is_finalized(&self) -> bool {
-        self.status_info.status == Status::Finalized(FinalizedStatus::ThresholdReached)
-            || self.status_info.status == Status::Finalized(FinalizedStatus::ThresholdNotReached)
+        matches!(self.status_info.status, Status::Finalized(_))
     }
 
     pub fn is_waiting(&self) -> bool {
@@ -77,12 +104,33 @@ impl StreamState {
     pub fn is_ended(&self) -> bool {
         self.status_info.status == Status::Ended
     }
+
+    pub fn is_paused(&self) -> bool {
+        self.status_info.status == Status::Paused
+    }
     pub fn check_threshold(&self) -> bool {
         match self.threshold {
             Some(threshold) => self.spent_in >= threshold,
             None => true,
         }
     }
+
+    pub fn is_hard_cap_reached(&self) -> bool {
+        self.hard_cap
+            .is_some_and(|hard_cap| self.spent_in >= hard_cap)
+    }
+
+    /// Average price is evaluated on the sold out asset, a stream with nothing sold does not reach
+    /// the minimum average price.
+    pub fn check_min_average_price(&self) -> bool {
+        match self.min_average_price {
+            Some(min_average_price) => {
+                let sold = to_uint256(self.out_asset.amount).saturating_sub(self.out_remaining);
+                !sold.is_zero() && Decimal256::from_ratio(self.spent_in, sold) >= min_average_price
+            }
+            None => true,
+        }
+    }
 }
 
 #[cw_serde]
@@ -108,6 +156,13 @@ impl StreamInfo {
     }
 }
 
+#[cw_serde]
+pub struct TreasuryRecipient {
+    pub address: Addr,
+    pub weight: Decimal256,
+    pub vesting: Option<VestingConfig>,
+}
+
 #[cw_serde]
 pub struct PostStreamActions {
     /// Pool Configuration for the pre stream
@@ -141,6 +196,9 @@ pub enum Status {
     Bootstrapping,
     /// Active status is when the stream is active. In this status, spending is allowed on each side.
     Active,
+    /// Paused status is when the protocol admin pauses an active stream.
+    /// In this status, no distribution happens. End time is extended by the paused duration on resume.
+    Paused,
     /// Ended status is when the stream is ended.
     /// In this status, Subscriber can exit the stream, creator can finalize and collect accumulated in assets.
     Ended,
@@ -152,13 +210,15 @@ pub enum Status {
     Cancelled,
 }
 
-/// Represents whether the stream's threshold was reached or not in the finalized state.
+/// Represents whether the stream's finalization conditions were reached or not in the finalized state.
 #[cw_serde]
 pub enum FinalizedStatus {
     /// Indicates that the stream's threshold was reached.
     ThresholdReached,
     /// Indicates that the stream's threshold was not reached.
     ThresholdNotReached,
+    /// Indicates that the stream's threshold was reached but the minimum average price was not.
+    MinAveragePriceNotReached,
 }
 
 impl std::fmt::Display for FinalizedStatus {
@@ -166,6 +226,7 @@ impl std::fmt::Display for FinalizedStatus {
         match self {
             FinalizedStatus::ThresholdReached => write!(f, "ThresholdReached"),
             FinalizedStatus::ThresholdNotReached => write!(f, "ThresholdNotReached"),
+            FinalizedStatus::MinAveragePriceNotReached => write!(f, "MinAveragePriceNotReached"),
         }
     }
 }
@@ -176,6 +237,7 @@ impl std::fmt::Display for Status {
             Status::Waiting => write!(f, "Waiting"),
             Status::Bootstrapping => write!(f, "Bootstrapping"),
             Status::Active => write!(f, "Active"),
+            Status::Paused => write!(f, "Paused"),
             Status::Ended => write!(f, "Ended"),
             Status::Cancelled => write!(f, "Cancelled"),
             Status::Finalized(finalized_status) => write!(f, "Finalized({})", finalized_status),
@@ -195,6 +257,10 @@ pub struct StatusInfo {
     pub end_time: Timestamp,
     /// Last updated time of the status info
     pub last_updated: Timestamp,
+    /// Time the stream was paused at, set only while the stream is paused
+    pub paused_at: Option<Timestamp>,
+    /// Total paused duration of the stream in nanos
+    pub paused_nanos: u64,
 }
 
 impl StatusInfo {
@@ -210,6 +276,8 @@ impl StatusInfo {
             start_time,
             end_time,
             last_updated: now,
+            paused_at: None,
+            paused_nanos: 0,
         }
     }
 }
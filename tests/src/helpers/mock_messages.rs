@@ -1,9 +1,13 @@
 use super::suite::TestAccounts;
 use cosmwasm_std::{Binary, Coin, Decimal256, Timestamp, Uint256};
-use streamswap_types::controller::{CreateStreamMsg, PoolConfig, VestingConfig};
+use cw_denom::CheckedDenom;
+use streamswap_types::controller::{
+    CreateStreamMsg, FeeCollectorMsg, PoolConfig, TreasuryRecipientMsg, VestingConfig,
+};
 use streamswap_types::controller::{
     ExecuteMsg as ControllerExecuteMsg, InstantiateMsg as ControllerInstantiateMsg,
 };
+use streamswap_types::stream::{Asset, EmissionCurve, PositionNftConfig};
 
 #[allow(dead_code)]
 pub fn get_controller_inst_msg(
@@ -15,17 +19,22 @@ pub fn get_controller_inst_msg(
         stream_contract_code_id,
         vesting_code_id,
         protocol_admin: Some(test_accounts.admin.to_string()),
-        fee_collector: Some(test_accounts.admin.to_string()),
+        fee_collectors: Some(vec![FeeCollectorMsg {
+            address: test_accounts.admin.to_string(),
+            weight: Decimal256::one(),
+        }]),
         stream_creation_fee: Coin {
             denom: "fee_denom".to_string(),
             amount: 100u128.into(),
         },
         exit_fee_percent: Decimal256::percent(1),
+        referral_fee_percent: Decimal256::zero(),
         accepted_in_denoms: vec!["in_denom".to_string()],
         min_waiting_duration: 49,
         min_bootstrapping_duration: 49,
         min_stream_duration: 99,
         tos_version: "v1".to_string(),
+        dust_sweep_grace_period: 1_000,
     }
 }
 
@@ -33,15 +42,24 @@ pub struct CreateStreamMsgBuilder {
     name: String,
     url: Option<String>,
     treasury: String,
-    out_asset: Coin,
-    in_denom: String,
+    out_asset: Asset,
+    in_denom: CheckedDenom,
     bootstrapping_start_time: Timestamp,
     start_time: Timestamp,
     end_time: Timestamp,
     threshold: Option<Uint256>,
+    min_average_price: Option<Decimal256>,
+    emission_curve: Option<EmissionCurve>,
+    max_in_per_address: Option<Uint256>,
+    max_in_supply: Option<Uint256>,
+    hard_cap: Option<Uint256>,
+    merkle_root: Option<String>,
+    position_nft: Option<PositionNftConfig>,
     pool_config: Option<PoolConfig>,
     subscriber_vesting: Option<VestingConfig>,
     creator_vesting: Option<VestingConfig>,
+    hooks: Option<Vec<String>>,
+    treasury_recipients: Option<Vec<TreasuryRecipientMsg>>,
     salt: Binary,
     tos_version: String,
 }
@@ -61,20 +79,39 @@ impl CreateStreamMsgBuilder {
             name: name.to_string(),
             url: None,
             treasury: treasury.to_string(),
-            out_asset,
-            in_denom: in_denom.to_string(),
+            out_asset: out_asset.into(),
+            in_denom: CheckedDenom::Native(in_denom.to_string()),
             bootstrapping_start_time,
             start_time,
             end_time,
             threshold: None,
+            min_average_price: None,
+            emission_curve: None,
+            max_in_per_address: None,
+            max_in_supply: None,
+            hard_cap: None,
+            merkle_root: None,
+            position_nft: None,
             pool_config: None,
             subscriber_vesting: None,
             creator_vesting: None,
+            hooks: None,
+            treasury_recipients: None,
             salt: Binary::from_base64("salt").unwrap(),
             tos_version: "v1".to_string(),
         }
     }
 
+    pub fn out_asset(mut self, out_asset: Asset) -> Self {
+        self.out_asset = out_asset;
+        self
+    }
+
+    pub fn in_denom(mut self, in_denom: CheckedDenom) -> Self {
+        self.in_denom = in_denom;
+        self
+    }
+
     pub fn url(mut self, url: String) -> Self {
         self.url = Some(url);
         self
@@ -85,6 +122,44 @@ impl CreateStreamMsgBuilder {
         self
     }
 
+    pub fn min_average_price(mut self, min_average_price: Decimal256) -> Self {
+        self.min_average_price = Some(min_average_price);
+        self
+    }
+
+    pub fn emission_curve(mut self, emission_curve: EmissionCurve) -> Self {
+        self.emission_curve = Some(emission_curve);
+        self
+    }
+
+    pub fn max_in_per_address(mut self, max_in_per_address: Uint256) -> Self {
+        self.max_in_per_address = Some(max_in_per_address);
+        self
+    }
+
+    pub fn max_in_supply(mut self, max_in_supply: Uint256) -> Self {
+        self.max_in_supply = Some(max_in_supply);
+        self
+    }
+
+    pub fn hard_cap(mut self, hard_cap: Uint256) -> Self {
+        self.hard_cap = Some(hard_cap);
+        self
+    }
+
+    pub fn merkle_root(mut self, merkle_root: String) -> Self {
+        self.merkle_root = Some(merkle_root);
+        self
+    }
+
+    pub fn position_nft(mut self, name: &str, symbol: &str) -> Self {
+        self.position_nft = Some(PositionNftConfig {
+            name: name.to_string(),
+            symbol: symbol.to_string(),
+        });
+        self
+    }
+
     pub fn pool_config(mut self, pool_config: PoolConfig) -> Self {
         self.pool_config = Some(pool_config);
         self
@@ -100,6 +175,16 @@ impl CreateStreamMsgBuilder {
         self
     }
 
+    pub fn hooks(mut self, hooks: Vec<String>) -> Self {
+        self.hooks = Some(hooks);
+        self
+    }
+
+    pub fn treasury_recipients(mut self, treasury_recipients: Vec<TreasuryRecipientMsg>) -> Self {
+        self.treasury_recipients = Some(treasury_recipients);
+        self
+    }
+
     #[allow(dead_code)]
     pub fn salt(mut self, salt: Binary) -> Self {
         self.salt = salt;
@@ -117,6 +202,7 @@ impl CreateStreamMsgBuilder {
             msg: Box::new(CreateStreamMsg {
                 bootstraping_start_time: self.bootstrapping_start_time,
                 treasury: self.treasury.clone(),
+                treasury_recipients: self.treasury_recipients,
                 stream_admin: self.treasury,
                 name: self.name,
                 url: self.url,
@@ -125,6 +211,14 @@ impl CreateStreamMsgBuilder {
                 start_time: self.start_time,
                 end_time: self.end_time,
                 threshold: self.threshold,
+                min_average_price: self.min_average_price,
+                emission_curve: self.emission_curve,
+                max_in_per_address: self.max_in_per_address,
+                max_in_supply: self.max_in_supply,
+                hard_cap: self.hard_cap,
+                merkle_root: self.merkle_root,
+                position_nft: self.position_nft,
+                hooks: self.hooks,
                 pool_config: self.pool_config,
                 subscriber_vesting: self.subscriber_vesting,
                 creator_vesting: self.creator_vesting,
@@ -12,7 +12,7 @@ use cw_multi_test::{
 pub const PREFIX: &str = "cosmwasm";
 
 #[allow(dead_code)]
-type AppType = App<
+pub(crate) type AppType = App<
     BankKeeper,
     MockApiBech32,
     MockStorage,
@@ -39,7 +39,6 @@ pub(crate) struct SuiteBuilder {}
 
 impl SuiteBuilder {
     #[allow(dead_code)]
-
     pub fn build(self) -> Suite {
         let denoms = [
             "fee_denom".to_string(),
@@ -68,16 +67,23 @@ impl SuiteBuilder {
             time: Timestamp::from_seconds(1_000),
         });
 
-        let stream_swap_controller_contract = Box::new(ContractWrapper::new(
-            streamswap_controller::contract::execute,
-            streamswap_controller::contract::instantiate,
-            streamswap_controller::contract::query,
-        ));
-        let stream_swap_contract = Box::new(ContractWrapper::new(
-            streamswap_stream::contract::execute,
-            streamswap_stream::contract::instantiate,
-            streamswap_stream::contract::query,
-        ));
+        let stream_swap_controller_contract = Box::new(
+            ContractWrapper::new(
+                streamswap_controller::contract::execute,
+                streamswap_controller::contract::instantiate,
+                streamswap_controller::contract::query,
+            )
+            .with_migrate(streamswap_controller::contract::migrate),
+        );
+        let stream_swap_contract = Box::new(
+            ContractWrapper::new(
+                streamswap_stream::contract::execute,
+                streamswap_stream::contract::instantiate,
+                streamswap_stream::contract::query,
+            )
+            .with_reply(streamswap_stream::contract::reply)
+            .with_migrate(streamswap_stream::contract::migrate),
+        );
         let vesting_contract = Box::new(ContractWrapper::new(
             cw_vesting::contract::execute,
             cw_vesting::contract::instantiate,
@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use cosmwasm_std::{Coin, Uint128};
+use cosmwasm_std::{Addr, Coin, Uint128};
 use cw_multi_test::AppResponse;
 
 #[allow(dead_code)]
@@ -65,3 +65,17 @@ pub fn get_wasm_attribute_with_key(res: AppResponse, key: String) -> String {
         "".to_string()
     }
 }
+
+/// Raw key of a contract storage entry in the wasm module of the test app, used to write state
+/// in the format of previous contract versions
+#[allow(dead_code)]
+pub fn contract_storage_key(contract: &Addr, key: &[u8]) -> Vec<u8> {
+    let contract_namespace = format!("contract_data/{}", contract);
+    let mut storage_key = vec![];
+    for namespace in [b"wasm".as_slice(), contract_namespace.as_bytes()] {
+        storage_key.extend_from_slice(&(namespace.len() as u16).to_be_bytes());
+        storage_key.extend_from_slice(namespace);
+    }
+    storage_key.extend_from_slice(key);
+    storage_key
+}
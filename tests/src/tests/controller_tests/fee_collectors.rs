@@ -0,0 +1,143 @@
+#![cfg(test)]
+use crate::helpers::mock_messages::CreateStreamMsgBuilder;
+use crate::helpers::suite::SuiteBuilder;
+use crate::helpers::utils::{get_contract_address_from_res, get_funds_from_res};
+use crate::helpers::{mock_messages::get_controller_inst_msg, suite::Suite};
+use cosmwasm_std::{coin, Addr, BlockInfo, Coin, Decimal256, Uint128};
+use cw_multi_test::Executor;
+use std::str::FromStr;
+use streamswap_controller::error::ContractError as ControllerError;
+use streamswap_types::controller::FeeCollectorMsg;
+use streamswap_types::stream::ExecuteMsg as StreamSwapExecuteMsg;
+
+#[test]
+fn fees_split_between_collectors() {
+    let Suite {
+        mut app,
+        test_accounts,
+        stream_swap_code_id,
+        stream_swap_controller_code_id,
+        vesting_code_id,
+    } = SuiteBuilder::default().build();
+
+    let collectors = |first: &str, second: &str| {
+        Some(vec![
+            FeeCollectorMsg {
+                address: test_accounts.admin.to_string(),
+                weight: Decimal256::from_str(first).unwrap(),
+            },
+            FeeCollectorMsg {
+                address: test_accounts.admin_2.to_string(),
+                weight: Decimal256::from_str(second).unwrap(),
+            },
+        ])
+    };
+
+    // Weights must sum up to 100%
+    let mut msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+    msg.fee_collectors = collectors("0.5", "0.4");
+    let err = app
+        .instantiate_contract(
+            stream_swap_controller_code_id,
+            test_accounts.admin.clone(),
+            &msg,
+            &[],
+            "Controller".to_string(),
+            None,
+        )
+        .unwrap_err();
+    let error = err.downcast_ref::<ControllerError>().unwrap();
+    assert_eq!(*error, ControllerError::InvalidFeeCollectors {});
+
+    msg.fee_collectors = collectors("0.55", "0.45");
+    let controller_address = app
+        .instantiate_contract(
+            stream_swap_controller_code_id,
+            test_accounts.admin.clone(),
+            &msg,
+            &[],
+            "Controller".to_string(),
+            None,
+        )
+        .unwrap();
+
+    let start_time = app.block_info().time.plus_seconds(100);
+    let end_time = app.block_info().time.plus_seconds(200);
+    let bootstrapping_start_time = app.block_info().time.plus_seconds(50);
+    let create_stream_msg = CreateStreamMsgBuilder::new(
+        "Stream Swap tests",
+        test_accounts.creator_1.as_ref(),
+        coin(1_000_000, "out_denom"),
+        "in_denom",
+        bootstrapping_start_time,
+        start_time,
+        end_time,
+    )
+    .build();
+    let res = app
+        .execute_contract(
+            test_accounts.creator_1.clone(),
+            controller_address.clone(),
+            &create_stream_msg,
+            &[coin(100, "fee_denom"), coin(1_000_000, "out_denom")],
+        )
+        .unwrap();
+    // Creation fee is split by weight
+    let funds = get_funds_from_res(res.clone());
+    for (collector, amount) in [(&test_accounts.admin, 55), (&test_accounts.admin_2, 45)] {
+        assert!(funds.contains(&(
+            collector.to_string(),
+            Coin {
+                denom: "fee_denom".to_string(),
+                amount: Uint128::new(amount)
+            }
+        )));
+    }
+    let stream_swap_contract_address = get_contract_address_from_res(res);
+
+    app.set_block(BlockInfo {
+        height: 1_100,
+        time: start_time,
+        chain_id: "test".to_string(),
+    });
+    app.execute_contract(
+        test_accounts.subscriber_1.clone(),
+        Addr::unchecked(stream_swap_contract_address.clone()),
+        &StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        },
+        &[coin(1_050, "in_denom")],
+    )
+    .unwrap();
+
+    app.set_block(BlockInfo {
+        height: 1_200,
+        time: end_time,
+        chain_id: "test".to_string(),
+    });
+    let res = app
+        .execute_contract(
+            test_accounts.creator_1.clone(),
+            Addr::unchecked(stream_swap_contract_address),
+            &StreamSwapExecuteMsg::FinalizeStream {
+                new_treasury: None,
+                create_pool: None,
+                salt: None,
+            },
+            &[],
+        )
+        .unwrap();
+    // Swap fee of 10 is split 5.5 / 4.5, rounding remainder goes to the first collector
+    let funds = get_funds_from_res(res);
+    for (collector, amount) in [(&test_accounts.admin, 6), (&test_accounts.admin_2, 4)] {
+        assert!(funds.contains(&(
+            collector.to_string(),
+            Coin {
+                denom: "in_denom".to_string(),
+                amount: Uint128::new(amount)
+            }
+        )));
+    }
+}
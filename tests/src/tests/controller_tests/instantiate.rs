@@ -3,8 +3,8 @@ use crate::helpers::suite::SuiteBuilder;
 use crate::helpers::{mock_messages::get_controller_inst_msg, suite::Suite};
 use cosmwasm_std::{coin, Decimal256};
 use cw_multi_test::Executor;
-use streamswap_types::controller::Params;
 use streamswap_types::controller::QueryMsg;
+use streamswap_types::controller::{FeeCollector, Params};
 
 #[test]
 fn controller_proper_instantiate() {
@@ -38,7 +38,13 @@ fn controller_proper_instantiate() {
     assert_eq!(res.exit_fee_percent, Decimal256::percent(1));
     assert_eq!(res.stream_contract_code_id, stream_swap_code_id);
     assert_eq!(res.accepted_in_denoms, vec!["in_denom".to_string()]);
-    assert_eq!(res.fee_collector, test_accounts.admin.to_string());
+    assert_eq!(
+        res.fee_collectors,
+        vec![FeeCollector {
+            address: test_accounts.admin.clone(),
+            weight: Decimal256::one(),
+        }]
+    );
     assert_eq!(res.min_waiting_duration, 49);
     assert_eq!(res.min_bootstrapping_duration, 49);
     assert_eq!(res.min_stream_duration, 99);
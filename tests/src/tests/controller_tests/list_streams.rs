@@ -5,7 +5,10 @@ use crate::helpers::utils::get_wasm_attribute_with_key;
 use cosmwasm_std::coin;
 use cosmwasm_std::Binary;
 use cw_multi_test::Executor;
-use streamswap_types::controller::{QueryMsg, StreamsResponse};
+use streamswap_types::controller::{
+    QueryMsg, StreamResponse, StreamWindow, StreamsFilter, StreamsResponse,
+};
+use streamswap_types::stream::Status;
 
 #[test]
 fn test_list_streams() {
@@ -86,6 +89,7 @@ fn test_list_streams() {
             &QueryMsg::ListStreams {
                 start_after: None,
                 limit: None,
+                filter: None,
             },
         )
         .unwrap();
@@ -97,3 +101,145 @@ fn test_list_streams() {
     assert_eq!(res.streams[1].id, 2);
     assert_eq!(res.streams[1].address, stream_addr2);
 }
+
+#[test]
+fn test_list_streams_filters() {
+    let Suite {
+        mut app,
+        test_accounts,
+        stream_swap_code_id,
+        stream_swap_controller_code_id,
+        vesting_code_id,
+    } = SuiteBuilder::default().build();
+
+    let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+    let controller_address = app
+        .instantiate_contract(
+            stream_swap_controller_code_id,
+            test_accounts.admin.clone(),
+            &msg,
+            &[],
+            "Controller".to_string(),
+            None,
+        )
+        .unwrap();
+
+    let now = app.block_info().time;
+    let mut stream_addrs = vec![];
+    for (creator, out_denom, offset, salt) in [
+        (&test_accounts.creator_1, "out_denom", 0, 1u8),
+        (&test_accounts.creator_2, "wrong_denom", 200, 2u8),
+    ] {
+        let create_stream_msg = CreateStreamMsgBuilder::new(
+            "stream",
+            creator.as_ref(),
+            coin(100, out_denom),
+            "in_denom",
+            now.plus_seconds(offset + 50),
+            now.plus_seconds(offset + 100),
+            now.plus_seconds(offset + 200),
+        )
+        .salt(Binary::from(vec![salt; 32]))
+        .build();
+        let res = app
+            .execute_contract(
+                creator.clone(),
+                controller_address.clone(),
+                &create_stream_msg,
+                &[coin(100, "fee_denom"), coin(100, out_denom)],
+            )
+            .unwrap();
+        stream_addrs.push(get_wasm_attribute_with_key(
+            res,
+            "stream_contract_addr".to_string(),
+        ));
+    }
+
+    let list_streams = |start_after: Option<u64>, filter: StreamsFilter| -> Vec<u64> {
+        let res: StreamsResponse = app
+            .wrap()
+            .query_wasm_smart(
+                controller_address.clone(),
+                &QueryMsg::ListStreams {
+                    start_after,
+                    limit: None,
+                    filter: Some(filter),
+                },
+            )
+            .unwrap();
+        res.streams.iter().map(|stream| stream.id).collect()
+    };
+
+    let by_creator = StreamsFilter {
+        creator: Some(test_accounts.creator_2.to_string()),
+        ..Default::default()
+    };
+    assert_eq!(list_streams(None, by_creator), vec![2]);
+    let by_out_denom = StreamsFilter {
+        out_denom: Some("wrong_denom".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(list_streams(None, by_out_denom), vec![2]);
+    let by_creator_and_in_denom = StreamsFilter {
+        creator: Some(test_accounts.creator_1.to_string()),
+        in_denom: Some("in_denom".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(list_streams(None, by_creator_and_in_denom), vec![1]);
+    let by_in_denom = StreamsFilter {
+        in_denom: Some("in_denom".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(list_streams(None, by_in_denom.clone()), vec![1, 2]);
+    assert_eq!(list_streams(Some(1), by_in_denom), vec![2]);
+
+    // Time windows are evaluated against the current block time
+    app.update_block(|b| b.time = now.plus_seconds(150));
+    let by_window = |window| StreamsFilter {
+        window: Some(window),
+        ..Default::default()
+    };
+    let list_streams = |filter: StreamsFilter| -> Vec<u64> {
+        let res: StreamsResponse = app
+            .wrap()
+            .query_wasm_smart(
+                controller_address.clone(),
+                &QueryMsg::ListStreams {
+                    start_after: None,
+                    limit: None,
+                    filter: Some(filter),
+                },
+            )
+            .unwrap();
+        res.streams.iter().map(|stream| stream.id).collect()
+    };
+    assert_eq!(list_streams(by_window(StreamWindow::Active)), vec![1]);
+    assert_eq!(list_streams(by_window(StreamWindow::Upcoming)), vec![2]);
+    assert!(list_streams(by_window(StreamWindow::Ended)).is_empty());
+
+    // Reverse lookup from the stream address
+    let stream: StreamResponse = app
+        .wrap()
+        .query_wasm_smart(
+            controller_address,
+            &QueryMsg::StreamByAddress {
+                address: stream_addrs[1].clone(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        stream,
+        StreamResponse {
+            id: 2,
+            address: stream_addrs[1].clone(),
+            code_id: stream_swap_code_id,
+            creator: test_accounts.creator_2.to_string(),
+            in_denom: "in_denom".to_string(),
+            out_denom: "wrong_denom".to_string(),
+            name: "stream".to_string(),
+            start_time: now.plus_seconds(300),
+            end_time: now.plus_seconds(400),
+            status: Status::Waiting,
+        }
+    );
+}
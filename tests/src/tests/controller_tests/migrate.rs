@@ -0,0 +1,227 @@
+#![cfg(test)]
+use crate::helpers::mock_messages::{get_controller_inst_msg, CreateStreamMsgBuilder};
+use crate::helpers::suite::{Suite, SuiteBuilder};
+use crate::helpers::utils::{contract_storage_key, get_wasm_attribute_with_key};
+use cosmwasm_std::{coin, to_json_vec, Addr, Decimal256, Order, Storage};
+use cw_multi_test::Executor;
+use cw_storage_plus::Map;
+use streamswap_types::controller::{
+    ExecuteMsg, LegacyParams, MigrateMsg, Params, QueryMsg, StreamResponse, StreamsResponse,
+    LEGACY_DUST_SWEEP_GRACE_PERIOD,
+};
+use streamswap_types::stream::Status;
+
+#[test]
+fn test_migrate_backfills_stream_records() {
+    let Suite {
+        mut app,
+        test_accounts,
+        stream_swap_code_id,
+        stream_swap_controller_code_id,
+        vesting_code_id,
+    } = SuiteBuilder::default().build();
+
+    let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+    let controller_address = app
+        .instantiate_contract(
+            stream_swap_controller_code_id,
+            test_accounts.admin.clone(),
+            &msg,
+            &[],
+            "Controller".to_string(),
+            Some(test_accounts.admin.to_string()),
+        )
+        .unwrap();
+
+    let now = app.block_info().time;
+    let create_stream_msg = CreateStreamMsgBuilder::new(
+        "stream",
+        test_accounts.creator_1.as_ref(),
+        coin(100, "out_denom"),
+        "in_denom",
+        now.plus_seconds(50),
+        now.plus_seconds(100),
+        now.plus_seconds(200),
+    )
+    .build();
+    let res = app
+        .execute_contract(
+            test_accounts.creator_1.clone(),
+            controller_address.clone(),
+            &create_stream_msg,
+            &[coin(100, "fee_denom"), coin(100, "out_denom")],
+        )
+        .unwrap();
+    let stream_address = Addr::unchecked(get_wasm_attribute_with_key(
+        res,
+        "stream_contract_addr".to_string(),
+    ));
+
+    // Previous versions did not record the contract version, stored a single fee collector and
+    // only stored the stream address by id
+    app.storage_mut()
+        .remove(&contract_storage_key(&controller_address, b"contract_info"));
+    let params: Params = app
+        .wrap()
+        .query_wasm_smart(controller_address.clone(), &QueryMsg::Params {})
+        .unwrap();
+    let legacy_params = LegacyParams {
+        protocol_admin: params.protocol_admin.clone(),
+        fee_collector: params.fee_collectors[0].address.clone(),
+        stream_creation_fee: params.stream_creation_fee.clone(),
+        exit_fee_percent: params.exit_fee_percent,
+        stream_contract_code_id: params.stream_contract_code_id,
+        vesting_code_id: params.vesting_code_id,
+        accepted_in_denoms: params.accepted_in_denoms.clone(),
+        min_stream_duration: params.min_stream_duration,
+        min_bootstrapping_duration: params.min_bootstrapping_duration,
+        min_waiting_duration: params.min_waiting_duration,
+        tos_version: params.tos_version.clone(),
+    };
+    app.storage_mut().set(
+        &contract_storage_key(&controller_address, b"params"),
+        &to_json_vec(&legacy_params).unwrap(),
+    );
+    let record_prefixes: Vec<Vec<u8>> = [
+        "stream_records",
+        "stream_records__address",
+        "stream_records__creator",
+        "stream_records__in_denom",
+        "stream_records__out_denom",
+    ]
+    .iter()
+    .map(|namespace| {
+        let mut prefix = (namespace.len() as u16).to_be_bytes().to_vec();
+        prefix.extend_from_slice(namespace.as_bytes());
+        contract_storage_key(&controller_address, &prefix)
+    })
+    .collect();
+    let record_keys: Vec<Vec<u8>> = app
+        .storage()
+        .range(None, None, Order::Ascending)
+        .map(|(key, _)| key)
+        .filter(|key| record_prefixes.iter().any(|prefix| key.starts_with(prefix)))
+        .collect();
+    assert!(!record_keys.is_empty());
+    for key in record_keys {
+        app.storage_mut().remove(&key);
+    }
+    let legacy_key = Map::<u64, Addr>::new("streams").key(1);
+    app.storage_mut().set(
+        &contract_storage_key(&controller_address, &legacy_key),
+        &to_json_vec(&stream_address).unwrap(),
+    );
+    let res: StreamsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            controller_address.clone(),
+            &QueryMsg::ListStreams {
+                start_after: None,
+                limit: None,
+                filter: None,
+            },
+        )
+        .unwrap();
+    assert!(res.streams.is_empty());
+
+    let res = app
+        .migrate_contract(
+            test_accounts.admin.clone(),
+            controller_address.clone(),
+            &MigrateMsg {},
+            stream_swap_controller_code_id,
+        )
+        .unwrap();
+    assert_eq!(
+        get_wasm_attribute_with_key(res.clone(), "migrated_params".to_string()),
+        "true"
+    );
+    assert_eq!(
+        get_wasm_attribute_with_key(res, "pending_stream_backfill".to_string()),
+        "true"
+    );
+
+    // Backfill rebuilds the record and its indexes from the stream, callable by anyone
+    let res = app
+        .execute_contract(
+            test_accounts.wrong_user.clone(),
+            controller_address.clone(),
+            &ExecuteMsg::BackfillStreams { limit: Some(1) },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(
+        get_wasm_attribute_with_key(res.clone(), "backfilled_streams".to_string()),
+        "1"
+    );
+    assert_eq!(
+        get_wasm_attribute_with_key(res, "completed".to_string()),
+        "true"
+    );
+    let migrated_params: Params = app
+        .wrap()
+        .query_wasm_smart(controller_address.clone(), &QueryMsg::Params {})
+        .unwrap();
+    assert_eq!(
+        migrated_params,
+        Params {
+            referral_fee_percent: Decimal256::zero(),
+            dust_sweep_grace_period: LEGACY_DUST_SWEEP_GRACE_PERIOD,
+            ..params
+        }
+    );
+    let stream: StreamResponse = app
+        .wrap()
+        .query_wasm_smart(
+            controller_address.clone(),
+            &QueryMsg::StreamByAddress {
+                address: stream_address.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        stream,
+        StreamResponse {
+            id: 1,
+            address: stream_address.to_string(),
+            code_id: stream_swap_code_id,
+            creator: test_accounts.creator_1.to_string(),
+            in_denom: "in_denom".to_string(),
+            out_denom: "out_denom".to_string(),
+            name: "stream".to_string(),
+            start_time: now.plus_seconds(100),
+            end_time: now.plus_seconds(200),
+            status: Status::Waiting,
+        }
+    );
+
+    // Legacy registry is drained, there is nothing left to backfill
+    let res = app
+        .execute_contract(
+            test_accounts.wrong_user.clone(),
+            controller_address.clone(),
+            &ExecuteMsg::BackfillStreams { limit: None },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(
+        get_wasm_attribute_with_key(res, "backfilled_streams".to_string()),
+        "0"
+    );
+    let res = app
+        .migrate_contract(
+            test_accounts.admin.clone(),
+            controller_address,
+            &MigrateMsg {},
+            stream_swap_controller_code_id,
+        )
+        .unwrap();
+    assert_eq!(
+        get_wasm_attribute_with_key(res.clone(), "migrated_params".to_string()),
+        "false"
+    );
+    assert_eq!(
+        get_wasm_attribute_with_key(res, "pending_stream_backfill".to_string()),
+        "false"
+    );
+}
@@ -0,0 +1,161 @@
+#![cfg(test)]
+use crate::helpers::mock_messages::{get_controller_inst_msg, CreateStreamMsgBuilder};
+use crate::helpers::suite::{Suite, SuiteBuilder};
+use crate::helpers::utils::get_wasm_attribute_with_key;
+use cosmwasm_std::{coin, Addr, Binary};
+use cw_multi_test::{ContractWrapper, Executor};
+use streamswap_controller::error::ContractError as ControllerError;
+use streamswap_types::controller::{ExecuteMsg, QueryMsg, StreamSelection, StreamsResponse};
+use streamswap_types::stream::{QueryMsg as StreamSwapQueryMsg, StreamResponse};
+
+#[test]
+fn test_migrate_streams() {
+    let Suite {
+        mut app,
+        test_accounts,
+        stream_swap_code_id,
+        stream_swap_controller_code_id,
+        vesting_code_id,
+    } = SuiteBuilder::default().build();
+
+    let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+    let controller_address = app
+        .instantiate_contract(
+            stream_swap_controller_code_id,
+            test_accounts.admin.clone(),
+            &msg,
+            &[],
+            "Controller".to_string(),
+            None,
+        )
+        .unwrap();
+
+    let now = app.block_info().time;
+    let mut stream_addrs = vec![];
+    for salt in 1u8..=3 {
+        let create_stream_msg = CreateStreamMsgBuilder::new(
+            "stream",
+            test_accounts.creator_1.as_ref(),
+            coin(100, "out_denom"),
+            "in_denom",
+            now.plus_seconds(50),
+            now.plus_seconds(100),
+            now.plus_seconds(200),
+        )
+        .salt(Binary::from(vec![salt; 32]))
+        .build();
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address.clone(),
+                &create_stream_msg,
+                &[coin(100, "fee_denom"), coin(100, "out_denom")],
+            )
+            .unwrap();
+        stream_addrs.push(Addr::unchecked(get_wasm_attribute_with_key(
+            res,
+            "stream_contract_addr".to_string(),
+        )));
+    }
+
+    // Controller is the admin of the streams
+    let contract_info = app
+        .wrap()
+        .query_wasm_contract_info(stream_addrs[0].clone())
+        .unwrap();
+    assert_eq!(contract_info.admin, Some(controller_address.to_string()));
+
+    let new_code_id = app.store_code(Box::new(
+        ContractWrapper::new(
+            streamswap_stream::contract::execute,
+            streamswap_stream::contract::instantiate,
+            streamswap_stream::contract::query,
+        )
+        .with_reply(streamswap_stream::contract::reply)
+        .with_migrate(streamswap_stream::contract::migrate),
+    ));
+
+    // Only protocol admin can migrate streams
+    let migrate_msg = |selection| ExecuteMsg::MigrateStreams {
+        new_code_id,
+        selection,
+    };
+    let err = app
+        .execute_contract(
+            test_accounts.wrong_user.clone(),
+            controller_address.clone(),
+            &migrate_msg(StreamSelection::Ids {
+                stream_ids: vec![2],
+            }),
+            &[],
+        )
+        .unwrap_err();
+    let error = err.downcast_ref::<ControllerError>().unwrap();
+    assert_eq!(*error, ControllerError::Unauthorized {});
+
+    let res = app
+        .execute_contract(
+            test_accounts.admin.clone(),
+            controller_address.clone(),
+            &migrate_msg(StreamSelection::Ids {
+                stream_ids: vec![2],
+            }),
+            &[],
+        )
+        .unwrap();
+    assert_eq!(
+        get_wasm_attribute_with_key(res, "migrated_streams".to_string()),
+        "1"
+    );
+
+    // Already migrated streams are skipped
+    let res = app
+        .execute_contract(
+            test_accounts.admin.clone(),
+            controller_address.clone(),
+            &migrate_msg(StreamSelection::Range {
+                start_after: None,
+                limit: Some(2),
+            }),
+            &[],
+        )
+        .unwrap();
+    assert_eq!(
+        get_wasm_attribute_with_key(res, "migrated_streams".to_string()),
+        "1"
+    );
+
+    let code_ids: Vec<u64> = stream_addrs
+        .iter()
+        .map(|stream_addr| {
+            app.wrap()
+                .query_wasm_contract_info(stream_addr.clone())
+                .unwrap()
+                .code_id
+        })
+        .collect();
+    assert_eq!(
+        code_ids,
+        vec![new_code_id, new_code_id, stream_swap_code_id]
+    );
+    let res: StreamsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            controller_address,
+            &QueryMsg::ListStreams {
+                start_after: None,
+                limit: None,
+                filter: None,
+            },
+        )
+        .unwrap();
+    let recorded: Vec<u64> = res.streams.iter().map(|stream| stream.code_id).collect();
+    assert_eq!(recorded, code_ids);
+
+    // Migrated stream keeps its state
+    let stream: StreamResponse = app
+        .wrap()
+        .query_wasm_smart(stream_addrs[0].clone(), &StreamSwapQueryMsg::Stream {})
+        .unwrap();
+    assert_eq!(stream.name, "stream");
+}
@@ -1,4 +1,9 @@
 mod controller_freeze;
+mod fee_collectors;
 mod instantiate;
 mod list_streams;
+mod migrate;
+mod migrate_streams;
 mod params_update;
+mod protocol_stats;
+mod roles;
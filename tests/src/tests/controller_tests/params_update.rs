@@ -1,10 +1,11 @@
 #![cfg(test)]
 use crate::helpers::suite::SuiteBuilder;
+use crate::helpers::utils::get_wasm_attribute_with_key;
 use crate::helpers::{mock_messages::get_controller_inst_msg, suite::Suite};
 use cosmwasm_std::{coin, Decimal256};
-use cw_multi_test::Executor;
+use cw_multi_test::{ContractWrapper, Executor};
 use streamswap_controller::error::ContractError as ControllerError;
-use streamswap_types::controller::{ExecuteMsg, Params, QueryMsg};
+use streamswap_types::controller::{ExecuteMsg, FeeCollector, FeeCollectorMsg, Params, QueryMsg};
 
 #[test]
 fn params_update() {
@@ -32,11 +33,16 @@ fn params_update() {
     let msg = ExecuteMsg::UpdateParams {
         stream_creation_fee: Some(coin(100, "fee_denom")),
         exit_fee_percent: None,
+        referral_fee_percent: None,
         accepted_in_denoms: None,
-        fee_collector: None,
+        fee_collectors: None,
         min_bootstrapping_duration: None,
         min_waiting_duration: None,
         min_stream_duration: None,
+        dust_sweep_grace_period: None,
+        stream_contract_code_id: None,
+        vesting_code_id: None,
+        tos_version: None,
     };
     let res = app
         .execute_contract(
@@ -54,11 +60,16 @@ fn params_update() {
     let msg = ExecuteMsg::UpdateParams {
         stream_creation_fee: Some(coin(200, "fee_denom")),
         exit_fee_percent: None,
+        referral_fee_percent: None,
         accepted_in_denoms: None,
-        fee_collector: None,
+        fee_collectors: None,
         min_bootstrapping_duration: None,
         min_waiting_duration: None,
         min_stream_duration: None,
+        dust_sweep_grace_period: None,
+        stream_contract_code_id: None,
+        vesting_code_id: None,
+        tos_version: None,
     };
     let _ = app
         .execute_contract(
@@ -81,11 +92,16 @@ fn params_update() {
     let msg = ExecuteMsg::UpdateParams {
         stream_creation_fee: None,
         exit_fee_percent: Some(Decimal256::percent(101)),
+        referral_fee_percent: None,
         accepted_in_denoms: None,
-        fee_collector: None,
+        fee_collectors: None,
         min_bootstrapping_duration: None,
         min_waiting_duration: None,
         min_stream_duration: None,
+        dust_sweep_grace_period: None,
+        stream_contract_code_id: None,
+        vesting_code_id: None,
+        tos_version: None,
     };
     let res = app
         .execute_contract(
@@ -103,11 +119,16 @@ fn params_update() {
     let msg = ExecuteMsg::UpdateParams {
         stream_creation_fee: None,
         exit_fee_percent: Some(Decimal256::percent(50)),
+        referral_fee_percent: None,
         accepted_in_denoms: None,
-        fee_collector: None,
+        fee_collectors: None,
         min_bootstrapping_duration: None,
         min_waiting_duration: None,
         min_stream_duration: None,
+        dust_sweep_grace_period: None,
+        stream_contract_code_id: None,
+        vesting_code_id: None,
+        tos_version: None,
     };
     let _ = app
         .execute_contract(
@@ -130,11 +151,16 @@ fn params_update() {
     let msg = ExecuteMsg::UpdateParams {
         stream_creation_fee: None,
         exit_fee_percent: None,
+        referral_fee_percent: None,
         accepted_in_denoms: Some(vec!["denom1".to_string(), "denom2".to_string()]),
-        fee_collector: None,
+        fee_collectors: None,
         min_bootstrapping_duration: None,
         min_waiting_duration: None,
         min_stream_duration: None,
+        dust_sweep_grace_period: None,
+        stream_contract_code_id: None,
+        vesting_code_id: None,
+        tos_version: None,
     };
     let _ = app
         .execute_contract(
@@ -160,11 +186,19 @@ fn params_update() {
     let msg = ExecuteMsg::UpdateParams {
         stream_creation_fee: None,
         exit_fee_percent: None,
+        referral_fee_percent: None,
         accepted_in_denoms: None,
-        fee_collector: test_accounts.admin_2.to_string().into(),
+        fee_collectors: Some(vec![FeeCollectorMsg {
+            address: test_accounts.admin_2.to_string(),
+            weight: Decimal256::one(),
+        }]),
         min_bootstrapping_duration: None,
         min_waiting_duration: None,
         min_stream_duration: None,
+        dust_sweep_grace_period: None,
+        stream_contract_code_id: None,
+        vesting_code_id: None,
+        tos_version: None,
     };
     let _ = app
         .execute_contract(
@@ -181,17 +215,28 @@ fn params_update() {
         .query_wasm_smart(controller_address.clone(), &QueryMsg::Params {})
         .unwrap();
 
-    assert_eq!(res.fee_collector, test_accounts.admin_2);
+    assert_eq!(
+        res.fee_collectors,
+        vec![FeeCollector {
+            address: test_accounts.admin_2.clone(),
+            weight: Decimal256::one(),
+        }]
+    );
 
     // Update min stream duration
     let msg = ExecuteMsg::UpdateParams {
         stream_creation_fee: None,
         exit_fee_percent: None,
+        referral_fee_percent: None,
         accepted_in_denoms: None,
-        fee_collector: None,
+        fee_collectors: None,
         min_bootstrapping_duration: None,
         min_waiting_duration: None,
         min_stream_duration: Some(200),
+        dust_sweep_grace_period: None,
+        stream_contract_code_id: None,
+        vesting_code_id: None,
+        tos_version: None,
     };
     let _ = app
         .execute_contract(
@@ -214,11 +259,16 @@ fn params_update() {
     let msg = ExecuteMsg::UpdateParams {
         stream_creation_fee: None,
         exit_fee_percent: None,
+        referral_fee_percent: None,
         accepted_in_denoms: None,
-        fee_collector: None,
+        fee_collectors: None,
         min_bootstrapping_duration: Some(200),
         min_waiting_duration: None,
         min_stream_duration: None,
+        dust_sweep_grace_period: None,
+        stream_contract_code_id: None,
+        vesting_code_id: None,
+        tos_version: None,
     };
     let _ = app
         .execute_contract(
@@ -236,4 +286,109 @@ fn params_update() {
         .unwrap();
 
     assert_eq!(res.min_bootstrapping_duration, 200);
+
+    // Update dust sweep grace period
+    let msg = ExecuteMsg::UpdateParams {
+        stream_creation_fee: None,
+        exit_fee_percent: None,
+        referral_fee_percent: None,
+        accepted_in_denoms: None,
+        fee_collectors: None,
+        min_bootstrapping_duration: None,
+        min_waiting_duration: None,
+        min_stream_duration: None,
+        dust_sweep_grace_period: Some(2_000),
+        stream_contract_code_id: None,
+        vesting_code_id: None,
+        tos_version: None,
+    };
+    let _ = app
+        .execute_contract(
+            test_accounts.admin.clone(),
+            controller_address.clone(),
+            &msg,
+            &[],
+        )
+        .unwrap();
+
+    // Query Params
+    let res: Params = app
+        .wrap()
+        .query_wasm_smart(controller_address.clone(), &QueryMsg::Params {})
+        .unwrap();
+
+    assert_eq!(res.dust_sweep_grace_period, 2_000);
+
+    // Code ids must be stored
+    let msg = ExecuteMsg::UpdateParams {
+        stream_creation_fee: None,
+        exit_fee_percent: None,
+        referral_fee_percent: None,
+        accepted_in_denoms: None,
+        fee_collectors: None,
+        min_bootstrapping_duration: None,
+        min_waiting_duration: None,
+        min_stream_duration: None,
+        dust_sweep_grace_period: None,
+        stream_contract_code_id: Some(999),
+        vesting_code_id: None,
+        tos_version: None,
+    };
+    let err = app
+        .execute_contract(
+            test_accounts.admin.clone(),
+            controller_address.clone(),
+            &msg,
+            &[],
+        )
+        .unwrap_err();
+    let error = err.downcast_ref::<ControllerError>().unwrap();
+    assert_eq!(*error, ControllerError::InvalidCodeId { code_id: 999 });
+
+    // Update code ids and tos version
+    let new_stream_code_id = app.store_code(Box::new(ContractWrapper::new(
+        streamswap_stream::contract::execute,
+        streamswap_stream::contract::instantiate,
+        streamswap_stream::contract::query,
+    )));
+    let msg = ExecuteMsg::UpdateParams {
+        stream_creation_fee: None,
+        exit_fee_percent: None,
+        referral_fee_percent: None,
+        accepted_in_denoms: None,
+        fee_collectors: None,
+        min_bootstrapping_duration: None,
+        min_waiting_duration: None,
+        min_stream_duration: None,
+        dust_sweep_grace_period: None,
+        stream_contract_code_id: Some(new_stream_code_id),
+        vesting_code_id: Some(vesting_code_id),
+        tos_version: Some("v2".to_string()),
+    };
+    let res = app
+        .execute_contract(
+            test_accounts.admin.clone(),
+            controller_address.clone(),
+            &msg,
+            &[],
+        )
+        .unwrap();
+    assert_eq!(
+        get_wasm_attribute_with_key(res.clone(), "stream_contract_code_id".to_string()),
+        new_stream_code_id.to_string()
+    );
+    assert_eq!(
+        get_wasm_attribute_with_key(res, "tos_version".to_string()),
+        "v2"
+    );
+
+    // Query Params
+    let res: Params = app
+        .wrap()
+        .query_wasm_smart(controller_address.clone(), &QueryMsg::Params {})
+        .unwrap();
+
+    assert_eq!(res.stream_contract_code_id, new_stream_code_id);
+    assert_eq!(res.vesting_code_id, vesting_code_id);
+    assert_eq!(res.tos_version, "v2");
 }
@@ -0,0 +1,164 @@
+#![cfg(test)]
+use crate::helpers::mock_messages::{get_controller_inst_msg, CreateStreamMsgBuilder};
+use crate::helpers::suite::{Suite, SuiteBuilder};
+use crate::helpers::utils::get_wasm_attribute_with_key;
+use cosmwasm_std::{coin, Addr, Binary, Uint256};
+use cw_multi_test::Executor;
+use streamswap_controller::error::ContractError as ControllerError;
+use streamswap_types::controller::{
+    DenomStatsResponse, ExecuteMsg, ProtocolStatsResponse, QueryMsg, StreamCallbackMsg,
+    StreamResponse,
+};
+use streamswap_types::stream::{ExecuteMsg as StreamSwapExecuteMsg, FinalizedStatus, Status};
+
+#[test]
+fn test_protocol_stats_from_stream_callbacks() {
+    let Suite {
+        mut app,
+        test_accounts,
+        stream_swap_code_id,
+        stream_swap_controller_code_id,
+        vesting_code_id,
+    } = SuiteBuilder::default().build();
+
+    let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+    let controller_address = app
+        .instantiate_contract(
+            stream_swap_controller_code_id,
+            test_accounts.admin.clone(),
+            &msg,
+            &[],
+            "Controller".to_string(),
+            None,
+        )
+        .unwrap();
+
+    let now = app.block_info().time;
+    let mut stream_addrs = vec![];
+    for (threshold, salt) in [(None, 1u8), (Some(10_000u128), 2u8), (None, 3u8)] {
+        let mut builder = CreateStreamMsgBuilder::new(
+            "stream",
+            test_accounts.creator_1.as_ref(),
+            coin(100, "out_denom"),
+            "in_denom",
+            now.plus_seconds(50),
+            now.plus_seconds(100),
+            now.plus_seconds(200),
+        )
+        .salt(Binary::from(vec![salt; 32]));
+        if let Some(threshold) = threshold {
+            builder = builder.threshold(Uint256::from(threshold));
+        }
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address.clone(),
+                &builder.build(),
+                &[coin(100, "fee_denom"), coin(100, "out_denom")],
+            )
+            .unwrap();
+        stream_addrs.push(Addr::unchecked(get_wasm_attribute_with_key(
+            res,
+            "stream_contract_addr".to_string(),
+        )));
+    }
+
+    // Only registered streams can report their status
+    let err = app
+        .execute_contract(
+            test_accounts.wrong_user.clone(),
+            controller_address.clone(),
+            &ExecuteMsg::StreamCallback {
+                msg: StreamCallbackMsg::Cancelled {},
+            },
+            &[],
+        )
+        .unwrap_err();
+    let error = err.downcast_ref::<ControllerError>().unwrap();
+    assert_eq!(*error, ControllerError::Unauthorized {});
+
+    // Third stream is cancelled while waiting
+    app.execute_contract(
+        test_accounts.creator_1.clone(),
+        stream_addrs[2].clone(),
+        &StreamSwapExecuteMsg::StreamAdminCancel {},
+        &[],
+    )
+    .unwrap();
+
+    app.update_block(|b| b.time = now.plus_seconds(100));
+    for stream_addr in &stream_addrs[..2] {
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_addr.clone(),
+            &StreamSwapExecuteMsg::Subscribe {
+                proof: None,
+                on_behalf_of: None,
+                referrer: None,
+            },
+            &[coin(1_000, "in_denom")],
+        )
+        .unwrap();
+    }
+
+    // First stream reaches its threshold, second one misses it
+    app.update_block(|b| b.time = now.plus_seconds(200));
+    for stream_addr in &stream_addrs[..2] {
+        app.execute_contract(
+            test_accounts.creator_1.clone(),
+            stream_addr.clone(),
+            &StreamSwapExecuteMsg::FinalizeStream {
+                new_treasury: None,
+                create_pool: None,
+                salt: None,
+            },
+            &[],
+        )
+        .unwrap();
+    }
+
+    let stats: ProtocolStatsResponse = app
+        .wrap()
+        .query_wasm_smart(controller_address.clone(), &QueryMsg::ProtocolStats {})
+        .unwrap();
+    assert_eq!(
+        stats,
+        ProtocolStatsResponse {
+            streams_created: 3,
+            streams_finalized: 1,
+            streams_cancelled: 1,
+            thresholds_missed: 1,
+            min_average_prices_missed: 0,
+            denoms: vec![DenomStatsResponse {
+                denom: "in_denom".to_string(),
+                volume: Uint256::from(1_000u128),
+                fees_collected: Uint256::from(10u128),
+            }],
+        }
+    );
+
+    // Registry status follows the stream callbacks
+    let statuses: Vec<Status> = stream_addrs
+        .iter()
+        .map(|stream_addr| {
+            let stream: StreamResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    controller_address.clone(),
+                    &QueryMsg::StreamByAddress {
+                        address: stream_addr.to_string(),
+                    },
+                )
+                .unwrap();
+            stream.status
+        })
+        .collect();
+    assert_eq!(
+        statuses,
+        vec![
+            Status::Finalized(FinalizedStatus::ThresholdReached),
+            Status::Finalized(FinalizedStatus::ThresholdNotReached),
+            Status::Cancelled,
+        ]
+    );
+}
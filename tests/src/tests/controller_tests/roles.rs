@@ -0,0 +1,342 @@
+#![cfg(test)]
+use crate::helpers::mock_messages::{get_controller_inst_msg, CreateStreamMsgBuilder};
+use crate::helpers::suite::{Suite, SuiteBuilder};
+use crate::helpers::utils::get_contract_address_from_res;
+use cosmwasm_std::{coin, Addr, Decimal256};
+use cw_multi_test::Executor;
+use streamswap_controller::error::ContractError as ControllerError;
+use streamswap_stream::ContractError as StreamSwapError;
+use streamswap_types::controller::{ExecuteMsg, QueryMsg, RolesResponse};
+use streamswap_types::stream::ExecuteMsg as StreamSwapExecuteMsg;
+
+fn update_params_msg(
+    exit_fee_percent: Option<Decimal256>,
+    min_stream_duration: Option<u64>,
+) -> ExecuteMsg {
+    ExecuteMsg::UpdateParams {
+        stream_creation_fee: None,
+        exit_fee_percent,
+        referral_fee_percent: None,
+        accepted_in_denoms: None,
+        fee_collectors: None,
+        min_bootstrapping_duration: None,
+        min_waiting_duration: None,
+        min_stream_duration,
+        dust_sweep_grace_period: None,
+        stream_contract_code_id: None,
+        vesting_code_id: None,
+        tos_version: None,
+    }
+}
+
+#[test]
+fn test_roles() {
+    let Suite {
+        mut app,
+        test_accounts,
+        stream_swap_code_id,
+        stream_swap_controller_code_id,
+        vesting_code_id,
+    } = SuiteBuilder::default().build();
+
+    let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+    let controller_address = app
+        .instantiate_contract(
+            stream_swap_controller_code_id,
+            test_accounts.admin.clone(),
+            &msg,
+            &[],
+            "Controller".to_string(),
+            None,
+        )
+        .unwrap();
+
+    // Every role belongs to the protocol admin by default
+    let roles: RolesResponse = app
+        .wrap()
+        .query_wasm_smart(controller_address.clone(), &QueryMsg::Roles {})
+        .unwrap();
+    let admin = test_accounts.admin.to_string();
+    assert_eq!(
+        roles,
+        RolesResponse {
+            protocol_admin: admin.clone(),
+            pending_admin: None,
+            params_manager: admin.clone(),
+            pauser: admin.clone(),
+            stream_canceller: admin.clone(),
+            fee_manager: admin,
+        }
+    );
+
+    // Only protocol admin can assign roles
+    let update_roles_msg = ExecuteMsg::UpdateRoles {
+        params_manager: None,
+        pauser: Some(test_accounts.subscriber_2.to_string()),
+        stream_canceller: Some(test_accounts.creator_2.to_string()),
+        fee_manager: Some(test_accounts.admin_2.to_string()),
+    };
+    let err = app
+        .execute_contract(
+            test_accounts.wrong_user.clone(),
+            controller_address.clone(),
+            &update_roles_msg,
+            &[],
+        )
+        .unwrap_err();
+    let error = err.downcast_ref::<ControllerError>().unwrap();
+    assert_eq!(*error, ControllerError::Unauthorized {});
+    app.execute_contract(
+        test_accounts.admin.clone(),
+        controller_address.clone(),
+        &update_roles_msg,
+        &[],
+    )
+    .unwrap();
+
+    // Freezing is left to the pauser
+    let err = app
+        .execute_contract(
+            test_accounts.admin.clone(),
+            controller_address.clone(),
+            &ExecuteMsg::Freeze {},
+            &[],
+        )
+        .unwrap_err();
+    let error = err.downcast_ref::<ControllerError>().unwrap();
+    assert_eq!(*error, ControllerError::Unauthorized {});
+    for msg in [ExecuteMsg::Freeze {}, ExecuteMsg::Unfreeze {}] {
+        app.execute_contract(
+            test_accounts.subscriber_2.clone(),
+            controller_address.clone(),
+            &msg,
+            &[],
+        )
+        .unwrap();
+    }
+
+    // Fees are updated by the fee manager, other params by the params manager
+    for (sender, msg) in [
+        (
+            &test_accounts.admin,
+            update_params_msg(Some(Decimal256::percent(2)), None),
+        ),
+        (&test_accounts.admin_2, update_params_msg(None, Some(100))),
+        (
+            &test_accounts.admin,
+            update_params_msg(Some(Decimal256::percent(2)), Some(100)),
+        ),
+    ] {
+        let err = app
+            .execute_contract(sender.clone(), controller_address.clone(), &msg, &[])
+            .unwrap_err();
+        let error = err.downcast_ref::<ControllerError>().unwrap();
+        assert_eq!(*error, ControllerError::Unauthorized {});
+    }
+    for (sender, msg) in [
+        (
+            &test_accounts.admin_2,
+            update_params_msg(Some(Decimal256::percent(2)), None),
+        ),
+        (&test_accounts.admin, update_params_msg(None, Some(100))),
+    ] {
+        app.execute_contract(sender.clone(), controller_address.clone(), &msg, &[])
+            .unwrap();
+    }
+
+    // Streams check the roles of the controller
+    let now = app.block_info().time;
+    let create_stream_msg = CreateStreamMsgBuilder::new(
+        "stream",
+        test_accounts.creator_1.as_ref(),
+        coin(100, "out_denom"),
+        "in_denom",
+        now.plus_seconds(50),
+        now.plus_seconds(100),
+        now.plus_seconds(200),
+    )
+    .build();
+    let res = app
+        .execute_contract(
+            test_accounts.creator_1.clone(),
+            controller_address.clone(),
+            &create_stream_msg,
+            &[coin(100, "fee_denom"), coin(100, "out_denom")],
+        )
+        .unwrap();
+    let stream_swap_contract_address = Addr::unchecked(get_contract_address_from_res(res));
+    let err = app
+        .execute_contract(
+            test_accounts.admin.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::CancelStream {},
+            &[],
+        )
+        .unwrap_err();
+    let error = err.source().unwrap().downcast_ref::<StreamSwapError>();
+    assert_eq!(error, Some(&StreamSwapError::Unauthorized {}));
+    app.execute_contract(
+        test_accounts.creator_2.clone(),
+        stream_swap_contract_address,
+        &StreamSwapExecuteMsg::CancelStream {},
+        &[],
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_protocol_admin_transfer() {
+    let Suite {
+        mut app,
+        test_accounts,
+        stream_swap_code_id,
+        stream_swap_controller_code_id,
+        vesting_code_id,
+    } = SuiteBuilder::default().build();
+
+    let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+    let controller_address = app
+        .instantiate_contract(
+            stream_swap_controller_code_id,
+            test_accounts.admin.clone(),
+            &msg,
+            &[],
+            "Controller".to_string(),
+            None,
+        )
+        .unwrap();
+
+    // Nothing to accept before a proposal
+    let err = app
+        .execute_contract(
+            test_accounts.admin_2.clone(),
+            controller_address.clone(),
+            &ExecuteMsg::AcceptAdmin {},
+            &[],
+        )
+        .unwrap_err();
+    let error = err.downcast_ref::<ControllerError>().unwrap();
+    assert_eq!(*error, ControllerError::NoPendingAdmin {});
+
+    let propose_msg = ExecuteMsg::ProposeAdmin {
+        new_admin: test_accounts.admin_2.to_string(),
+    };
+    let err = app
+        .execute_contract(
+            test_accounts.wrong_user.clone(),
+            controller_address.clone(),
+            &propose_msg,
+            &[],
+        )
+        .unwrap_err();
+    let error = err.downcast_ref::<ControllerError>().unwrap();
+    assert_eq!(*error, ControllerError::Unauthorized {});
+
+    // Cancelled proposal can not be accepted
+    for msg in [propose_msg.clone(), ExecuteMsg::CancelAdminProposal {}] {
+        app.execute_contract(
+            test_accounts.admin.clone(),
+            controller_address.clone(),
+            &msg,
+            &[],
+        )
+        .unwrap();
+    }
+    let err = app
+        .execute_contract(
+            test_accounts.admin_2.clone(),
+            controller_address.clone(),
+            &ExecuteMsg::AcceptAdmin {},
+            &[],
+        )
+        .unwrap_err();
+    let error = err.downcast_ref::<ControllerError>().unwrap();
+    assert_eq!(*error, ControllerError::NoPendingAdmin {});
+
+    // Pauser role is delegated before the transfer
+    app.execute_contract(
+        test_accounts.admin.clone(),
+        controller_address.clone(),
+        &ExecuteMsg::UpdateRoles {
+            params_manager: None,
+            pauser: Some(test_accounts.creator_2.to_string()),
+            stream_canceller: None,
+            fee_manager: None,
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        test_accounts.admin.clone(),
+        controller_address.clone(),
+        &propose_msg,
+        &[],
+    )
+    .unwrap();
+    let roles: RolesResponse = app
+        .wrap()
+        .query_wasm_smart(controller_address.clone(), &QueryMsg::Roles {})
+        .unwrap();
+    assert_eq!(roles.protocol_admin, test_accounts.admin.to_string());
+    assert_eq!(roles.pending_admin, Some(test_accounts.admin_2.to_string()));
+
+    // Only the proposed admin can accept
+    let err = app
+        .execute_contract(
+            test_accounts.wrong_user.clone(),
+            controller_address.clone(),
+            &ExecuteMsg::AcceptAdmin {},
+            &[],
+        )
+        .unwrap_err();
+    let error = err.downcast_ref::<ControllerError>().unwrap();
+    assert_eq!(*error, ControllerError::Unauthorized {});
+    app.execute_contract(
+        test_accounts.admin_2.clone(),
+        controller_address.clone(),
+        &ExecuteMsg::AcceptAdmin {},
+        &[],
+    )
+    .unwrap();
+
+    // Roles of the previous admin move to the new admin, the delegated role is kept
+    let roles: RolesResponse = app
+        .wrap()
+        .query_wasm_smart(controller_address.clone(), &QueryMsg::Roles {})
+        .unwrap();
+    assert_eq!(
+        roles,
+        RolesResponse {
+            protocol_admin: test_accounts.admin_2.to_string(),
+            pending_admin: None,
+            params_manager: test_accounts.admin_2.to_string(),
+            pauser: test_accounts.creator_2.to_string(),
+            stream_canceller: test_accounts.admin_2.to_string(),
+            fee_manager: test_accounts.admin_2.to_string(),
+        }
+    );
+
+    let update_roles_msg = ExecuteMsg::UpdateRoles {
+        params_manager: None,
+        pauser: Some(test_accounts.admin_2.to_string()),
+        stream_canceller: None,
+        fee_manager: None,
+    };
+    let err = app
+        .execute_contract(
+            test_accounts.admin.clone(),
+            controller_address.clone(),
+            &update_roles_msg,
+            &[],
+        )
+        .unwrap_err();
+    let error = err.downcast_ref::<ControllerError>().unwrap();
+    assert_eq!(*error, ControllerError::Unauthorized {});
+    app.execute_contract(
+        test_accounts.admin_2.clone(),
+        controller_address,
+        &update_roles_msg,
+        &[],
+    )
+    .unwrap();
+}
@@ -0,0 +1,383 @@
+#[cfg(test)]
+mod allowlist {
+    use crate::helpers::mock_messages::CreateStreamMsgBuilder;
+    use crate::helpers::suite::SuiteBuilder;
+    use crate::helpers::{
+        mock_messages::get_controller_inst_msg, suite::Suite, utils::get_contract_address_from_res,
+    };
+    use cosmwasm_std::{coin, Addr, BlockInfo, HexBinary};
+    use cw_multi_test::Executor;
+    use sha2::{Digest, Sha256};
+    use streamswap_stream::ContractError as StreamSwapError;
+    use streamswap_types::stream::{
+        ExecuteMsg as StreamSwapExecuteMsg, QueryMsg as StreamSwapQueryMsg, StreamResponse,
+    };
+
+    fn leaf(addr: &Addr) -> [u8; 32] {
+        Sha256::digest(addr.as_bytes()).into()
+    }
+
+    fn root(leaf_1: [u8; 32], leaf_2: [u8; 32]) -> String {
+        let mut hashes = [leaf_1, leaf_2];
+        hashes.sort_unstable();
+        HexBinary::from(Sha256::digest(hashes.concat()).as_slice()).to_hex()
+    }
+
+    #[test]
+    fn allowlisted_subscription() {
+        let Suite {
+            mut app,
+            test_accounts,
+            stream_swap_code_id,
+            stream_swap_controller_code_id,
+            vesting_code_id,
+        } = SuiteBuilder::default().build();
+
+        let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+        let controller_address = app
+            .instantiate_contract(
+                stream_swap_controller_code_id,
+                test_accounts.admin.clone(),
+                &msg,
+                &[],
+                "Controller".to_string(),
+                None,
+            )
+            .unwrap();
+
+        let leaf_1 = leaf(&test_accounts.subscriber_1);
+        let leaf_2 = leaf(&test_accounts.subscriber_2);
+        let merkle_root = root(leaf_1, leaf_2);
+
+        let start_time = app.block_info().time.plus_seconds(100);
+        let end_time = app.block_info().time.plus_seconds(200);
+        let bootstrapping_start_time = app.block_info().time.plus_seconds(50);
+        let create_stream_msg = CreateStreamMsgBuilder::new(
+            "stream",
+            test_accounts.creator_1.as_ref(),
+            coin(1_000_000, "out_denom"),
+            "in_denom",
+            bootstrapping_start_time,
+            start_time,
+            end_time,
+        )
+        .merkle_root(merkle_root.clone())
+        .build();
+
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address.clone(),
+                &create_stream_msg,
+                &[coin(100, "fee_denom"), coin(1_000_000, "out_denom")],
+            )
+            .unwrap();
+        let stream_swap_contract_address = get_contract_address_from_res(res);
+
+        let stream: StreamResponse = app
+            .wrap()
+            .query_wasm_smart(
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapQueryMsg::Stream {},
+            )
+            .unwrap();
+        assert_eq!(stream.merkle_root, Some(merkle_root));
+
+        app.set_block(BlockInfo {
+            height: 1_100,
+            time: bootstrapping_start_time,
+            chain_id: "test".to_string(),
+        });
+
+        // Proof is required on the first subscription
+        let res = app
+            .execute_contract(
+                test_accounts.subscriber_1.clone(),
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapExecuteMsg::Subscribe {
+                    proof: None,
+                    on_behalf_of: None,
+                    referrer: None,
+                },
+                &[coin(100, "in_denom")],
+            )
+            .unwrap_err();
+        let err = res.source().unwrap();
+        let error = err.downcast_ref::<StreamSwapError>().unwrap();
+        assert_eq!(*error, StreamSwapError::NotAllowlisted {});
+
+        // Malformed proof
+        let res = app
+            .execute_contract(
+                test_accounts.subscriber_1.clone(),
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapExecuteMsg::Subscribe {
+                    proof: Some(vec!["not_hex".to_string()]),
+                    on_behalf_of: None,
+                    referrer: None,
+                },
+                &[coin(100, "in_denom")],
+            )
+            .unwrap_err();
+        let err = res.source().unwrap();
+        let error = err.downcast_ref::<StreamSwapError>().unwrap();
+        assert_eq!(*error, StreamSwapError::InvalidMerkleProof {});
+
+        // Someone else's proof
+        let res = app
+            .execute_contract(
+                test_accounts.wrong_user.clone(),
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapExecuteMsg::Subscribe {
+                    proof: Some(vec![HexBinary::from(leaf_2.as_slice()).to_hex()]),
+                    on_behalf_of: None,
+                    referrer: None,
+                },
+                &[coin(100, "in_denom")],
+            )
+            .unwrap_err();
+        let err = res.source().unwrap();
+        let error = err.downcast_ref::<StreamSwapError>().unwrap();
+        assert_eq!(*error, StreamSwapError::NotAllowlisted {});
+
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            Addr::unchecked(stream_swap_contract_address.clone()),
+            &StreamSwapExecuteMsg::Subscribe {
+                proof: Some(vec![HexBinary::from(leaf_2.as_slice()).to_hex()]),
+                on_behalf_of: None,
+                referrer: None,
+            },
+            &[coin(100, "in_denom")],
+        )
+        .unwrap();
+
+        let verified: bool = app
+            .wrap()
+            .query_wasm_smart(
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapQueryMsg::AllowlistVerified {
+                    addr: test_accounts.subscriber_1.to_string(),
+                },
+            )
+            .unwrap();
+        assert!(verified);
+        let verified: bool = app
+            .wrap()
+            .query_wasm_smart(
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapQueryMsg::AllowlistVerified {
+                    addr: test_accounts.subscriber_2.to_string(),
+                },
+            )
+            .unwrap();
+        assert!(!verified);
+
+        // Verified addresses do not need a proof anymore
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            Addr::unchecked(stream_swap_contract_address.clone()),
+            &StreamSwapExecuteMsg::Subscribe {
+                proof: None,
+                on_behalf_of: None,
+                referrer: None,
+            },
+            &[coin(100, "in_denom")],
+        )
+        .unwrap();
+
+        // Only stream admin can rotate the root
+        let new_root = HexBinary::from(leaf(&test_accounts.wrong_user).as_slice()).to_hex();
+        let res = app
+            .execute_contract(
+                test_accounts.subscriber_1.clone(),
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapExecuteMsg::UpdateMerkleRoot {
+                    merkle_root: Some(new_root.clone()),
+                },
+                &[],
+            )
+            .unwrap_err();
+        let err = res.source().unwrap();
+        let error = err.downcast_ref::<StreamSwapError>().unwrap();
+        assert_eq!(*error, StreamSwapError::Unauthorized {});
+
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapExecuteMsg::UpdateMerkleRoot {
+                    merkle_root: Some("abcd".to_string()),
+                },
+                &[],
+            )
+            .unwrap_err();
+        let err = res.source().unwrap();
+        let error = err.downcast_ref::<StreamSwapError>().unwrap();
+        assert_eq!(*error, StreamSwapError::InvalidMerkleRoot {});
+
+        // Single leaf tree, root is the leaf itself
+        app.execute_contract(
+            test_accounts.creator_1.clone(),
+            Addr::unchecked(stream_swap_contract_address.clone()),
+            &StreamSwapExecuteMsg::UpdateMerkleRoot {
+                merkle_root: Some(new_root.clone()),
+            },
+            &[],
+        )
+        .unwrap();
+        app.execute_contract(
+            test_accounts.wrong_user.clone(),
+            Addr::unchecked(stream_swap_contract_address.clone()),
+            &StreamSwapExecuteMsg::Subscribe {
+                proof: Some(vec![]),
+                on_behalf_of: None,
+                referrer: None,
+            },
+            &[coin(100, "in_denom")],
+        )
+        .unwrap();
+
+        // Addresses verified against the previous root are not verified against the new one
+        let verified: bool = app
+            .wrap()
+            .query_wasm_smart(
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapQueryMsg::AllowlistVerified {
+                    addr: test_accounts.subscriber_1.to_string(),
+                },
+            )
+            .unwrap();
+        assert!(!verified);
+        let res = app
+            .execute_contract(
+                test_accounts.subscriber_1.clone(),
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapExecuteMsg::Subscribe {
+                    proof: None,
+                    on_behalf_of: None,
+                    referrer: None,
+                },
+                &[coin(100, "in_denom")],
+            )
+            .unwrap_err();
+        let err = res.source().unwrap();
+        let error = err.downcast_ref::<StreamSwapError>().unwrap();
+        assert_eq!(*error, StreamSwapError::NotAllowlisted {});
+
+        // Root can not be rotated once the stream is active
+        app.set_block(BlockInfo {
+            height: 1_200,
+            time: start_time,
+            chain_id: "test".to_string(),
+        });
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapExecuteMsg::UpdateMerkleRoot { merkle_root: None },
+                &[],
+            )
+            .unwrap_err();
+        let err = res.source().unwrap();
+        let error = err.downcast_ref::<StreamSwapError>().unwrap();
+        assert_eq!(
+            *error,
+            StreamSwapError::OperationNotAllowed {
+                current_status: "Active".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn transferred_position_requires_proof_to_subscribe() {
+        let Suite {
+            mut app,
+            test_accounts,
+            stream_swap_code_id,
+            stream_swap_controller_code_id,
+            vesting_code_id,
+        } = SuiteBuilder::default().build();
+
+        let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+        let controller_address = app
+            .instantiate_contract(
+                stream_swap_controller_code_id,
+                test_accounts.admin.clone(),
+                &msg,
+                &[],
+                "Controller".to_string(),
+                None,
+            )
+            .unwrap();
+
+        // Single leaf tree, root is the leaf itself
+        let merkle_root = HexBinary::from(leaf(&test_accounts.subscriber_1).as_slice()).to_hex();
+        let start_time = app.block_info().time.plus_seconds(100);
+        let end_time = app.block_info().time.plus_seconds(200);
+        let bootstrapping_start_time = app.block_info().time.plus_seconds(50);
+        let create_stream_msg = CreateStreamMsgBuilder::new(
+            "stream",
+            test_accounts.creator_1.as_ref(),
+            coin(1_000_000, "out_denom"),
+            "in_denom",
+            bootstrapping_start_time,
+            start_time,
+            end_time,
+        )
+        .merkle_root(merkle_root)
+        .build();
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address,
+                &create_stream_msg,
+                &[coin(100, "fee_denom"), coin(1_000_000, "out_denom")],
+            )
+            .unwrap();
+        let stream_swap_contract_address = Addr::unchecked(get_contract_address_from_res(res));
+
+        app.set_block(BlockInfo {
+            height: 1_100,
+            time: bootstrapping_start_time,
+            chain_id: "test".to_string(),
+        });
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::Subscribe {
+                proof: Some(vec![]),
+                on_behalf_of: None,
+                referrer: None,
+            },
+            &[coin(100, "in_denom")],
+        )
+        .unwrap();
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::TransferPosition {
+                recipient: test_accounts.subscriber_2.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+        // Recipient owns the position but can not top it up without a proof
+        let res = app
+            .execute_contract(
+                test_accounts.subscriber_2.clone(),
+                stream_swap_contract_address,
+                &StreamSwapExecuteMsg::Subscribe {
+                    proof: None,
+                    on_behalf_of: None,
+                    referrer: None,
+                },
+                &[coin(100, "in_denom")],
+            )
+            .unwrap_err();
+        let err = res.source().unwrap();
+        let error = err.downcast_ref::<StreamSwapError>().unwrap();
+        assert_eq!(*error, StreamSwapError::NotAllowlisted {});
+    }
+}
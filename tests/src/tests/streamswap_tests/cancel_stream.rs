@@ -67,7 +67,11 @@ mod cancel_stream {
             chain_id: "test".to_string(),
         });
 
-        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
 
         let _res = app
             .execute_contract(
@@ -150,7 +154,11 @@ mod cancel_stream {
             chain_id: "test".to_string(),
         });
 
-        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
 
         let _res = app
             .execute_contract(
@@ -237,7 +245,11 @@ mod cancel_stream {
             chain_id: "test".to_string(),
         });
 
-        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
 
         let _res = app
             .execute_contract(
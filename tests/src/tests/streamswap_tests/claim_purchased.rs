@@ -0,0 +1,309 @@
+#[cfg(test)]
+mod claim_purchased {
+    use crate::helpers::mock_messages::CreateStreamMsgBuilder;
+    use crate::helpers::suite::SuiteBuilder;
+    use crate::helpers::{
+        mock_messages::get_controller_inst_msg, suite::Suite, utils::get_contract_address_from_res,
+    };
+    use cosmwasm_std::{coin, Addr, BlockInfo, Uint256};
+    use cw_multi_test::Executor;
+    use streamswap_stream::ContractError as StreamSwapError;
+    use streamswap_types::stream::{
+        ExecuteMsg as StreamSwapExecuteMsg, PositionResponse, QueryMsg as StreamSwapQueryMsg,
+    };
+
+    #[test]
+    fn claim_purchased_after_threshold() {
+        let Suite {
+            mut app,
+            test_accounts,
+            stream_swap_code_id,
+            stream_swap_controller_code_id,
+            vesting_code_id,
+        } = SuiteBuilder::default().build();
+
+        let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+        let controller_address = app
+            .instantiate_contract(
+                stream_swap_controller_code_id,
+                test_accounts.admin.clone(),
+                &msg,
+                &[],
+                "Controller".to_string(),
+                None,
+            )
+            .unwrap();
+
+        let start_time = app.block_info().time.plus_seconds(100);
+        let end_time = app.block_info().time.plus_seconds(200);
+        let bootstrapping_start_time = app.block_info().time.plus_seconds(50);
+        let create_stream_msg = CreateStreamMsgBuilder::new(
+            "stream",
+            test_accounts.creator_1.as_ref(),
+            coin(1_000_000, "out_denom"),
+            "in_denom",
+            bootstrapping_start_time,
+            start_time,
+            end_time,
+        )
+        .threshold(Uint256::from(500u128))
+        .build();
+
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address,
+                &create_stream_msg,
+                &[coin(100, "fee_denom"), coin(1_000_000, "out_denom")],
+            )
+            .unwrap();
+        let stream_swap_contract_address = Addr::unchecked(get_contract_address_from_res(res));
+
+        app.set_block(BlockInfo {
+            height: 1_100,
+            time: start_time,
+            chain_id: "test".to_string(),
+        });
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::Subscribe {
+                proof: None,
+                on_behalf_of: None,
+                referrer: None,
+            },
+            &[coin(1_000, "in_denom")],
+        )
+        .unwrap();
+
+        // Claims are blocked until the threshold is reached
+        app.set_block(BlockInfo {
+            height: 1_125,
+            time: start_time.plus_seconds(25),
+            chain_id: "test".to_string(),
+        });
+        let res = app
+            .execute_contract(
+                test_accounts.subscriber_1.clone(),
+                stream_swap_contract_address.clone(),
+                &StreamSwapExecuteMsg::ClaimPurchased {},
+                &[],
+            )
+            .unwrap_err();
+        let err = res.source().unwrap();
+        let error = err.downcast_ref::<StreamSwapError>().unwrap();
+        assert_eq!(*error, StreamSwapError::ThresholdNotReached {});
+
+        app.set_block(BlockInfo {
+            height: 1_150,
+            time: start_time.plus_seconds(50),
+            chain_id: "test".to_string(),
+        });
+        let balance_before = app
+            .wrap()
+            .query_balance(test_accounts.subscriber_1.clone(), "out_denom")
+            .unwrap();
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::ClaimPurchased {},
+            &[],
+        )
+        .unwrap();
+        let balance = app
+            .wrap()
+            .query_balance(test_accounts.subscriber_1.clone(), "out_denom")
+            .unwrap();
+        assert_eq!((balance.amount - balance_before.amount).u128(), 500_000);
+
+        let position: PositionResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::Position {
+                    owner: test_accounts.subscriber_1.to_string(),
+                    at_time: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(position.claimed, Uint256::from(500_000u128));
+        assert_eq!(position.purchased, Uint256::from(500_000u128));
+
+        // Nothing new to claim in the same block
+        let res = app
+            .execute_contract(
+                test_accounts.subscriber_1.clone(),
+                stream_swap_contract_address.clone(),
+                &StreamSwapExecuteMsg::ClaimPurchased {},
+                &[],
+            )
+            .unwrap_err();
+        let err = res.source().unwrap();
+        let error = err.downcast_ref::<StreamSwapError>().unwrap();
+        assert_eq!(*error, StreamSwapError::NoDistribution {});
+
+        // Exit pays only the unclaimed remainder
+        app.set_block(BlockInfo {
+            height: 1_250,
+            time: end_time.plus_seconds(1),
+            chain_id: "test".to_string(),
+        });
+        let res = app
+            .execute_contract(
+                test_accounts.subscriber_1.clone(),
+                stream_swap_contract_address.clone(),
+                &StreamSwapExecuteMsg::ClaimPurchased {},
+                &[],
+            )
+            .unwrap_err();
+        let err = res.source().unwrap();
+        let error = err.downcast_ref::<StreamSwapError>().unwrap();
+        assert_eq!(
+            *error,
+            StreamSwapError::OperationNotAllowed {
+                current_status: "Ended".to_string()
+            }
+        );
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::ExitStream { salt: None },
+            &[],
+        )
+        .unwrap();
+        let balance = app
+            .wrap()
+            .query_balance(test_accounts.subscriber_1.clone(), "out_denom")
+            .unwrap();
+        assert_eq!((balance.amount - balance_before.amount).u128(), 1_000_000);
+    }
+
+    #[test]
+    fn cancel_after_claim() {
+        let Suite {
+            mut app,
+            test_accounts,
+            stream_swap_code_id,
+            stream_swap_controller_code_id,
+            vesting_code_id,
+        } = SuiteBuilder::default().build();
+
+        let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+        let controller_address = app
+            .instantiate_contract(
+                stream_swap_controller_code_id,
+                test_accounts.admin.clone(),
+                &msg,
+                &[],
+                "Controller".to_string(),
+                None,
+            )
+            .unwrap();
+
+        let start_time = app.block_info().time.plus_seconds(100);
+        let end_time = app.block_info().time.plus_seconds(200);
+        let bootstrapping_start_time = app.block_info().time.plus_seconds(50);
+        let create_stream_msg = CreateStreamMsgBuilder::new(
+            "stream",
+            test_accounts.creator_1.as_ref(),
+            coin(1_000_000, "out_denom"),
+            "in_denom",
+            bootstrapping_start_time,
+            start_time,
+            end_time,
+        )
+        .build();
+
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address,
+                &create_stream_msg,
+                &[coin(100, "fee_denom"), coin(1_000_000, "out_denom")],
+            )
+            .unwrap();
+        let stream_swap_contract_address = Addr::unchecked(get_contract_address_from_res(res));
+
+        app.set_block(BlockInfo {
+            height: 1_100,
+            time: start_time,
+            chain_id: "test".to_string(),
+        });
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::Subscribe {
+                proof: None,
+                on_behalf_of: None,
+                referrer: None,
+            },
+            &[coin(1_000, "in_denom")],
+        )
+        .unwrap();
+
+        app.set_block(BlockInfo {
+            height: 1_150,
+            time: start_time.plus_seconds(50),
+            chain_id: "test".to_string(),
+        });
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::ClaimPurchased {},
+            &[],
+        )
+        .unwrap();
+
+        let out_before = app
+            .wrap()
+            .query_balance(test_accounts.creator_1.clone(), "out_denom")
+            .unwrap();
+        let in_before = app
+            .wrap()
+            .query_balance(test_accounts.creator_1.clone(), "in_denom")
+            .unwrap();
+        app.execute_contract(
+            test_accounts.admin.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::CancelStream {},
+            &[],
+        )
+        .unwrap();
+
+        // Treasury receives the unclaimed out tokens and the in tokens spent for the claimed ones
+        let out_after = app
+            .wrap()
+            .query_balance(test_accounts.creator_1.clone(), "out_denom")
+            .unwrap();
+        let in_after = app
+            .wrap()
+            .query_balance(test_accounts.creator_1.clone(), "in_denom")
+            .unwrap();
+        assert_eq!((out_after.amount - out_before.amount).u128(), 500_000);
+        assert_eq!((in_after.amount - in_before.amount).u128(), 500);
+
+        // Subscriber is refunded only the unsettled in tokens
+        let in_before = app
+            .wrap()
+            .query_balance(test_accounts.subscriber_1.clone(), "in_denom")
+            .unwrap();
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::ExitStream { salt: None },
+            &[],
+        )
+        .unwrap();
+        let in_after = app
+            .wrap()
+            .query_balance(test_accounts.subscriber_1.clone(), "in_denom")
+            .unwrap();
+        assert_eq!((in_after.amount - in_before.amount).u128(), 500);
+
+        let contract_balance = app
+            .wrap()
+            .query_all_balances(stream_swap_contract_address)
+            .unwrap();
+        assert!(contract_balance.is_empty());
+    }
+}
@@ -0,0 +1,418 @@
+#[cfg(test)]
+mod cw20 {
+    use crate::helpers::mock_messages::CreateStreamMsgBuilder;
+    use crate::helpers::suite::{AppType, SuiteBuilder};
+    use crate::helpers::{
+        mock_messages::get_controller_inst_msg, suite::Suite, utils::get_contract_address_from_res,
+    };
+    use cosmwasm_std::{coin, to_json_binary, Addr, BlockInfo, Uint128, Uint256};
+    use cw20::{BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg};
+    use cw_denom::CheckedDenom;
+    use cw_multi_test::{ContractWrapper, Executor};
+    use streamswap_controller::error::ContractError as ControllerError;
+    use streamswap_stream::ContractError as StreamSwapError;
+    use streamswap_types::controller::PoolConfig;
+    use streamswap_types::stream::{
+        Asset, ExecuteMsg as StreamSwapExecuteMsg, QueryMsg as StreamSwapQueryMsg, ReceiveMsg,
+        StreamResponse,
+    };
+
+    fn instantiate_cw20(
+        app: &mut AppType,
+        owner: &Addr,
+        symbol: &str,
+        initial_balances: Vec<Cw20Coin>,
+    ) -> Addr {
+        let cw20_code_id = app.store_code(Box::new(ContractWrapper::new(
+            cw20_base::contract::execute,
+            cw20_base::contract::instantiate,
+            cw20_base::contract::query,
+        )));
+        app.instantiate_contract(
+            cw20_code_id,
+            owner.clone(),
+            &cw20_base::msg::InstantiateMsg {
+                name: format!("{} token", symbol),
+                symbol: symbol.to_string(),
+                decimals: 6,
+                initial_balances,
+                mint: None,
+                marketing: None,
+            },
+            &[],
+            symbol,
+            None,
+        )
+        .unwrap()
+    }
+
+    fn cw20_balance(app: &AppType, token: &Addr, addr: &Addr) -> Uint128 {
+        let res: BalanceResponse = app
+            .wrap()
+            .query_wasm_smart(
+                token,
+                &Cw20QueryMsg::Balance {
+                    address: addr.to_string(),
+                },
+            )
+            .unwrap();
+        res.balance
+    }
+
+    #[test]
+    fn cw20_in_and_out_stream() {
+        let Suite {
+            mut app,
+            test_accounts,
+            stream_swap_code_id,
+            stream_swap_controller_code_id,
+            vesting_code_id,
+        } = SuiteBuilder::default().build();
+
+        let out_token = instantiate_cw20(
+            &mut app,
+            &test_accounts.admin,
+            "TOKOUT",
+            vec![
+                Cw20Coin {
+                    address: test_accounts.creator_1.to_string(),
+                    amount: Uint128::new(1_000_000),
+                },
+                Cw20Coin {
+                    address: test_accounts.wrong_user.to_string(),
+                    amount: Uint128::new(1_000),
+                },
+            ],
+        );
+        let in_token = instantiate_cw20(
+            &mut app,
+            &test_accounts.admin,
+            "TOKIN",
+            vec![Cw20Coin {
+                address: test_accounts.subscriber_1.to_string(),
+                amount: Uint128::new(10_000),
+            }],
+        );
+
+        let mut msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+        msg.accepted_in_denoms.push(in_token.to_string());
+        let controller_address = app
+            .instantiate_contract(
+                stream_swap_controller_code_id,
+                test_accounts.admin.clone(),
+                &msg,
+                &[],
+                "Controller".to_string(),
+                None,
+            )
+            .unwrap();
+
+        // Out asset is transferred from the creator by the controller
+        app.execute_contract(
+            test_accounts.creator_1.clone(),
+            out_token.clone(),
+            &Cw20ExecuteMsg::IncreaseAllowance {
+                spender: controller_address.to_string(),
+                amount: Uint128::new(1_000_000),
+                expires: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        let start_time = app.block_info().time.plus_seconds(100);
+        let end_time = app.block_info().time.plus_seconds(200);
+        let bootstrapping_start_time = app.block_info().time.plus_seconds(50);
+        let create_stream_msg = CreateStreamMsgBuilder::new(
+            "stream",
+            test_accounts.creator_1.as_ref(),
+            coin(1_000_000, "out_denom"),
+            "in_denom",
+            bootstrapping_start_time,
+            start_time,
+            end_time,
+        )
+        .out_asset(Asset::new(
+            CheckedDenom::Cw20(out_token.clone()),
+            Uint128::new(1_000_000),
+        ))
+        .in_denom(CheckedDenom::Cw20(in_token.clone()))
+        .build();
+
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address.clone(),
+                &create_stream_msg,
+                &[coin(100, "fee_denom")],
+            )
+            .unwrap();
+        let stream_swap_contract_address = Addr::unchecked(get_contract_address_from_res(res));
+        assert_eq!(
+            cw20_balance(&app, &out_token, &stream_swap_contract_address),
+            Uint128::new(1_000_000)
+        );
+
+        let stream: StreamResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::Stream {},
+            )
+            .unwrap();
+        assert_eq!(stream.in_denom, CheckedDenom::Cw20(in_token.clone()));
+        assert_eq!(
+            stream.out_asset.denom,
+            CheckedDenom::Cw20(out_token.clone())
+        );
+
+        app.set_block(BlockInfo {
+            height: 1_100,
+            time: start_time,
+            chain_id: "test".to_string(),
+        });
+
+        // Native subscription is not accepted
+        let res = app
+            .execute_contract(
+                test_accounts.subscriber_1.clone(),
+                stream_swap_contract_address.clone(),
+                &StreamSwapExecuteMsg::Subscribe {
+                    proof: None,
+                    on_behalf_of: None,
+                    referrer: None,
+                },
+                &[coin(1_000, "in_denom")],
+            )
+            .unwrap_err();
+        let err = res.source().unwrap();
+        let error = err.downcast_ref::<StreamSwapError>().unwrap();
+        assert_eq!(*error, StreamSwapError::InDenomIsNotAccepted {});
+
+        // Only the in denom cw20 can call the receive hook
+        let res = app
+            .execute_contract(
+                test_accounts.wrong_user.clone(),
+                out_token.clone(),
+                &Cw20ExecuteMsg::Send {
+                    contract: stream_swap_contract_address.to_string(),
+                    amount: Uint128::new(1),
+                    msg: to_json_binary(&ReceiveMsg::Subscribe {
+                        proof: None,
+                        on_behalf_of: None,
+                        referrer: None,
+                    })
+                    .unwrap(),
+                },
+                &[],
+            )
+            .unwrap_err();
+        let error = res.root_cause().downcast_ref::<StreamSwapError>().unwrap();
+        assert_eq!(*error, StreamSwapError::InDenomIsNotAccepted {});
+
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            in_token.clone(),
+            &Cw20ExecuteMsg::Send {
+                contract: stream_swap_contract_address.to_string(),
+                amount: Uint128::new(1_000),
+                msg: to_json_binary(&ReceiveMsg::Subscribe {
+                    proof: None,
+                    on_behalf_of: None,
+                    referrer: None,
+                })
+                .unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+
+        // Half of the balance is spent by the middle of the stream
+        app.set_block(BlockInfo {
+            height: 1_150,
+            time: start_time.plus_seconds(50),
+            chain_id: "test".to_string(),
+        });
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::Withdraw {
+                cap: Some(Uint256::from(100u128)),
+            },
+            &[],
+        )
+        .unwrap();
+        assert_eq!(
+            cw20_balance(&app, &in_token, &test_accounts.subscriber_1),
+            Uint128::new(9_100)
+        );
+
+        app.set_block(BlockInfo {
+            height: 1_200,
+            time: end_time.plus_seconds(1),
+            chain_id: "test".to_string(),
+        });
+        app.execute_contract(
+            test_accounts.creator_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::FinalizeStream {
+                new_treasury: None,
+                create_pool: None,
+                salt: None,
+            },
+            &[],
+        )
+        .unwrap();
+        // 1% exit fee is collected from 900 spent
+        assert_eq!(
+            cw20_balance(&app, &in_token, &test_accounts.creator_1),
+            Uint128::new(891)
+        );
+        assert_eq!(
+            cw20_balance(&app, &in_token, &test_accounts.admin),
+            Uint128::new(9)
+        );
+
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::ExitStream { salt: None },
+            &[],
+        )
+        .unwrap();
+        assert_eq!(
+            cw20_balance(&app, &out_token, &test_accounts.subscriber_1),
+            Uint128::new(1_000_000)
+        );
+        assert_eq!(
+            cw20_balance(&app, &out_token, &stream_swap_contract_address),
+            Uint128::zero()
+        );
+        assert_eq!(
+            cw20_balance(&app, &in_token, &stream_swap_contract_address),
+            Uint128::zero()
+        );
+    }
+
+    #[test]
+    fn cw20_cancelled_stream_refund() {
+        let Suite {
+            mut app,
+            test_accounts,
+            stream_swap_code_id,
+            stream_swap_controller_code_id,
+            vesting_code_id,
+        } = SuiteBuilder::default().build();
+
+        let out_token = instantiate_cw20(
+            &mut app,
+            &test_accounts.admin,
+            "TOKOUT",
+            vec![Cw20Coin {
+                address: test_accounts.creator_1.to_string(),
+                amount: Uint128::new(1_000_000),
+            }],
+        );
+
+        let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+        let controller_address = app
+            .instantiate_contract(
+                stream_swap_controller_code_id,
+                test_accounts.admin.clone(),
+                &msg,
+                &[],
+                "Controller".to_string(),
+                None,
+            )
+            .unwrap();
+        app.execute_contract(
+            test_accounts.creator_1.clone(),
+            out_token.clone(),
+            &Cw20ExecuteMsg::IncreaseAllowance {
+                spender: controller_address.to_string(),
+                amount: Uint128::new(1_000_000),
+                expires: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        let start_time = app.block_info().time.plus_seconds(100);
+        let end_time = app.block_info().time.plus_seconds(200);
+        let bootstrapping_start_time = app.block_info().time.plus_seconds(50);
+
+        // Pools can not be created with cw20 assets
+        let create_stream_msg = CreateStreamMsgBuilder::new(
+            "stream",
+            test_accounts.creator_1.as_ref(),
+            coin(1_000_000, "out_denom"),
+            "in_denom",
+            bootstrapping_start_time,
+            start_time,
+            end_time,
+        )
+        .out_asset(Asset::new(
+            CheckedDenom::Cw20(out_token.clone()),
+            Uint128::new(1_000_000),
+        ))
+        .pool_config(PoolConfig::ConcentratedLiquidity {
+            out_amount_clp: Uint256::from(1_000u128),
+        })
+        .build();
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address.clone(),
+                &create_stream_msg,
+                &[coin(100, "fee_denom")],
+            )
+            .unwrap_err();
+        let err = res.source().unwrap();
+        let error = err.downcast_ref::<ControllerError>().unwrap();
+        assert_eq!(*error, ControllerError::InvalidPoolDenom {});
+
+        let create_stream_msg = CreateStreamMsgBuilder::new(
+            "stream",
+            test_accounts.creator_1.as_ref(),
+            coin(1_000_000, "out_denom"),
+            "in_denom",
+            bootstrapping_start_time,
+            start_time,
+            end_time,
+        )
+        .out_asset(Asset::new(
+            CheckedDenom::Cw20(out_token.clone()),
+            Uint128::new(1_000_000),
+        ))
+        .build();
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address,
+                &create_stream_msg,
+                &[coin(100, "fee_denom")],
+            )
+            .unwrap();
+        let stream_swap_contract_address = Addr::unchecked(get_contract_address_from_res(res));
+        assert_eq!(
+            cw20_balance(&app, &out_token, &test_accounts.creator_1),
+            Uint128::zero()
+        );
+
+        app.execute_contract(
+            test_accounts.creator_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::StreamAdminCancel {},
+            &[],
+        )
+        .unwrap();
+        assert_eq!(
+            cw20_balance(&app, &out_token, &test_accounts.creator_1),
+            Uint128::new(1_000_000)
+        );
+        assert_eq!(
+            cw20_balance(&app, &out_token, &stream_swap_contract_address),
+            Uint128::zero()
+        );
+    }
+}
@@ -0,0 +1,373 @@
+#[cfg(test)]
+mod emission_curve {
+    use std::str::FromStr;
+
+    use crate::helpers::mock_messages::CreateStreamMsgBuilder;
+    use crate::helpers::suite::SuiteBuilder;
+    use crate::helpers::{
+        mock_messages::get_controller_inst_msg, suite::Suite, utils::get_contract_address_from_res,
+    };
+    use cosmwasm_std::{coin, Addr, BlockInfo, Decimal256, Uint256};
+    use cw_multi_test::Executor;
+    use streamswap_stream::ContractError as StreamSwapError;
+    use streamswap_types::stream::{
+        CurveBreakpoint, EmissionCurve, ExecuteMsg as StreamSwapExecuteMsg, PositionResponse,
+        QueryMsg as StreamSwapQueryMsg, StreamResponse,
+    };
+
+    #[test]
+    fn back_loaded_emission() {
+        let Suite {
+            mut app,
+            test_accounts,
+            stream_swap_code_id,
+            stream_swap_controller_code_id,
+            vesting_code_id,
+        } = SuiteBuilder::default().build();
+
+        let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+        let controller_address = app
+            .instantiate_contract(
+                stream_swap_controller_code_id,
+                test_accounts.admin.clone(),
+                &msg,
+                &[],
+                "Controller".to_string(),
+                None,
+            )
+            .unwrap();
+
+        let start_time = app.block_info().time.plus_seconds(100);
+        let end_time = app.block_info().time.plus_seconds(200);
+        let bootstrapping_start_time = app.block_info().time.plus_seconds(50);
+        let create_stream_msg = CreateStreamMsgBuilder::new(
+            "stream",
+            test_accounts.creator_1.as_ref(),
+            coin(1_000_000, "out_denom"),
+            "in_denom",
+            bootstrapping_start_time,
+            start_time,
+            end_time,
+        )
+        .emission_curve(EmissionCurve::BackLoaded { exponent: 2 })
+        .build();
+
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address.clone(),
+                &create_stream_msg,
+                &[coin(100, "fee_denom"), coin(1_000_000, "out_denom")],
+            )
+            .unwrap();
+        let stream_swap_contract_address = get_contract_address_from_res(res);
+
+        app.set_block(BlockInfo {
+            height: 1_100,
+            time: start_time,
+            chain_id: "test".to_string(),
+        });
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            Addr::unchecked(stream_swap_contract_address.clone()),
+            &StreamSwapExecuteMsg::Subscribe {
+                proof: None,
+                on_behalf_of: None,
+                referrer: None,
+            },
+            &[coin(1_000, "in_denom")],
+        )
+        .unwrap();
+
+        // Half way through, only a quarter of the out asset is released
+        app.set_block(BlockInfo {
+            height: 1_150,
+            time: start_time.plus_seconds(50),
+            chain_id: "test".to_string(),
+        });
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            Addr::unchecked(stream_swap_contract_address.clone()),
+            &StreamSwapExecuteMsg::SyncStream {},
+            &[],
+        )
+        .unwrap();
+
+        let stream: StreamResponse = app
+            .wrap()
+            .query_wasm_smart(
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapQueryMsg::Stream {},
+            )
+            .unwrap();
+        assert_eq!(stream.out_remaining, Uint256::from(750_000u128));
+        assert_eq!(stream.spent_in, Uint256::from(250u128));
+        assert_eq!(stream.in_supply, Uint256::from(750u128));
+        assert_eq!(
+            stream.current_streamed_price,
+            Decimal256::from_str("0.001").unwrap()
+        );
+
+        // Everything is released at the end
+        app.set_block(BlockInfo {
+            height: 1_200,
+            time: end_time.plus_seconds(1),
+            chain_id: "test".to_string(),
+        });
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            Addr::unchecked(stream_swap_contract_address.clone()),
+            &StreamSwapExecuteMsg::SyncPosition {},
+            &[],
+        )
+        .unwrap();
+
+        let stream: StreamResponse = app
+            .wrap()
+            .query_wasm_smart(
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapQueryMsg::Stream {},
+            )
+            .unwrap();
+        assert_eq!(stream.out_remaining, Uint256::zero());
+        assert_eq!(stream.spent_in, Uint256::from(1_000u128));
+
+        let position: PositionResponse = app
+            .wrap()
+            .query_wasm_smart(
+                Addr::unchecked(stream_swap_contract_address),
+                &StreamSwapQueryMsg::Position {
+                    owner: test_accounts.subscriber_1.to_string(),
+                    at_time: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(position.purchased, Uint256::from(1_000_000u128));
+        assert_eq!(position.spent, Uint256::from(1_000u128));
+    }
+
+    #[test]
+    fn piecewise_and_exponential_decay_emission() {
+        let Suite {
+            mut app,
+            test_accounts,
+            stream_swap_code_id,
+            stream_swap_controller_code_id,
+            vesting_code_id,
+        } = SuiteBuilder::default().build();
+
+        let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+        let controller_address = app
+            .instantiate_contract(
+                stream_swap_controller_code_id,
+                test_accounts.admin.clone(),
+                &msg,
+                &[],
+                "Controller".to_string(),
+                None,
+            )
+            .unwrap();
+
+        let start_time = app.block_info().time.plus_seconds(100);
+        let end_time = app.block_info().time.plus_seconds(200);
+        let bootstrapping_start_time = app.block_info().time.plus_seconds(50);
+
+        let curves = vec![
+            (
+                // 80% released in the first half
+                EmissionCurve::PiecewiseLinear {
+                    breakpoints: vec![CurveBreakpoint {
+                        time: Decimal256::percent(50),
+                        released: Decimal256::percent(80),
+                    }],
+                },
+                "sal1",
+                Uint256::from(200_000u128),
+            ),
+            (
+                // First epoch releases twice as much as the second one
+                EmissionCurve::ExponentialDecay {
+                    epochs: 2,
+                    decay: Decimal256::percent(50),
+                },
+                "sal2",
+                Uint256::from(333_334u128),
+            ),
+        ];
+
+        for (curve, salt, out_remaining_at_half) in curves {
+            let create_stream_msg = CreateStreamMsgBuilder::new(
+                "stream",
+                test_accounts.creator_1.as_ref(),
+                coin(1_000_000, "out_denom"),
+                "in_denom",
+                bootstrapping_start_time,
+                start_time,
+                end_time,
+            )
+            .emission_curve(curve)
+            .salt(cosmwasm_std::Binary::from_base64(salt).unwrap())
+            .build();
+
+            let res = app
+                .execute_contract(
+                    test_accounts.creator_1.clone(),
+                    controller_address.clone(),
+                    &create_stream_msg,
+                    &[coin(100, "fee_denom"), coin(1_000_000, "out_denom")],
+                )
+                .unwrap();
+            let stream_swap_contract_address = get_contract_address_from_res(res);
+
+            let block = app.block_info();
+            app.set_block(BlockInfo {
+                time: start_time,
+                ..block.clone()
+            });
+            app.execute_contract(
+                test_accounts.subscriber_1.clone(),
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapExecuteMsg::Subscribe {
+                    proof: None,
+                    on_behalf_of: None,
+                    referrer: None,
+                },
+                &[coin(1_000, "in_denom")],
+            )
+            .unwrap();
+
+            app.set_block(BlockInfo {
+                time: start_time.plus_seconds(50),
+                ..block.clone()
+            });
+            app.execute_contract(
+                test_accounts.subscriber_1.clone(),
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapExecuteMsg::SyncStream {},
+                &[],
+            )
+            .unwrap();
+
+            let stream: StreamResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    Addr::unchecked(stream_swap_contract_address.clone()),
+                    &StreamSwapQueryMsg::Stream {},
+                )
+                .unwrap();
+            assert_eq!(stream.out_remaining, out_remaining_at_half);
+
+            app.set_block(BlockInfo {
+                time: end_time,
+                ..block.clone()
+            });
+            app.execute_contract(
+                test_accounts.subscriber_1.clone(),
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapExecuteMsg::SyncStream {},
+                &[],
+            )
+            .unwrap();
+            let stream: StreamResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    Addr::unchecked(stream_swap_contract_address.clone()),
+                    &StreamSwapQueryMsg::Stream {},
+                )
+                .unwrap();
+            assert_eq!(stream.out_remaining, Uint256::zero());
+            assert_eq!(stream.in_supply, Uint256::zero());
+
+            // Rewind for the next stream
+            app.set_block(block);
+        }
+    }
+
+    #[test]
+    fn malformed_emission_curve() {
+        let Suite {
+            mut app,
+            test_accounts,
+            stream_swap_code_id,
+            stream_swap_controller_code_id,
+            vesting_code_id,
+        } = SuiteBuilder::default().build();
+
+        let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+        let controller_address = app
+            .instantiate_contract(
+                stream_swap_controller_code_id,
+                test_accounts.admin.clone(),
+                &msg,
+                &[],
+                "Controller".to_string(),
+                None,
+            )
+            .unwrap();
+
+        let start_time = app.block_info().time.plus_seconds(100);
+        let end_time = app.block_info().time.plus_seconds(200);
+        let bootstrapping_start_time = app.block_info().time.plus_seconds(50);
+
+        let malformed_curves = vec![
+            // Breakpoints must release a non decreasing amount
+            EmissionCurve::PiecewiseLinear {
+                breakpoints: vec![
+                    CurveBreakpoint {
+                        time: Decimal256::percent(20),
+                        released: Decimal256::percent(50),
+                    },
+                    CurveBreakpoint {
+                        time: Decimal256::percent(40),
+                        released: Decimal256::percent(30),
+                    },
+                ],
+            },
+            // Breakpoints must be inside the stream duration
+            EmissionCurve::PiecewiseLinear {
+                breakpoints: vec![CurveBreakpoint {
+                    time: Decimal256::percent(100),
+                    released: Decimal256::percent(50),
+                }],
+            },
+            EmissionCurve::PiecewiseLinear {
+                breakpoints: vec![],
+            },
+            EmissionCurve::ExponentialDecay {
+                epochs: 0,
+                decay: Decimal256::percent(50),
+            },
+            EmissionCurve::ExponentialDecay {
+                epochs: 10,
+                decay: Decimal256::percent(100),
+            },
+            EmissionCurve::BackLoaded { exponent: 1 },
+        ];
+
+        for curve in malformed_curves {
+            let create_stream_msg = CreateStreamMsgBuilder::new(
+                "stream",
+                test_accounts.creator_1.as_ref(),
+                coin(1_000_000, "out_denom"),
+                "in_denom",
+                bootstrapping_start_time,
+                start_time,
+                end_time,
+            )
+            .emission_curve(curve)
+            .build();
+
+            let res = app
+                .execute_contract(
+                    test_accounts.creator_1.clone(),
+                    controller_address.clone(),
+                    &create_stream_msg,
+                    &[coin(100, "fee_denom"), coin(1_000_000, "out_denom")],
+                )
+                .unwrap_err();
+            let err = res.source().unwrap().source().unwrap();
+            let error = err.downcast_ref::<StreamSwapError>().unwrap();
+            assert_eq!(*error, StreamSwapError::InvalidEmissionCurve {});
+        }
+    }
+}
@@ -0,0 +1,272 @@
+#[cfg(test)]
+mod exit_fee_override {
+    use crate::helpers::mock_messages::CreateStreamMsgBuilder;
+    use crate::helpers::suite::SuiteBuilder;
+    use crate::helpers::utils::get_funds_from_res;
+    use crate::helpers::{
+        mock_messages::get_controller_inst_msg, suite::Suite, utils::get_contract_address_from_res,
+    };
+    use cosmwasm_std::{coin, Addr, Binary, BlockInfo, Coin, Decimal256, QuerierWrapper, Uint128};
+    use cw_multi_test::Executor;
+    use streamswap_controller::error::ContractError as ControllerError;
+    use streamswap_stream::ContractError as StreamSwapError;
+    use streamswap_types::controller::{
+        ExecuteMsg as ControllerExecuteMsg, ExitFeeOverrideTarget, QueryMsg as ControllerQueryMsg,
+    };
+    use streamswap_types::stream::{
+        ExecuteMsg as StreamSwapExecuteMsg, QueryMsg as StreamSwapQueryMsg, StreamResponse,
+    };
+
+    fn query_exit_fee(querier: QuerierWrapper, stream: &str) -> Decimal256 {
+        let stream: StreamResponse = querier
+            .query_wasm_smart(Addr::unchecked(stream), &StreamSwapQueryMsg::Stream {})
+            .unwrap();
+        stream.exit_fee_percent
+    }
+
+    #[test]
+    fn exit_fee_overrides() {
+        let Suite {
+            mut app,
+            test_accounts,
+            stream_swap_code_id,
+            stream_swap_controller_code_id,
+            vesting_code_id,
+        } = SuiteBuilder::default().build();
+
+        let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+        let controller_address = app
+            .instantiate_contract(
+                stream_swap_controller_code_id,
+                test_accounts.admin.clone(),
+                &msg,
+                &[],
+                "Controller".to_string(),
+                None,
+            )
+            .unwrap();
+
+        let start_time = app.block_info().time.plus_seconds(100);
+        let end_time = app.block_info().time.plus_seconds(200);
+        let bootstrapping_start_time = app.block_info().time.plus_seconds(50);
+        let create_stream_msg = CreateStreamMsgBuilder::new(
+            "Stream Swap tests",
+            test_accounts.creator_1.as_ref(),
+            coin(1_000_000, "out_denom"),
+            "in_denom",
+            bootstrapping_start_time,
+            start_time,
+            end_time,
+        )
+        .build();
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address.clone(),
+                &create_stream_msg,
+                &[coin(100, "fee_denom"), coin(1_000_000, "out_denom")],
+            )
+            .unwrap();
+        let stream_swap_contract_address = get_contract_address_from_res(res);
+        assert_eq!(
+            query_exit_fee(app.wrap(), &stream_swap_contract_address),
+            Decimal256::percent(1)
+        );
+
+        // Only protocol admin can set overrides
+        let set_override = |target: ExitFeeOverrideTarget, percent: Option<u64>| {
+            ControllerExecuteMsg::SetExitFeeOverride {
+                target,
+                exit_fee_percent: percent.map(Decimal256::percent),
+            }
+        };
+        let err = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address.clone(),
+                &set_override(
+                    ExitFeeOverrideTarget::Creator {
+                        address: test_accounts.creator_1.to_string(),
+                    },
+                    Some(0),
+                ),
+                &[],
+            )
+            .unwrap_err();
+        let error = err.downcast_ref::<ControllerError>().unwrap();
+        assert_eq!(*error, ControllerError::Unauthorized {});
+
+        // Creator and in denom overrides do not change existing streams
+        for (target, percent) in [
+            (
+                ExitFeeOverrideTarget::InDenom {
+                    denom: "in_denom".to_string(),
+                },
+                3,
+            ),
+            (
+                ExitFeeOverrideTarget::Creator {
+                    address: test_accounts.creator_1.to_string(),
+                },
+                2,
+            ),
+        ] {
+            app.execute_contract(
+                test_accounts.admin.clone(),
+                controller_address.clone(),
+                &set_override(target, Some(percent)),
+                &[],
+            )
+            .unwrap();
+            assert_eq!(
+                query_exit_fee(app.wrap(), &stream_swap_contract_address),
+                Decimal256::percent(1)
+            );
+        }
+        let creator_override = app
+            .wrap()
+            .query_wasm_smart::<Option<Decimal256>>(
+                controller_address.clone(),
+                &ControllerQueryMsg::ExitFeeOverride {
+                    stream: stream_swap_contract_address.clone(),
+                },
+            )
+            .unwrap();
+        assert_eq!(creator_override, Some(Decimal256::percent(2)));
+
+        // Streams created afterwards resolve the overrides, creator override takes precedence
+        // over in denom override
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address.clone(),
+                &CreateStreamMsgBuilder::new(
+                    "Stream Swap tests",
+                    test_accounts.creator_1.as_ref(),
+                    coin(1_000_000, "out_denom"),
+                    "in_denom",
+                    bootstrapping_start_time,
+                    start_time,
+                    end_time,
+                )
+                .salt(Binary::from(vec![1; 32]))
+                .build(),
+                &[coin(100, "fee_denom"), coin(1_000_000, "out_denom")],
+            )
+            .unwrap();
+        let new_stream_address = get_contract_address_from_res(res);
+        assert_eq!(
+            query_exit_fee(app.wrap(), &new_stream_address),
+            Decimal256::percent(2)
+        );
+
+        // Stream overrides are only set on registered streams
+        let err = app
+            .execute_contract(
+                test_accounts.admin.clone(),
+                controller_address.clone(),
+                &set_override(
+                    ExitFeeOverrideTarget::Stream {
+                        address: test_accounts.wrong_user.to_string(),
+                    },
+                    Some(5),
+                ),
+                &[],
+            )
+            .unwrap_err();
+        let error = err.downcast_ref::<ControllerError>().unwrap();
+        assert_eq!(*error, ControllerError::StreamNotRegistered {});
+
+        // Stream override takes precedence, removing it falls back to the creator override
+        let stream_target = || ExitFeeOverrideTarget::Stream {
+            address: stream_swap_contract_address.clone(),
+        };
+        app.execute_contract(
+            test_accounts.admin.clone(),
+            controller_address.clone(),
+            &set_override(stream_target(), Some(5)),
+            &[],
+        )
+        .unwrap();
+        assert_eq!(
+            query_exit_fee(app.wrap(), &stream_swap_contract_address),
+            Decimal256::percent(5)
+        );
+        app.execute_contract(
+            test_accounts.admin.clone(),
+            controller_address.clone(),
+            &set_override(stream_target(), None),
+            &[],
+        )
+        .unwrap();
+        assert_eq!(
+            query_exit_fee(app.wrap(), &stream_swap_contract_address),
+            Decimal256::percent(2)
+        );
+
+        app.set_block(BlockInfo {
+            height: 1_100,
+            time: start_time,
+            chain_id: "test".to_string(),
+        });
+
+        // Stream override can not be changed once the stream starts
+        let err = app
+            .execute_contract(
+                test_accounts.admin.clone(),
+                controller_address.clone(),
+                &set_override(stream_target(), Some(10)),
+                &[],
+            )
+            .unwrap_err();
+        let error = err.root_cause().downcast_ref::<StreamSwapError>().unwrap();
+        assert_eq!(
+            *error,
+            StreamSwapError::OperationNotAllowed {
+                current_status: "Active".to_string()
+            }
+        );
+        assert_eq!(
+            query_exit_fee(app.wrap(), &stream_swap_contract_address),
+            Decimal256::percent(2)
+        );
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            Addr::unchecked(stream_swap_contract_address.clone()),
+            &StreamSwapExecuteMsg::Subscribe {
+                proof: None,
+                on_behalf_of: None,
+                referrer: None,
+            },
+            &[coin(1_000, "in_denom")],
+        )
+        .unwrap();
+
+        app.set_block(BlockInfo {
+            height: 1_200,
+            time: end_time,
+            chain_id: "test".to_string(),
+        });
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapExecuteMsg::FinalizeStream {
+                    new_treasury: None,
+                    create_pool: None,
+                    salt: None,
+                },
+                &[],
+            )
+            .unwrap();
+        // Swap fee is collected with the creator override
+        let funds = get_funds_from_res(res);
+        assert!(funds.contains(&(
+            test_accounts.admin.to_string(),
+            Coin {
+                denom: "in_denom".to_string(),
+                amount: Uint128::new(20)
+            }
+        )));
+    }
+}
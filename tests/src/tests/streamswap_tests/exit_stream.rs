@@ -7,10 +7,15 @@ mod exit_stream {
         suite::Suite,
         utils::{get_contract_address_from_res, get_funds_from_res},
     };
-    use cosmwasm_std::{coin, Addr, BlockInfo, Uint128, Uint256};
+    use cosmwasm_std::{coin, Addr, Binary, BlockInfo, Uint128, Uint256};
     use cw_multi_test::Executor;
+    use cw_vesting::vesting::Schedule;
+    use sha2::{Digest, Sha256};
     use streamswap_stream::ContractError;
-    use streamswap_types::stream::ExecuteMsg as StreamSwapExecuteMsg;
+    use streamswap_types::controller::VestingConfig;
+    use streamswap_types::stream::{
+        ExecuteMsg as StreamSwapExecuteMsg, PositionResponse, QueryMsg as StreamSwapQueryMsg,
+    };
 
     #[test]
     fn happy_path() {
@@ -66,7 +71,11 @@ mod exit_stream {
             chain_id: "test".to_string(),
         });
 
-        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
 
         let _res = app
             .execute_contract(
@@ -158,7 +167,11 @@ mod exit_stream {
         });
 
         // Subscriber 1 subscribes to the stream
-        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
 
         let _res = app
             .execute_contract(
@@ -307,7 +320,11 @@ mod exit_stream {
         });
 
         // Subscriber 1 subscribes to the stream
-        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
 
         let _res = app
             .execute_contract(
@@ -517,7 +534,11 @@ mod exit_stream {
         });
 
         // Subscriber subscribes to the stream
-        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
         app.execute_contract(
             test_accounts.subscriber_1.clone(),
             Addr::unchecked(stream_swap_contract_address.clone()),
@@ -558,4 +579,191 @@ mod exit_stream {
             ContractError::SubscriberAlreadyExited {}
         );
     }
+
+    #[test]
+    fn exit_positions_crank() {
+        let Suite {
+            mut app,
+            test_accounts,
+            stream_swap_code_id,
+            stream_swap_controller_code_id,
+            vesting_code_id,
+        } = SuiteBuilder::default().build();
+
+        let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+        let controller_address = app
+            .instantiate_contract(
+                stream_swap_controller_code_id,
+                test_accounts.admin.clone(),
+                &msg,
+                &[],
+                "Controller".to_string(),
+                None,
+            )
+            .unwrap();
+
+        let start_time = app.block_info().time.plus_seconds(100);
+        let end_time = app.block_info().time.plus_seconds(200);
+        let bootstrapping_start_time = app.block_info().time.plus_seconds(50);
+        let create_stream_msg = CreateStreamMsgBuilder::new(
+            "stream",
+            test_accounts.creator_1.as_ref(),
+            coin(1_000_000, "out_denom"),
+            "in_denom",
+            bootstrapping_start_time,
+            start_time,
+            end_time,
+        )
+        .subscriber_vesting(VestingConfig {
+            schedule: Schedule::SaturatingLinear,
+            vesting_duration_seconds: 150,
+            unbonding_duration_seconds: 0,
+        })
+        .build();
+
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address,
+                &create_stream_msg,
+                &[coin(100, "fee_denom"), coin(1_000_000, "out_denom")],
+            )
+            .unwrap();
+        let stream_swap_contract_address = Addr::unchecked(get_contract_address_from_res(res));
+
+        app.set_block(BlockInfo {
+            height: 1_100,
+            time: start_time,
+            chain_id: "test".to_string(),
+        });
+        for subscriber in [&test_accounts.subscriber_1, &test_accounts.subscriber_2] {
+            app.execute_contract(
+                subscriber.clone(),
+                stream_swap_contract_address.clone(),
+                &StreamSwapExecuteMsg::Subscribe {
+                    proof: None,
+                    on_behalf_of: None,
+                    referrer: None,
+                },
+                &[coin(1_000, "in_denom")],
+            )
+            .unwrap();
+        }
+
+        // Crank is only available once the stream is over
+        let res = app
+            .execute_contract(
+                test_accounts.wrong_user.clone(),
+                stream_swap_contract_address.clone(),
+                &StreamSwapExecuteMsg::ExitPositions {
+                    start_after: None,
+                    limit: None,
+                },
+                &[],
+            )
+            .unwrap_err();
+        let err = res.source().unwrap();
+        let error = err.downcast_ref::<ContractError>().unwrap();
+        assert_eq!(
+            *error,
+            ContractError::OperationNotAllowed {
+                current_status: "Active".to_string()
+            }
+        );
+
+        app.set_block(BlockInfo {
+            height: 1_250,
+            time: end_time.plus_seconds(1),
+            chain_id: "test".to_string(),
+        });
+        // Subscriber 1 exits on its own with the salt the crank derives for subscriber 2, it is
+        // namespaced by subscriber 1 and does not block the crank
+        let mut hasher = Sha256::new();
+        hasher.update(b"subscriber_vesting");
+        hasher.update((test_accounts.subscriber_2.as_str().len() as u64).to_be_bytes());
+        hasher.update(test_accounts.subscriber_2.as_bytes());
+        let crank_salt = Binary::from(hasher.finalize().as_slice());
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::ExitStream {
+                salt: Some(crank_salt),
+            },
+            &[],
+        )
+        .unwrap();
+
+        // Anyone can exit the remaining positions, already exited ones are skipped
+        let res = app
+            .execute_contract(
+                test_accounts.wrong_user.clone(),
+                stream_swap_contract_address.clone(),
+                &StreamSwapExecuteMsg::ExitPositions {
+                    start_after: None,
+                    limit: None,
+                },
+                &[],
+            )
+            .unwrap();
+        // Each exited owner gets an event with the attributes of its exit
+        let exit_events: Vec<_> = res
+            .events
+            .iter()
+            .filter(|event| event.ty == "wasm-exit_position")
+            .collect();
+        assert_eq!(exit_events.len(), 1);
+        assert!(exit_events[0]
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "owner" && attr.value == test_accounts.subscriber_2.as_str()));
+        assert!(exit_events[0]
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "purchased" && attr.value == "500000"));
+
+        let position: PositionResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::Position {
+                    owner: test_accounts.subscriber_2.to_string(),
+                    at_time: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(position.exit_date, end_time.plus_seconds(1));
+
+        // Purchased tokens are sent to the vesting contract of the owner
+        let vesting_address: Addr = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::SubscriberVesting {
+                    addr: test_accounts.subscriber_2.to_string(),
+                },
+            )
+            .unwrap();
+        let balance = app
+            .wrap()
+            .query_balance(vesting_address, "out_denom")
+            .unwrap();
+        assert_eq!(balance.amount, Uint128::new(500_000));
+
+        // Nothing left to exit
+        let res = app
+            .execute_contract(
+                test_accounts.wrong_user.clone(),
+                stream_swap_contract_address.clone(),
+                &StreamSwapExecuteMsg::ExitPositions {
+                    start_after: None,
+                    limit: None,
+                },
+                &[],
+            )
+            .unwrap();
+        assert!(res.events.iter().any(|event| event
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "exited" && attr.value == "0")));
+    }
 }
@@ -6,7 +6,7 @@ mod finalize_stream_tests {
     use crate::helpers::suite::SuiteBuilder;
     use crate::helpers::utils::{get_contract_address_from_res, get_funds_from_res};
     use crate::helpers::{mock_messages::get_controller_inst_msg, suite::Suite};
-    use cosmwasm_std::{coin, Addr, BlockInfo, Coin, Uint128, Uint256};
+    use cosmwasm_std::{coin, Addr, BlockInfo, Coin, Decimal256, Uint128, Uint256};
     use cw_multi_test::Executor;
     use streamswap_types::stream::{
         ExecuteMsg as StreamSwapExecuteMsg, FinalizedStatus, QueryMsg as StreamSwapQueryMsg,
@@ -55,7 +55,11 @@ mod finalize_stream_tests {
             )
             .unwrap();
         let stream_swap_contract_address: String = get_contract_address_from_res(res);
-        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
         app.set_block(BlockInfo {
             height: 1_100,
             time: start_time,
@@ -174,7 +178,11 @@ mod finalize_stream_tests {
             )
             .unwrap();
         let stream_swap_contract_address: String = get_contract_address_from_res(res);
-        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
         app.set_block(BlockInfo {
             height: 1_100,
             time: start_time,
@@ -294,7 +302,11 @@ mod finalize_stream_tests {
             )
             .unwrap();
         let stream_swap_contract_address: String = get_contract_address_from_res(res);
-        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
         app.set_block(BlockInfo {
             height: 1_100,
             time: start_time,
@@ -428,7 +440,11 @@ mod finalize_stream_tests {
             )
             .unwrap();
         let stream_swap_contract_address: String = get_contract_address_from_res(res);
-        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
         app.set_block(BlockInfo {
             height: 1_100,
             time: start_time,
@@ -540,7 +556,11 @@ mod finalize_stream_tests {
             )
             .unwrap();
         let stream_swap_contract_address: String = get_contract_address_from_res(res);
-        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
         app.set_block(BlockInfo {
             height: 1_100,
             time: start_time,
@@ -653,6 +673,141 @@ mod finalize_stream_tests {
         assert_eq!(contract_balance.len(), 0);
     }
 
+    #[test]
+    fn finalize_stream_min_average_price_not_reached() {
+        let Suite {
+            mut app,
+            test_accounts,
+            stream_swap_code_id,
+            stream_swap_controller_code_id,
+            vesting_code_id,
+        } = SuiteBuilder::default().build();
+        let start_time = app.block_info().time.plus_seconds(100);
+        let end_time = app.block_info().time.plus_seconds(200);
+        let bootstrapping_start_time = app.block_info().time.plus_seconds(50);
+        let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+        let controller_address = app
+            .instantiate_contract(
+                stream_swap_controller_code_id,
+                test_accounts.admin.clone(),
+                &msg,
+                &[],
+                "Controller".to_string(),
+                None,
+            )
+            .unwrap();
+        // Threshold is reached but the average price of 200 / 1_000_000 is below the floor
+        let create_stream_msg = CreateStreamMsgBuilder::new(
+            "Stream Swap tests",
+            test_accounts.creator_1.as_ref(),
+            coin(1_000_000, "out_denom"),
+            "in_denom",
+            bootstrapping_start_time,
+            start_time,
+            end_time,
+        )
+        .threshold(Uint256::from(100u128))
+        .min_average_price(Decimal256::from_str("0.001").unwrap())
+        .build();
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address.clone(),
+                &create_stream_msg,
+                &[coin(100, "fee_denom"), coin(1_000_000, "out_denom")],
+            )
+            .unwrap();
+        let stream_swap_contract_address: String = get_contract_address_from_res(res);
+
+        app.set_block(BlockInfo {
+            height: 1_100,
+            time: start_time,
+            chain_id: "test".to_string(),
+        });
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            Addr::unchecked(stream_swap_contract_address.clone()),
+            &subscribe_msg,
+            &[coin(200, "in_denom")],
+        )
+        .unwrap();
+
+        app.set_block(BlockInfo {
+            height: 1_100,
+            time: end_time,
+            chain_id: "test".to_string(),
+        });
+
+        // Finalize refunds all out tokens to the treasury
+        let finalized_msg = StreamSwapExecuteMsg::FinalizeStream {
+            new_treasury: None,
+            create_pool: None,
+            salt: None,
+        };
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &finalized_msg,
+                &[],
+            )
+            .unwrap();
+        let stream_swap_funds = get_funds_from_res(res);
+        assert_eq!(
+            stream_swap_funds,
+            vec![(
+                String::from(test_accounts.creator_1.clone()),
+                Coin {
+                    denom: "out_denom".to_string(),
+                    amount: Uint128::new(1_000_000)
+                }
+            ),]
+        );
+
+        let stream: StreamResponse = app
+            .wrap()
+            .query_wasm_smart(
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapQueryMsg::Stream {},
+            )
+            .unwrap();
+        assert_eq!(
+            stream.status,
+            Status::Finalized(FinalizedStatus::MinAveragePriceNotReached)
+        );
+
+        // Subscriber is fully refunded
+        let res = app
+            .execute_contract(
+                test_accounts.subscriber_1.clone(),
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapExecuteMsg::ExitStream { salt: None },
+                &[],
+            )
+            .unwrap();
+        let stream_swap_funds = get_funds_from_res(res);
+        assert_eq!(
+            stream_swap_funds,
+            vec![(
+                String::from(test_accounts.subscriber_1.clone()),
+                Coin {
+                    denom: "in_denom".to_string(),
+                    amount: Uint128::new(200)
+                }
+            ),]
+        );
+        let contract_balance = app
+            .wrap()
+            .query_all_balances(Addr::unchecked(stream_swap_contract_address.clone()))
+            .unwrap();
+        assert_eq!(contract_balance.len(), 0);
+    }
+
     #[test]
     fn out_amount_refund() {
         let Suite {
@@ -702,7 +857,11 @@ mod finalize_stream_tests {
             )
             .unwrap();
         let stream_swap_contract_address: String = get_contract_address_from_res(res);
-        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
 
         app.set_block(BlockInfo {
             height: 1_100,
@@ -0,0 +1,184 @@
+#[cfg(test)]
+mod hard_cap {
+    use crate::helpers::mock_messages::CreateStreamMsgBuilder;
+    use crate::helpers::suite::SuiteBuilder;
+    use crate::helpers::utils::get_funds_from_res;
+    use crate::helpers::{
+        mock_messages::get_controller_inst_msg, suite::Suite, utils::get_contract_address_from_res,
+    };
+    use cosmwasm_std::{coin, Addr, BlockInfo, Coin, Uint128, Uint256};
+    use cw_multi_test::Executor;
+    use streamswap_stream::ContractError as StreamSwapError;
+    use streamswap_types::stream::{
+        ExecuteMsg as StreamSwapExecuteMsg, QueryMsg as StreamSwapQueryMsg, Status, StreamResponse,
+    };
+
+    #[test]
+    fn stream_ends_when_hard_cap_reached() {
+        let Suite {
+            mut app,
+            test_accounts,
+            stream_swap_code_id,
+            stream_swap_controller_code_id,
+            vesting_code_id,
+        } = SuiteBuilder::default().build();
+
+        let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+        let controller_address = app
+            .instantiate_contract(
+                stream_swap_controller_code_id,
+                test_accounts.admin.clone(),
+                &msg,
+                &[],
+                "Controller".to_string(),
+                None,
+            )
+            .unwrap();
+
+        let start_time = app.block_info().time.plus_seconds(100);
+        let end_time = app.block_info().time.plus_seconds(200);
+        let bootstrapping_start_time = app.block_info().time.plus_seconds(50);
+        let create_stream_msg = CreateStreamMsgBuilder::new(
+            "Stream Swap tests",
+            test_accounts.creator_1.as_ref(),
+            coin(1_000_000, "out_denom"),
+            "in_denom",
+            bootstrapping_start_time,
+            start_time,
+            end_time,
+        )
+        .hard_cap(Uint256::from(500u128))
+        .build();
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address.clone(),
+                &create_stream_msg,
+                &[coin(100, "fee_denom"), coin(1_000_000, "out_denom")],
+            )
+            .unwrap();
+        let stream_swap_contract_address = get_contract_address_from_res(res);
+
+        app.set_block(BlockInfo {
+            height: 1_100,
+            time: start_time,
+            chain_id: "test".to_string(),
+        });
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            Addr::unchecked(stream_swap_contract_address.clone()),
+            &subscribe_msg,
+            &[coin(1_000, "in_denom")],
+        )
+        .unwrap();
+
+        // 60% of the in supply would be spent, the distribution is clipped at the cap
+        let capped_at = start_time.plus_seconds(60);
+        app.set_block(BlockInfo {
+            height: 1_200,
+            time: capped_at,
+            chain_id: "test".to_string(),
+        });
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            Addr::unchecked(stream_swap_contract_address.clone()),
+            &StreamSwapExecuteMsg::SyncStream {},
+            &[],
+        )
+        .unwrap();
+
+        let stream: StreamResponse = app
+            .wrap()
+            .query_wasm_smart(
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapQueryMsg::Stream {},
+            )
+            .unwrap();
+        assert_eq!(stream.status, Status::Ended);
+        assert_eq!(stream.end_time, capped_at);
+        assert_eq!(stream.spent_in, Uint256::from(500u128));
+        assert_eq!(stream.in_supply, Uint256::from(500u128));
+        assert_eq!(stream.out_remaining, Uint256::from(500_000u128));
+
+        // Subscriptions are closed once the cap is reached
+        let err = app
+            .execute_contract(
+                test_accounts.subscriber_2.clone(),
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &subscribe_msg,
+                &[coin(1_000, "in_denom")],
+            )
+            .unwrap_err();
+        let error = err.source().unwrap().downcast_ref::<StreamSwapError>();
+        assert_eq!(
+            error,
+            Some(&StreamSwapError::OperationNotAllowed {
+                current_status: "Ended".to_string()
+            })
+        );
+
+        // Nothing is spent after the cap
+        app.set_block(BlockInfo {
+            height: 1_300,
+            time: end_time,
+            chain_id: "test".to_string(),
+        });
+        let finalize_msg = StreamSwapExecuteMsg::FinalizeStream {
+            new_treasury: None,
+            create_pool: None,
+            salt: None,
+        };
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &finalize_msg,
+                &[],
+            )
+            .unwrap();
+        // Unsold out tokens are refunded to the treasury
+        let funds = get_funds_from_res(res);
+        assert!(funds.contains(&(
+            test_accounts.creator_1.to_string(),
+            Coin {
+                denom: "out_denom".to_string(),
+                amount: Uint128::new(500_000)
+            }
+        )));
+
+        // Subscriber receives the purchased tokens and the unspent in tokens
+        let res = app
+            .execute_contract(
+                test_accounts.subscriber_1.clone(),
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapExecuteMsg::ExitStream { salt: None },
+                &[],
+            )
+            .unwrap();
+        let funds = get_funds_from_res(res);
+        assert_eq!(
+            funds,
+            vec![
+                (
+                    test_accounts.subscriber_1.to_string(),
+                    Coin {
+                        denom: "out_denom".to_string(),
+                        amount: Uint128::new(500_000)
+                    }
+                ),
+                (
+                    test_accounts.subscriber_1.to_string(),
+                    Coin {
+                        denom: "in_denom".to_string(),
+                        amount: Uint128::new(500)
+                    }
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,293 @@
+#[cfg(test)]
+mod hooks {
+    use crate::helpers::mock_messages::CreateStreamMsgBuilder;
+    use crate::helpers::suite::SuiteBuilder;
+    use crate::helpers::{
+        mock_messages::get_controller_inst_msg, suite::Suite, utils::get_contract_address_from_res,
+    };
+    use cosmwasm_std::{
+        coin, to_json_binary, Addr, Binary, BlockInfo, Deps, DepsMut, Empty, Env, MessageInfo,
+        Response, StdError, StdResult, Uint256,
+    };
+    use cw_multi_test::{ContractWrapper, Executor};
+    use cw_storage_plus::Item;
+    use streamswap_stream::ContractError as StreamSwapError;
+    use streamswap_types::stream::{
+        ExecuteMsg as StreamSwapExecuteMsg, FinalizedStatus, HooksResponse, PositionDelta,
+        QueryMsg as StreamSwapQueryMsg, StreamHookExecuteMsg, StreamHookMsg,
+    };
+
+    const FAIL: Item<bool> = Item::new("fail");
+    const RECEIVED: Item<Vec<StreamHookMsg>> = Item::new("received");
+
+    fn hook_instantiate(deps: DepsMut, _: Env, _: MessageInfo, fail: bool) -> StdResult<Response> {
+        FAIL.save(deps.storage, &fail)?;
+        RECEIVED.save(deps.storage, &vec![])?;
+        Ok(Response::new())
+    }
+
+    fn hook_execute(
+        deps: DepsMut,
+        _: Env,
+        _: MessageInfo,
+        msg: StreamHookExecuteMsg,
+    ) -> StdResult<Response> {
+        if FAIL.load(deps.storage)? {
+            return Err(StdError::generic_err("hook failed"));
+        }
+        let StreamHookExecuteMsg::StreamHook(msg) = msg;
+        RECEIVED.update(deps.storage, |mut received| -> StdResult<_> {
+            received.push(msg);
+            Ok(received)
+        })?;
+        Ok(Response::new())
+    }
+
+    fn hook_query(deps: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+        to_json_binary(&RECEIVED.load(deps.storage)?)
+    }
+
+    #[test]
+    fn hooks_notified_of_stream_activity() {
+        let Suite {
+            mut app,
+            test_accounts,
+            stream_swap_code_id,
+            stream_swap_controller_code_id,
+            vesting_code_id,
+        } = SuiteBuilder::default().build();
+
+        let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+        let controller_address = app
+            .instantiate_contract(
+                stream_swap_controller_code_id,
+                test_accounts.admin.clone(),
+                &msg,
+                &[],
+                "Controller".to_string(),
+                None,
+            )
+            .unwrap();
+
+        let hook_code_id = app.store_code(Box::new(ContractWrapper::new(
+            hook_execute,
+            hook_instantiate,
+            hook_query,
+        )));
+        let mut hooks = vec![];
+        for fail in [false, true] {
+            let hook = app
+                .instantiate_contract(
+                    hook_code_id,
+                    test_accounts.admin.clone(),
+                    &fail,
+                    &[],
+                    "Hook".to_string(),
+                    None,
+                )
+                .unwrap();
+            hooks.push(hook);
+        }
+        let (hook, failing_hook) = (hooks[0].clone(), hooks[1].clone());
+
+        let start_time = app.block_info().time.plus_seconds(100);
+        let end_time = app.block_info().time.plus_seconds(200);
+        let bootstrapping_start_time = app.block_info().time.plus_seconds(50);
+        let create_stream_msg = CreateStreamMsgBuilder::new(
+            "Stream Swap tests",
+            test_accounts.creator_1.as_ref(),
+            coin(1_000_000, "out_denom"),
+            "in_denom",
+            bootstrapping_start_time,
+            start_time,
+            end_time,
+        )
+        .hooks(vec![hook.to_string()])
+        .build();
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address.clone(),
+                &create_stream_msg,
+                &[coin(100, "fee_denom"), coin(1_000_000, "out_denom")],
+            )
+            .unwrap();
+        let stream_swap_contract_address = Addr::unchecked(get_contract_address_from_res(res));
+
+        // Only stream admin can register hooks
+        let add_hook_msg = StreamSwapExecuteMsg::AddHook {
+            addr: failing_hook.to_string(),
+        };
+        let err = app
+            .execute_contract(
+                test_accounts.subscriber_1.clone(),
+                stream_swap_contract_address.clone(),
+                &add_hook_msg,
+                &[],
+            )
+            .unwrap_err();
+        let error = err.source().unwrap().downcast_ref::<StreamSwapError>();
+        assert_eq!(error, Some(&StreamSwapError::Unauthorized {}));
+
+        app.execute_contract(
+            test_accounts.creator_1.clone(),
+            stream_swap_contract_address.clone(),
+            &add_hook_msg,
+            &[],
+        )
+        .unwrap();
+        let registered: HooksResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::Hooks {},
+            )
+            .unwrap();
+        assert_eq!(
+            registered.hooks,
+            vec![hook.to_string(), failing_hook.to_string()]
+        );
+
+        // Hooks can not be updated once the stream leaves waiting status
+        app.set_block(BlockInfo {
+            height: 1_100,
+            time: start_time,
+            chain_id: "test".to_string(),
+        });
+        let err = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                stream_swap_contract_address.clone(),
+                &StreamSwapExecuteMsg::RemoveHook {
+                    addr: failing_hook.to_string(),
+                },
+                &[],
+            )
+            .unwrap_err();
+        let error = err.source().unwrap().downcast_ref::<StreamSwapError>();
+        assert_eq!(
+            error,
+            Some(&StreamSwapError::OperationNotAllowed {
+                current_status: "Active".to_string()
+            })
+        );
+
+        // Failing hook does not block the subscription
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
+        let res = app
+            .execute_contract(
+                test_accounts.subscriber_1.clone(),
+                stream_swap_contract_address.clone(),
+                &subscribe_msg,
+                &[coin(1_000, "in_denom")],
+            )
+            .unwrap();
+        assert!(res.events.iter().any(|event| event
+            .attributes
+            .iter()
+            .any(|a| a.key == "action" && a.value == "hook_failed")));
+
+        app.set_block(BlockInfo {
+            height: 1_150,
+            time: start_time.plus_seconds(50),
+            chain_id: "test".to_string(),
+        });
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_swap_contract_address.clone(),
+            &subscribe_msg,
+            &[coin(1_000, "in_denom")],
+        )
+        .unwrap();
+
+        app.set_block(BlockInfo {
+            height: 1_175,
+            time: start_time.plus_seconds(75),
+            chain_id: "test".to_string(),
+        });
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::Withdraw {
+                cap: Some(Uint256::from(100u128)),
+            },
+            &[],
+        )
+        .unwrap();
+
+        app.set_block(BlockInfo {
+            height: 1_200,
+            time: end_time,
+            chain_id: "test".to_string(),
+        });
+        app.execute_contract(
+            test_accounts.creator_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::FinalizeStream {
+                new_treasury: None,
+                create_pool: None,
+                salt: None,
+            },
+            &[],
+        )
+        .unwrap();
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::ExitStream { salt: None },
+            &[],
+        )
+        .unwrap();
+
+        let received: Vec<StreamHookMsg> = app.wrap().query_wasm_smart(hook, &Empty {}).unwrap();
+        let subscriber = test_accounts.subscriber_1.to_string();
+        assert_eq!(
+            received,
+            vec![
+                StreamHookMsg::Subscribe {
+                    subscriber: subscriber.clone(),
+                    in_amount: Uint256::from(1_000u128),
+                    shares: Uint256::from(1_000u128),
+                    delta: PositionDelta::default(),
+                },
+                StreamHookMsg::Subscribe {
+                    subscriber: subscriber.clone(),
+                    in_amount: Uint256::from(1_000u128),
+                    shares: Uint256::from(2_000u128),
+                    delta: PositionDelta {
+                        purchased: Uint256::from(500_000u128),
+                        spent: Uint256::from(500u128),
+                    },
+                },
+                StreamHookMsg::Withdraw {
+                    owner: subscriber.clone(),
+                    withdraw_amount: Uint256::from(100u128),
+                    shares: Uint256::from(400u128),
+                    delta: PositionDelta {
+                        purchased: Uint256::from(249_999u128),
+                        spent: Uint256::from(750u128),
+                    },
+                },
+                StreamHookMsg::Finalize {
+                    status: FinalizedStatus::ThresholdReached,
+                    spent_in: Uint256::from(1_900u128),
+                    out_remaining: Uint256::zero(),
+                },
+                StreamHookMsg::Exit {
+                    owner: subscriber,
+                    purchased: Uint256::from(999_999u128),
+                    spent: Uint256::from(1_900u128),
+                    refunded: false,
+                    delta: PositionDelta {
+                        purchased: Uint256::from(250_000u128),
+                        spent: Uint256::from(650u128),
+                    },
+                },
+            ]
+        );
+    }
+}
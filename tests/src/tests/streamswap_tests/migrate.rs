@@ -0,0 +1,395 @@
+#[cfg(test)]
+mod migrate {
+    use crate::helpers::mock_messages::{get_controller_inst_msg, CreateStreamMsgBuilder};
+    use crate::helpers::suite::{Suite, SuiteBuilder};
+    use crate::helpers::utils::{
+        contract_storage_key, get_contract_address_from_res, get_wasm_attribute_with_key,
+    };
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::{
+        coin, from_json, to_json_vec, Addr, BlockInfo, Coin, CosmosMsg, Decimal256, Storage,
+        Timestamp, Uint128, Uint256, WasmMsg,
+    };
+    use cw2::ContractVersion;
+    use cw_denom::CheckedDenom;
+    use cw_multi_test::{ContractWrapper, Executor};
+    use cw_storage_plus::Map;
+    use streamswap_stream::ContractError as StreamSwapError;
+    use streamswap_types::controller::{
+        ExecuteMsg as ControllerExecuteMsg, FeeCollector, Params, StreamSelection,
+        LEGACY_DUST_SWEEP_GRACE_PERIOD,
+    };
+    use streamswap_types::stream::{
+        EmissionCurve, ExecuteMsg as StreamSwapExecuteMsg, Position, PositionResponse,
+        QueryMsg as StreamSwapQueryMsg, Status, StreamResponse, StreamState,
+    };
+
+    // Storage formats of the 0.2.0-beta3 streams
+    #[cw_serde]
+    struct LegacyParams {
+        protocol_admin: Addr,
+        fee_collector: Addr,
+        stream_creation_fee: Coin,
+        exit_fee_percent: Decimal256,
+        stream_contract_code_id: u64,
+        vesting_code_id: u64,
+        accepted_in_denoms: Vec<String>,
+        min_stream_duration: u64,
+        min_bootstrapping_duration: u64,
+        min_waiting_duration: u64,
+        tos_version: String,
+    }
+
+    #[cw_serde]
+    struct LegacyStatusInfo {
+        status: Status,
+        bootstrapping_start_time: Timestamp,
+        start_time: Timestamp,
+        end_time: Timestamp,
+        last_updated: Timestamp,
+    }
+
+    #[cw_serde]
+    struct LegacyStreamState {
+        dist_index: Decimal256,
+        out_remaining: Uint256,
+        in_denom: String,
+        in_supply: Uint256,
+        spent_in: Uint256,
+        shares: Uint256,
+        current_streamed_price: Decimal256,
+        out_asset: Coin,
+        status_info: LegacyStatusInfo,
+        threshold: Option<Uint256>,
+    }
+
+    #[cw_serde]
+    struct LegacyPosition {
+        owner: Addr,
+        in_balance: Uint256,
+        shares: Uint256,
+        index: Decimal256,
+        last_updated: Timestamp,
+        purchased: Uint256,
+        pending_purchase: Decimal256,
+        spent: Uint256,
+        exit_date: Timestamp,
+    }
+
+    #[test]
+    fn migrate_legacy_stream() {
+        let Suite {
+            mut app,
+            test_accounts,
+            stream_swap_code_id,
+            stream_swap_controller_code_id,
+            vesting_code_id,
+        } = SuiteBuilder::default().build();
+
+        let start_time = app.block_info().time.plus_seconds(100);
+        let end_time = app.block_info().time.plus_seconds(200);
+        let bootstrapping_start_time = app.block_info().time.plus_seconds(50);
+
+        let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+        let controller_address = app
+            .instantiate_contract(
+                stream_swap_controller_code_id,
+                test_accounts.admin.clone(),
+                &msg,
+                &[],
+                "Controller".to_string(),
+                None,
+            )
+            .unwrap();
+        let create_stream_msg = CreateStreamMsgBuilder::new(
+            "stream",
+            test_accounts.creator_1.as_ref(),
+            coin(1_000_000, "out_denom"),
+            "in_denom",
+            bootstrapping_start_time,
+            start_time,
+            end_time,
+        )
+        .build();
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address.clone(),
+                &create_stream_msg,
+                &[coin(100, "fee_denom"), coin(1_000_000, "out_denom")],
+            )
+            .unwrap();
+        let stream_address = Addr::unchecked(get_contract_address_from_res(res));
+
+        app.set_block(BlockInfo {
+            time: start_time,
+            height: 1,
+            chain_id: "SS".to_string(),
+        });
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_address.clone(),
+            &subscribe_msg,
+            &[coin(1_000_000, "in_denom")],
+        )
+        .unwrap();
+        // Position withdrawn before spending has nothing to exit
+        app.execute_contract(
+            test_accounts.subscriber_2.clone(),
+            stream_address.clone(),
+            &subscribe_msg,
+            &[coin(1_000, "in_denom")],
+        )
+        .unwrap();
+        app.execute_contract(
+            test_accounts.subscriber_2.clone(),
+            stream_address.clone(),
+            &StreamSwapExecuteMsg::Withdraw { cap: None },
+            &[],
+        )
+        .unwrap();
+
+        // Rewrite the stream state in the 0.2.0-beta3 format, streams of that version were
+        // administered by the protocol admin
+        let params_key = contract_storage_key(&stream_address, b"params");
+        let params: Params = from_json(app.storage().get(&params_key).unwrap()).unwrap();
+        let legacy_params = LegacyParams {
+            protocol_admin: params.protocol_admin.clone(),
+            fee_collector: params.fee_collectors[0].address.clone(),
+            stream_creation_fee: params.stream_creation_fee.clone(),
+            exit_fee_percent: params.exit_fee_percent,
+            stream_contract_code_id: params.stream_contract_code_id,
+            vesting_code_id: params.vesting_code_id,
+            accepted_in_denoms: params.accepted_in_denoms.clone(),
+            min_stream_duration: params.min_stream_duration,
+            min_bootstrapping_duration: params.min_bootstrapping_duration,
+            min_waiting_duration: params.min_waiting_duration,
+            tos_version: params.tos_version.clone(),
+        };
+        app.storage_mut()
+            .set(&params_key, &to_json_vec(&legacy_params).unwrap());
+
+        let state_key = contract_storage_key(&stream_address, b"ss");
+        let state: StreamState = from_json(app.storage().get(&state_key).unwrap()).unwrap();
+        let legacy_state = LegacyStreamState {
+            dist_index: state.dist_index,
+            out_remaining: state.out_remaining,
+            in_denom: "in_denom".to_string(),
+            in_supply: state.in_supply,
+            spent_in: state.spent_in,
+            shares: state.shares,
+            current_streamed_price: state.current_streamed_price,
+            out_asset: coin(1_000_000, "out_denom"),
+            status_info: LegacyStatusInfo {
+                status: state.status_info.status.clone(),
+                bootstrapping_start_time: state.status_info.bootstrapping_start_time,
+                start_time: state.status_info.start_time,
+                end_time: state.status_info.end_time,
+                last_updated: state.status_info.last_updated,
+            },
+            threshold: state.threshold,
+        };
+        app.storage_mut()
+            .set(&state_key, &to_json_vec(&legacy_state).unwrap());
+
+        let positions = Map::<&Addr, Position>::new("positions");
+        for owner in [&test_accounts.subscriber_1, &test_accounts.subscriber_2] {
+            let position_key = contract_storage_key(&stream_address, &positions.key(owner));
+            let position: Position = from_json(app.storage().get(&position_key).unwrap()).unwrap();
+            let legacy_position = LegacyPosition {
+                owner: position.owner,
+                in_balance: position.in_balance,
+                shares: position.shares,
+                index: position.index,
+                last_updated: position.last_updated,
+                purchased: position.purchased,
+                pending_purchase: position.pending_purchase,
+                spent: position.spent,
+                exit_date: position.exit_date,
+            };
+            app.storage_mut()
+                .set(&position_key, &to_json_vec(&legacy_position).unwrap());
+        }
+        for key in [b"controller".as_slice(), b"unexited_positions"] {
+            app.storage_mut()
+                .remove(&contract_storage_key(&stream_address, key));
+        }
+        app.storage_mut().set(
+            &contract_storage_key(&stream_address, b"contract_info"),
+            &to_json_vec(&ContractVersion {
+                contract: "crates.io:streamswap-stream".to_string(),
+                version: "0.2.0-beta3".to_string(),
+            })
+            .unwrap(),
+        );
+        app.execute(
+            controller_address.clone(),
+            CosmosMsg::Wasm(WasmMsg::UpdateAdmin {
+                contract_addr: stream_address.to_string(),
+                admin: test_accounts.admin.to_string(),
+            }),
+        )
+        .unwrap();
+        app.wrap()
+            .query_wasm_smart::<StreamResponse>(
+                stream_address.clone(),
+                &StreamSwapQueryMsg::Stream {},
+            )
+            .unwrap_err();
+
+        let new_code_id = app.store_code(Box::new(
+            ContractWrapper::new(
+                streamswap_stream::contract::execute,
+                streamswap_stream::contract::instantiate,
+                streamswap_stream::contract::query,
+            )
+            .with_reply(streamswap_stream::contract::reply)
+            .with_migrate(streamswap_stream::contract::migrate),
+        ));
+        let migrate_streams_msg = ControllerExecuteMsg::MigrateStreams {
+            new_code_id,
+            selection: StreamSelection::Ids {
+                stream_ids: vec![1],
+            },
+        };
+
+        // Controller can not migrate the stream until the protocol admin hands it over
+        app.execute_contract(
+            test_accounts.admin.clone(),
+            controller_address.clone(),
+            &migrate_streams_msg,
+            &[],
+        )
+        .unwrap_err();
+        app.execute(
+            test_accounts.admin.clone(),
+            CosmosMsg::Wasm(WasmMsg::UpdateAdmin {
+                contract_addr: stream_address.to_string(),
+                admin: controller_address.to_string(),
+            }),
+        )
+        .unwrap();
+        let res = app
+            .execute_contract(
+                test_accounts.admin.clone(),
+                controller_address.clone(),
+                &migrate_streams_msg,
+                &[],
+            )
+            .unwrap();
+        // Stream reports the applied migration in its own wasm event
+        assert!(res.events.iter().any(|event| event.ty == "wasm"
+            && event
+                .attributes
+                .iter()
+                .any(|attr| attr.key == "applied_migration" && attr.value == "0.2.0-beta4")));
+
+        // Legacy state is rewritten in the current format
+        let stream: StreamResponse = app
+            .wrap()
+            .query_wasm_smart(stream_address.clone(), &StreamSwapQueryMsg::Stream {})
+            .unwrap();
+        assert_eq!(
+            stream.in_denom,
+            CheckedDenom::Native("in_denom".to_string())
+        );
+        assert_eq!(
+            stream.out_asset.denom,
+            CheckedDenom::Native("out_denom".to_string())
+        );
+        assert_eq!(stream.out_asset.amount, Uint128::new(1_000_000));
+        assert_eq!(stream.emission_curve, EmissionCurve::Linear);
+        let params: Params = app
+            .wrap()
+            .query_wasm_smart(stream_address.clone(), &StreamSwapQueryMsg::Params {})
+            .unwrap();
+        assert_eq!(
+            params.fee_collectors,
+            vec![FeeCollector {
+                address: legacy_params.fee_collector,
+                weight: Decimal256::one(),
+            }]
+        );
+        assert_eq!(params.referral_fee_percent, Decimal256::zero());
+        assert_eq!(
+            params.dust_sweep_grace_period,
+            LEGACY_DUST_SWEEP_GRACE_PERIOD
+        );
+        let position_query = StreamSwapQueryMsg::Position {
+            owner: test_accounts.subscriber_1.to_string(),
+            at_time: None,
+        };
+        app.wrap()
+            .query_wasm_smart::<PositionResponse>(stream_address.clone(), &position_query)
+            .unwrap_err();
+
+        // Positions are migrated in pages by anyone
+        for (limit, completed) in [(Some(1), "false"), (None, "true")] {
+            let res = app
+                .execute_contract(
+                    test_accounts.wrong_user.clone(),
+                    stream_address.clone(),
+                    &StreamSwapExecuteMsg::MigratePositions { limit },
+                    &[],
+                )
+                .unwrap();
+            assert_eq!(
+                get_wasm_attribute_with_key(res, "completed".to_string()),
+                completed
+            );
+        }
+        let res = app
+            .execute_contract(
+                test_accounts.wrong_user.clone(),
+                stream_address.clone(),
+                &StreamSwapExecuteMsg::MigratePositions { limit: None },
+                &[],
+            )
+            .unwrap_err();
+        let err = res.source().unwrap();
+        let error = err.downcast_ref::<StreamSwapError>().unwrap();
+        assert_eq!(*error, StreamSwapError::PositionsAlreadyMigrated {});
+        let position: PositionResponse = app
+            .wrap()
+            .query_wasm_smart(stream_address.clone(), &position_query)
+            .unwrap();
+        assert_eq!(position.in_balance, Uint256::from(1_000_000u128));
+
+        // Migrated stream runs to the end, the withdrawn position does not hold back the sweep
+        app.set_block(BlockInfo {
+            time: end_time.plus_seconds(1),
+            height: 2,
+            chain_id: "SS".to_string(),
+        });
+        app.execute_contract(
+            test_accounts.creator_1.clone(),
+            stream_address.clone(),
+            &StreamSwapExecuteMsg::FinalizeStream {
+                new_treasury: None,
+                create_pool: None,
+                salt: None,
+            },
+            &[],
+        )
+        .unwrap();
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_address.clone(),
+            &StreamSwapExecuteMsg::ExitStream { salt: None },
+            &[],
+        )
+        .unwrap();
+        app.execute_contract(
+            test_accounts.wrong_user.clone(),
+            stream_address,
+            &StreamSwapExecuteMsg::SweepDust {},
+            &[],
+        )
+        .unwrap();
+    }
+}
@@ -1,13 +1,28 @@
+mod allowlist;
 mod cancel_stream;
+mod claim_purchased;
 mod create_stream;
+mod cw20;
+mod emission_curve;
+mod exit_fee_override;
 mod exit_stream;
 mod finalize_stream;
+mod hard_cap;
+mod hooks;
+mod migrate;
+mod pause_stream;
 mod pool;
+mod position_nft;
+mod referral;
 mod rounding_leftover;
 mod shares;
+mod simulate;
 mod subscribe;
+mod subscription_caps;
 mod sync_position;
 mod sync_stream;
 mod threshold;
+mod transfer_position;
+mod treasury_recipients;
 mod vesting;
 mod withdraw;
@@ -0,0 +1,260 @@
+#[cfg(test)]
+mod pause_stream {
+    use crate::helpers::mock_messages::CreateStreamMsgBuilder;
+    use crate::helpers::suite::SuiteBuilder;
+    use crate::helpers::{
+        mock_messages::get_controller_inst_msg, suite::Suite, utils::get_contract_address_from_res,
+    };
+    use cosmwasm_std::{coin, Addr, BlockInfo, Uint256};
+    use cw_multi_test::Executor;
+    use streamswap_stream::ContractError as StreamSwapError;
+    use streamswap_types::stream::{
+        ExecuteMsg as StreamSwapExecuteMsg, QueryMsg as StreamSwapQueryMsg, Status, StreamResponse,
+    };
+
+    #[test]
+    fn pause_and_resume_stream() {
+        let Suite {
+            mut app,
+            test_accounts,
+            stream_swap_code_id,
+            stream_swap_controller_code_id,
+            vesting_code_id,
+        } = SuiteBuilder::default().build();
+
+        let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+        let controller_address = app
+            .instantiate_contract(
+                stream_swap_controller_code_id,
+                test_accounts.admin.clone(),
+                &msg,
+                &[],
+                "Controller".to_string(),
+                None,
+            )
+            .unwrap();
+
+        let start_time = app.block_info().time.plus_seconds(100);
+        let end_time = app.block_info().time.plus_seconds(200);
+        let bootstrapping_start_time = app.block_info().time.plus_seconds(50);
+        let create_stream_msg = CreateStreamMsgBuilder::new(
+            "stream",
+            test_accounts.creator_1.as_ref(),
+            coin(1_000_000, "out_denom"),
+            "in_denom",
+            bootstrapping_start_time,
+            start_time,
+            end_time,
+        )
+        .build();
+
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address,
+                &create_stream_msg,
+                &[coin(100, "fee_denom"), coin(1_000_000, "out_denom")],
+            )
+            .unwrap();
+        let stream_swap_contract_address = Addr::unchecked(get_contract_address_from_res(res));
+
+        // Only active streams can be paused
+        let res = app
+            .execute_contract(
+                test_accounts.admin.clone(),
+                stream_swap_contract_address.clone(),
+                &StreamSwapExecuteMsg::PauseStream {},
+                &[],
+            )
+            .unwrap_err();
+        let err = res.source().unwrap();
+        let error = err.downcast_ref::<StreamSwapError>().unwrap();
+        assert_eq!(
+            *error,
+            StreamSwapError::OperationNotAllowed {
+                current_status: "Waiting".to_string()
+            }
+        );
+
+        app.set_block(BlockInfo {
+            height: 1_100,
+            time: start_time,
+            chain_id: "test".to_string(),
+        });
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::Subscribe {
+                proof: None,
+                on_behalf_of: None,
+                referrer: None,
+            },
+            &[coin(1_000, "in_denom")],
+        )
+        .unwrap();
+
+        app.set_block(BlockInfo {
+            height: 1_125,
+            time: start_time.plus_seconds(25),
+            chain_id: "test".to_string(),
+        });
+        // Only protocol admin can pause
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                stream_swap_contract_address.clone(),
+                &StreamSwapExecuteMsg::PauseStream {},
+                &[],
+            )
+            .unwrap_err();
+        let err = res.source().unwrap();
+        let error = err.downcast_ref::<StreamSwapError>().unwrap();
+        assert_eq!(*error, StreamSwapError::Unauthorized {});
+
+        app.execute_contract(
+            test_accounts.admin.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::PauseStream {},
+            &[],
+        )
+        .unwrap();
+
+        let stream: StreamResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::Stream {},
+            )
+            .unwrap();
+        assert_eq!(stream.status, Status::Paused);
+        assert_eq!(stream.out_remaining, Uint256::from(750_000u128));
+        assert_eq!(stream.in_supply, Uint256::from(750u128));
+
+        // Nothing is distributed while paused
+        app.set_block(BlockInfo {
+            height: 1_175,
+            time: start_time.plus_seconds(75),
+            chain_id: "test".to_string(),
+        });
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::SyncStream {},
+            &[],
+        )
+        .unwrap();
+        let stream: StreamResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::Stream {},
+            )
+            .unwrap();
+        assert_eq!(stream.out_remaining, Uint256::from(750_000u128));
+        assert_eq!(stream.in_supply, Uint256::from(750u128));
+
+        let res = app
+            .execute_contract(
+                test_accounts.subscriber_2.clone(),
+                stream_swap_contract_address.clone(),
+                &StreamSwapExecuteMsg::Subscribe {
+                    proof: None,
+                    on_behalf_of: None,
+                    referrer: None,
+                },
+                &[coin(1_000, "in_denom")],
+            )
+            .unwrap_err();
+        let err = res.source().unwrap();
+        let error = err.downcast_ref::<StreamSwapError>().unwrap();
+        assert_eq!(
+            *error,
+            StreamSwapError::OperationNotAllowed {
+                current_status: "Paused".to_string()
+            }
+        );
+
+        // Resume extends the end time by the paused duration
+        app.execute_contract(
+            test_accounts.admin.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::ResumeStream {},
+            &[],
+        )
+        .unwrap();
+        let stream: StreamResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::Stream {},
+            )
+            .unwrap();
+        assert_eq!(stream.status, Status::Active);
+        assert_eq!(stream.end_time, end_time.plus_seconds(50));
+
+        let res = app
+            .execute_contract(
+                test_accounts.admin.clone(),
+                stream_swap_contract_address.clone(),
+                &StreamSwapExecuteMsg::ResumeStream {},
+                &[],
+            )
+            .unwrap_err();
+        let err = res.source().unwrap();
+        let error = err.downcast_ref::<StreamSwapError>().unwrap();
+        assert_eq!(
+            *error,
+            StreamSwapError::OperationNotAllowed {
+                current_status: "Active".to_string()
+            }
+        );
+
+        // Stream is still active after the original end time
+        app.set_block(BlockInfo {
+            height: 1_200,
+            time: end_time,
+            chain_id: "test".to_string(),
+        });
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::SyncStream {},
+            &[],
+        )
+        .unwrap();
+        let stream: StreamResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::Stream {},
+            )
+            .unwrap();
+        // A third of the remaining amounts is distributed, rounded down
+        assert_eq!(stream.status, Status::Active);
+        assert_eq!(stream.out_remaining, Uint256::from(500_001u128));
+        assert_eq!(stream.in_supply, Uint256::from(501u128));
+
+        app.set_block(BlockInfo {
+            height: 1_250,
+            time: end_time.plus_seconds(50),
+            chain_id: "test".to_string(),
+        });
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::SyncStream {},
+            &[],
+        )
+        .unwrap();
+        let stream: StreamResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::Stream {},
+            )
+            .unwrap();
+        assert_eq!(stream.status, Status::Ended);
+        assert_eq!(stream.out_remaining, Uint256::zero());
+        assert_eq!(stream.spent_in, Uint256::from(1_000u128));
+    }
+}
@@ -81,7 +81,11 @@ mod pool {
         let stream_swap_contract_address: String = get_contract_address_from_res(res);
 
         // First Subscription
-        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
         app.update_block(|b| b.time = start_time.plus_seconds(100));
         app.execute_contract(
             test_accounts.subscriber_1.clone(),
@@ -215,7 +219,11 @@ mod pool {
             chain_id: "test".to_string(),
         });
 
-        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
 
         let _res = app
             .execute_contract(
@@ -332,7 +340,11 @@ mod pool {
             chain_id: "test".to_string(),
         });
 
-        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
 
         let _res = app
             .execute_contract(
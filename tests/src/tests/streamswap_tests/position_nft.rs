@@ -0,0 +1,497 @@
+#[cfg(test)]
+mod position_nft {
+    use crate::helpers::mock_messages::CreateStreamMsgBuilder;
+    use crate::helpers::suite::SuiteBuilder;
+    use crate::helpers::{
+        mock_messages::get_controller_inst_msg, suite::Suite, utils::get_contract_address_from_res,
+    };
+    use cosmwasm_std::{coin, Addr, Binary, BlockInfo, Uint256};
+    use cw_multi_test::Executor;
+    use cw_utils::Expiration;
+    use streamswap_stream::ContractError as StreamSwapError;
+    use streamswap_types::stream::{
+        AllNftInfoResponse, Approval, ApprovalResponse, ApprovalsResponse, ContractInfoResponse,
+        ExecuteMsg as StreamSwapExecuteMsg, NumTokensResponse, OperatorsResponse, OwnerOfResponse,
+        PositionResponse, QueryMsg as StreamSwapQueryMsg, TokensResponse,
+    };
+
+    #[test]
+    fn position_nft_transfer() {
+        let Suite {
+            mut app,
+            test_accounts,
+            stream_swap_code_id,
+            stream_swap_controller_code_id,
+            vesting_code_id,
+        } = SuiteBuilder::default().build();
+
+        let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+        let controller_address = app
+            .instantiate_contract(
+                stream_swap_controller_code_id,
+                test_accounts.admin.clone(),
+                &msg,
+                &[],
+                "Controller".to_string(),
+                None,
+            )
+            .unwrap();
+
+        let start_time = app.block_info().time.plus_seconds(100);
+        let end_time = app.block_info().time.plus_seconds(200);
+        let bootstrapping_start_time = app.block_info().time.plus_seconds(50);
+        let create_stream_msg = CreateStreamMsgBuilder::new(
+            "stream",
+            test_accounts.creator_1.as_ref(),
+            coin(1_000_000, "out_denom"),
+            "in_denom",
+            bootstrapping_start_time,
+            start_time,
+            end_time,
+        )
+        .position_nft("Stream Positions", "POS")
+        .build();
+
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address,
+                &create_stream_msg,
+                &[coin(100, "fee_denom"), coin(1_000_000, "out_denom")],
+            )
+            .unwrap();
+        let stream_swap_contract_address = Addr::unchecked(get_contract_address_from_res(res));
+
+        let contract_info: ContractInfoResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::ContractInfo {},
+            )
+            .unwrap();
+        assert_eq!(contract_info.name, "Stream Positions");
+        assert_eq!(contract_info.symbol, "POS");
+
+        app.set_block(BlockInfo {
+            height: 1_100,
+            time: start_time,
+            chain_id: "test".to_string(),
+        });
+        // Token is minted on the first subscription only
+        for _ in 0..2 {
+            app.execute_contract(
+                test_accounts.subscriber_1.clone(),
+                stream_swap_contract_address.clone(),
+                &StreamSwapExecuteMsg::Subscribe {
+                    proof: None,
+                    on_behalf_of: None,
+                    referrer: None,
+                },
+                &[coin(500, "in_denom")],
+            )
+            .unwrap();
+        }
+
+        let num_tokens: NumTokensResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::NumTokens {},
+            )
+            .unwrap();
+        assert_eq!(num_tokens.count, 1);
+        let tokens: TokensResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::Tokens {
+                    owner: test_accounts.subscriber_1.to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(tokens.tokens, vec!["1".to_string()]);
+        let position: PositionResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::Position {
+                    owner: test_accounts.subscriber_1.to_string(),
+                    at_time: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(position.token_id, Some("1".to_string()));
+
+        // Only approved accounts can transfer the token
+        let res = app
+            .execute_contract(
+                test_accounts.subscriber_2.clone(),
+                stream_swap_contract_address.clone(),
+                &StreamSwapExecuteMsg::TransferNft {
+                    recipient: test_accounts.wrong_user.to_string(),
+                    token_id: "1".to_string(),
+                },
+                &[],
+            )
+            .unwrap_err();
+        let err = res.source().unwrap();
+        let error = err.downcast_ref::<StreamSwapError>().unwrap();
+        assert_eq!(*error, StreamSwapError::Unauthorized {});
+
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::Approve {
+                spender: test_accounts.subscriber_2.to_string(),
+                token_id: "1".to_string(),
+                expires: None,
+            },
+            &[],
+        )
+        .unwrap();
+        let owner_of: OwnerOfResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::OwnerOf {
+                    token_id: "1".to_string(),
+                    include_expired: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(owner_of.owner, test_accounts.subscriber_1.to_string());
+        assert_eq!(owner_of.approvals.len(), 1);
+
+        app.set_block(BlockInfo {
+            height: 1_150,
+            time: start_time.plus_seconds(50),
+            chain_id: "test".to_string(),
+        });
+        app.execute_contract(
+            test_accounts.subscriber_2.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::TransferNft {
+                recipient: test_accounts.wrong_user.to_string(),
+                token_id: "1".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+        // Position follows the token and approvals are cleared
+        let owner_of: OwnerOfResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::OwnerOf {
+                    token_id: "1".to_string(),
+                    include_expired: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(owner_of.owner, test_accounts.wrong_user.to_string());
+        assert!(owner_of.approvals.is_empty());
+        let position: PositionResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::Position {
+                    owner: test_accounts.wrong_user.to_string(),
+                    at_time: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(position.token_id, Some("1".to_string()));
+        assert_eq!(position.in_balance, Uint256::from(500u128));
+
+        // Token owner controls the position
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::Withdraw { cap: None },
+            &[],
+        )
+        .unwrap_err();
+        app.execute_contract(
+            test_accounts.wrong_user.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::Withdraw { cap: None },
+            &[],
+        )
+        .unwrap();
+
+        // Tokens can not be transferred to a position holder, each token keeps its own position
+        app.execute_contract(
+            test_accounts.subscriber_2.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::Subscribe {
+                proof: None,
+                on_behalf_of: None,
+                referrer: None,
+            },
+            &[coin(500, "in_denom")],
+        )
+        .unwrap();
+        for msg in [
+            StreamSwapExecuteMsg::TransferNft {
+                recipient: test_accounts.subscriber_2.to_string(),
+                token_id: "1".to_string(),
+            },
+            StreamSwapExecuteMsg::SendNft {
+                contract: test_accounts.subscriber_2.to_string(),
+                token_id: "1".to_string(),
+                msg: Binary::default(),
+            },
+        ] {
+            let res = app
+                .execute_contract(
+                    test_accounts.wrong_user.clone(),
+                    stream_swap_contract_address.clone(),
+                    &msg,
+                    &[],
+                )
+                .unwrap_err();
+            let err = res.source().unwrap();
+            let error = err.downcast_ref::<StreamSwapError>().unwrap();
+            assert_eq!(*error, StreamSwapError::RecipientHoldsPosition {});
+        }
+        let num_tokens: NumTokensResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::NumTokens {},
+            )
+            .unwrap();
+        assert_eq!(num_tokens.count, 2);
+
+        // Operators can approve and transfer every token of the owner
+        app.execute_contract(
+            test_accounts.subscriber_2.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::ApproveAll {
+                operator: test_accounts.creator_2.to_string(),
+                expires: None,
+            },
+            &[],
+        )
+        .unwrap();
+        let operators: OperatorsResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::AllOperators {
+                    owner: test_accounts.subscriber_2.to_string(),
+                    include_expired: None,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            operators.operators,
+            vec![Approval {
+                spender: test_accounts.creator_2.to_string(),
+                expires: Expiration::Never {},
+            }]
+        );
+        app.execute_contract(
+            test_accounts.creator_2.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::Approve {
+                spender: test_accounts.creator_1.to_string(),
+                token_id: "2".to_string(),
+                expires: None,
+            },
+            &[],
+        )
+        .unwrap();
+        let approval: ApprovalResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::Approval {
+                    token_id: "2".to_string(),
+                    spender: test_accounts.creator_1.to_string(),
+                    include_expired: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            approval.approval.spender,
+            test_accounts.creator_1.to_string()
+        );
+        let approvals: ApprovalsResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::Approvals {
+                    token_id: "2".to_string(),
+                    include_expired: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(approvals.approvals.len(), 1);
+        app.execute_contract(
+            test_accounts.creator_2.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::TransferNft {
+                recipient: test_accounts.admin_2.to_string(),
+                token_id: "2".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+        let all_nft_info: AllNftInfoResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::AllNftInfo {
+                    token_id: "2".to_string(),
+                    include_expired: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(all_nft_info.access.owner, test_accounts.admin_2.to_string());
+        assert!(all_nft_info.access.approvals.is_empty());
+        let position: PositionResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::Position {
+                    owner: test_accounts.admin_2.to_string(),
+                    at_time: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(position.token_id, Some("2".to_string()));
+
+        app.execute_contract(
+            test_accounts.subscriber_2.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::RevokeAll {
+                operator: test_accounts.creator_2.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+        let operators: OperatorsResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::AllOperators {
+                    owner: test_accounts.subscriber_2.to_string(),
+                    include_expired: None,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert!(operators.operators.is_empty());
+
+        // Tokens query honors the limit
+        let tokens: TokensResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::Tokens {
+                    owner: test_accounts.admin_2.to_string(),
+                    start_after: None,
+                    limit: Some(0),
+                },
+            )
+            .unwrap();
+        assert!(tokens.tokens.is_empty());
+    }
+
+    #[test]
+    fn position_nft_not_enabled() {
+        let Suite {
+            mut app,
+            test_accounts,
+            stream_swap_code_id,
+            stream_swap_controller_code_id,
+            vesting_code_id,
+        } = SuiteBuilder::default().build();
+
+        let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+        let controller_address = app
+            .instantiate_contract(
+                stream_swap_controller_code_id,
+                test_accounts.admin.clone(),
+                &msg,
+                &[],
+                "Controller".to_string(),
+                None,
+            )
+            .unwrap();
+
+        let start_time = app.block_info().time.plus_seconds(100);
+        let end_time = app.block_info().time.plus_seconds(200);
+        let bootstrapping_start_time = app.block_info().time.plus_seconds(50);
+        let create_stream_msg = CreateStreamMsgBuilder::new(
+            "stream",
+            test_accounts.creator_1.as_ref(),
+            coin(1_000_000, "out_denom"),
+            "in_denom",
+            bootstrapping_start_time,
+            start_time,
+            end_time,
+        )
+        .build();
+
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address,
+                &create_stream_msg,
+                &[coin(100, "fee_denom"), coin(1_000_000, "out_denom")],
+            )
+            .unwrap();
+        let stream_swap_contract_address = Addr::unchecked(get_contract_address_from_res(res));
+
+        app.set_block(BlockInfo {
+            height: 1_100,
+            time: start_time,
+            chain_id: "test".to_string(),
+        });
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::Subscribe {
+                proof: None,
+                on_behalf_of: None,
+                referrer: None,
+            },
+            &[coin(500, "in_denom")],
+        )
+        .unwrap();
+        let position: PositionResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::Position {
+                    owner: test_accounts.subscriber_1.to_string(),
+                    at_time: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(position.token_id, None);
+
+        let res = app
+            .execute_contract(
+                test_accounts.subscriber_1.clone(),
+                stream_swap_contract_address.clone(),
+                &StreamSwapExecuteMsg::TransferNft {
+                    recipient: test_accounts.subscriber_2.to_string(),
+                    token_id: "1".to_string(),
+                },
+                &[],
+            )
+            .unwrap_err();
+        let err = res.source().unwrap();
+        let error = err.downcast_ref::<StreamSwapError>().unwrap();
+        assert_eq!(*error, StreamSwapError::PositionNftNotEnabled {});
+    }
+}
@@ -0,0 +1,204 @@
+#[cfg(test)]
+mod referral {
+    use crate::helpers::mock_messages::CreateStreamMsgBuilder;
+    use crate::helpers::suite::SuiteBuilder;
+    use crate::helpers::utils::get_funds_from_res;
+    use crate::helpers::{
+        mock_messages::get_controller_inst_msg, suite::Suite, utils::get_contract_address_from_res,
+    };
+    use cosmwasm_std::{coin, Addr, BlockInfo, Coin, Decimal256, Uint128, Uint256};
+    use cw_multi_test::Executor;
+    use streamswap_stream::ContractError as StreamSwapError;
+    use streamswap_types::stream::{
+        ExecuteMsg as StreamSwapExecuteMsg, PositionResponse, QueryMsg as StreamSwapQueryMsg,
+        ReferralResponse, ReferralsResponse,
+    };
+
+    #[test]
+    fn referrer_paid_from_exit_fee() {
+        let Suite {
+            mut app,
+            test_accounts,
+            stream_swap_code_id,
+            stream_swap_controller_code_id,
+            vesting_code_id,
+        } = SuiteBuilder::default().build();
+
+        let mut msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+        msg.referral_fee_percent = Decimal256::percent(50);
+        let controller_address = app
+            .instantiate_contract(
+                stream_swap_controller_code_id,
+                test_accounts.admin.clone(),
+                &msg,
+                &[],
+                "Controller".to_string(),
+                None,
+            )
+            .unwrap();
+
+        let start_time = app.block_info().time.plus_seconds(100);
+        let end_time = app.block_info().time.plus_seconds(200);
+        let bootstrapping_start_time = app.block_info().time.plus_seconds(50);
+        let create_stream_msg = CreateStreamMsgBuilder::new(
+            "Stream Swap tests",
+            test_accounts.creator_1.as_ref(),
+            coin(1_000_000, "out_denom"),
+            "in_denom",
+            bootstrapping_start_time,
+            start_time,
+            end_time,
+        )
+        .build();
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address.clone(),
+                &create_stream_msg,
+                &[coin(100, "fee_denom"), coin(1_000_000, "out_denom")],
+            )
+            .unwrap();
+        let stream_swap_contract_address = get_contract_address_from_res(res);
+
+        app.set_block(BlockInfo {
+            height: 1_100,
+            time: start_time,
+            chain_id: "test".to_string(),
+        });
+
+        // Subscriber can not refer itself
+        let err = app
+            .execute_contract(
+                test_accounts.subscriber_1.clone(),
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapExecuteMsg::Subscribe {
+                    proof: None,
+                    on_behalf_of: None,
+                    referrer: Some(test_accounts.subscriber_1.to_string()),
+                },
+                &[coin(1_000, "in_denom")],
+            )
+            .unwrap_err();
+        let error = err.source().unwrap().downcast_ref::<StreamSwapError>();
+        assert_eq!(error, Some(&StreamSwapError::InvalidReferrer {}));
+
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            Addr::unchecked(stream_swap_contract_address.clone()),
+            &StreamSwapExecuteMsg::Subscribe {
+                proof: None,
+                on_behalf_of: None,
+                referrer: Some(test_accounts.admin_2.to_string()),
+            },
+            &[coin(1_000, "in_denom")],
+        )
+        .unwrap();
+        app.execute_contract(
+            test_accounts.subscriber_2.clone(),
+            Addr::unchecked(stream_swap_contract_address.clone()),
+            &StreamSwapExecuteMsg::Subscribe {
+                proof: None,
+                on_behalf_of: None,
+                referrer: None,
+            },
+            &[coin(1_000, "in_denom")],
+        )
+        .unwrap();
+
+        let position: PositionResponse = app
+            .wrap()
+            .query_wasm_smart(
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapQueryMsg::Position {
+                    owner: test_accounts.subscriber_1.to_string(),
+                    at_time: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(position.referrer, Some(test_accounts.admin_2.to_string()));
+
+        app.set_block(BlockInfo {
+            height: 1_200,
+            time: end_time,
+            chain_id: "test".to_string(),
+        });
+
+        // Swap fee is 1% of 2_000, half of it is reserved for referrers
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapExecuteMsg::FinalizeStream {
+                    new_treasury: None,
+                    create_pool: None,
+                    salt: None,
+                },
+                &[],
+            )
+            .unwrap();
+        let funds = get_funds_from_res(res);
+        assert!(funds.contains(&(
+            test_accounts.admin.to_string(),
+            Coin {
+                denom: "in_denom".to_string(),
+                amount: Uint128::new(10)
+            }
+        )));
+
+        // Referrer receives half of the exit fee of the referred position
+        let res = app
+            .execute_contract(
+                test_accounts.subscriber_1.clone(),
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapExecuteMsg::ExitStream { salt: None },
+                &[],
+            )
+            .unwrap();
+        let funds = get_funds_from_res(res);
+        assert!(funds.contains(&(
+            test_accounts.admin_2.to_string(),
+            Coin {
+                denom: "in_denom".to_string(),
+                amount: Uint128::new(5)
+            }
+        )));
+
+        // Positions without a referrer pay no referral fee
+        let res = app
+            .execute_contract(
+                test_accounts.subscriber_2.clone(),
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapExecuteMsg::ExitStream { salt: None },
+                &[],
+            )
+            .unwrap();
+        let funds = get_funds_from_res(res);
+        assert!(funds.iter().all(|(_, coin)| coin.denom != "in_denom"));
+
+        let referrals: ReferralsResponse = app
+            .wrap()
+            .query_wasm_smart(
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapQueryMsg::ListReferrals {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            referrals.referrals,
+            vec![ReferralResponse {
+                referrer: test_accounts.admin_2.to_string(),
+                referred_positions: 1,
+                fees_paid: Uint256::from(5u128),
+            }]
+        );
+
+        // Unused reserve is left for the dust sweep
+        let balance = app
+            .wrap()
+            .query_balance(stream_swap_contract_address, "in_denom")
+            .unwrap();
+        assert_eq!(balance.amount, Uint128::new(5));
+    }
+}
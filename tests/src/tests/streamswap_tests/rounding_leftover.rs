@@ -10,6 +10,7 @@ mod rounding_leftover {
     use cosmwasm_std::Uint256;
     use cosmwasm_std::{coin, Addr, BlockInfo, Decimal256, Timestamp};
     use cw_multi_test::Executor;
+    use streamswap_stream::ContractError as StreamSwapError;
     use streamswap_types::stream::{
         ExecuteMsg as StreamSwapExecuteMsg, QueryMsg as StreamSwapQueryMsg,
     };
@@ -68,8 +69,28 @@ mod rounding_leftover {
             height: 1,
             chain_id: "SS".to_string(),
         });
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
+        // Position withdrawn before spending does not need to exit for the dust sweep
+        app.execute_contract(
+            test_accounts.creator_2.clone(),
+            Addr::unchecked(stream_swap_contract_address.clone()),
+            &subscribe_msg,
+            &[coin(1_000, "in_denom")],
+        )
+        .unwrap();
+        app.execute_contract(
+            test_accounts.creator_2.clone(),
+            Addr::unchecked(stream_swap_contract_address.clone()),
+            &StreamSwapExecuteMsg::Withdraw { cap: None },
+            &[],
+        )
+        .unwrap();
+
         // First subscription
-        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
         let _res = app
             .execute_contract(
                 test_accounts.subscriber_1.clone(),
@@ -116,6 +137,7 @@ mod rounding_leftover {
                 Addr::unchecked(stream_swap_contract_address.clone()),
                 &StreamSwapQueryMsg::Position {
                     owner: test_accounts.subscriber_1.to_string(),
+                    at_time: None,
                 },
             )
             .unwrap();
@@ -161,6 +183,7 @@ mod rounding_leftover {
                 Addr::unchecked(stream_swap_contract_address.clone()),
                 &StreamSwapQueryMsg::Position {
                     owner: test_accounts.subscriber_2.to_string(),
+                    at_time: None,
                 },
             )
             .unwrap();
@@ -221,6 +244,7 @@ mod rounding_leftover {
                 Addr::unchecked(stream_swap_contract_address.clone()),
                 &StreamSwapQueryMsg::Position {
                     owner: test_accounts.subscriber_1.to_string(),
+                    at_time: None,
                 },
             )
             .unwrap();
@@ -267,6 +291,7 @@ mod rounding_leftover {
                 Addr::unchecked(stream_swap_contract_address.clone()),
                 &StreamSwapQueryMsg::Position {
                     owner: test_accounts.subscriber_2.to_string(),
+                    at_time: None,
                 },
             )
             .unwrap();
@@ -286,5 +311,239 @@ mod rounding_leftover {
             // 1 difference due to rounding
             Uint256::from(stream.out_asset.amount.u128()).saturating_sub(Uint256::from(1u128))
         );
+
+        app.execute_contract(
+            test_accounts.creator_1.clone(),
+            Addr::unchecked(stream_swap_contract_address.clone()),
+            &StreamSwapExecuteMsg::FinalizeStream {
+                new_treasury: None,
+                create_pool: None,
+                salt: None,
+            },
+            &[],
+        )
+        .unwrap();
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            Addr::unchecked(stream_swap_contract_address.clone()),
+            &StreamSwapExecuteMsg::ExitStream { salt: None },
+            &[],
+        )
+        .unwrap();
+
+        // Dust can not be swept while positions are left
+        let res = app
+            .execute_contract(
+                test_accounts.wrong_user.clone(),
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapExecuteMsg::SweepDust {},
+                &[],
+            )
+            .unwrap_err();
+        let err = res.source().unwrap();
+        let error = err.downcast_ref::<StreamSwapError>().unwrap();
+        assert_eq!(*error, StreamSwapError::DustSweepNotAllowed {});
+
+        app.execute_contract(
+            test_accounts.subscriber_2.clone(),
+            Addr::unchecked(stream_swap_contract_address.clone()),
+            &StreamSwapExecuteMsg::ExitStream { salt: None },
+            &[],
+        )
+        .unwrap();
+
+        let fee_collector_balance = app
+            .wrap()
+            .query_balance(test_accounts.admin.clone(), "out_denom")
+            .unwrap();
+        app.execute_contract(
+            test_accounts.wrong_user.clone(),
+            Addr::unchecked(stream_swap_contract_address.clone()),
+            &StreamSwapExecuteMsg::SweepDust {},
+            &[],
+        )
+        .unwrap();
+
+        // Rounding leftover is sent to the fee collector
+        let balance = app
+            .wrap()
+            .query_balance(test_accounts.admin.clone(), "out_denom")
+            .unwrap();
+        assert_eq!((balance.amount - fee_collector_balance.amount).u128(), 1);
+        let contract_balance = app
+            .wrap()
+            .query_all_balances(Addr::unchecked(stream_swap_contract_address))
+            .unwrap();
+        assert!(contract_balance.is_empty());
+    }
+
+    #[test]
+    fn sweep_dust_keeps_unexited_balances() {
+        let Suite {
+            mut app,
+            test_accounts,
+            stream_swap_code_id,
+            stream_swap_controller_code_id,
+            vesting_code_id,
+        } = SuiteBuilder::default().build();
+
+        let start_time = Timestamp::from_seconds(1_000_000);
+        let end_time = Timestamp::from_seconds(5_000_000);
+        let bootstrapping_start_time = Timestamp::from_seconds(500_000);
+
+        let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+        let controller_address = app
+            .instantiate_contract(
+                stream_swap_controller_code_id,
+                test_accounts.admin.clone(),
+                &msg,
+                &[],
+                "Controller".to_string(),
+                None,
+            )
+            .unwrap();
+        let create_stream_msg = CreateStreamMsgBuilder::new(
+            "Stream Swap tests",
+            test_accounts.creator_1.as_ref(),
+            coin(1_000_000_000_000, "out_denom"),
+            "in_denom",
+            bootstrapping_start_time,
+            start_time,
+            end_time,
+        )
+        .build();
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address.clone(),
+                &create_stream_msg,
+                &[coin(100, "fee_denom"), coin(1_000_000_000_000, "out_denom")],
+            )
+            .unwrap();
+        let stream_swap_contract_address = Addr::unchecked(get_contract_address_from_res(res));
+
+        app.set_block(BlockInfo {
+            time: start_time.plus_seconds(100),
+            height: 1,
+            chain_id: "SS".to_string(),
+        });
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_swap_contract_address.clone(),
+            &subscribe_msg,
+            &[coin(1_000_000_000, "in_denom")],
+        )
+        .unwrap();
+        app.set_block(BlockInfo {
+            time: start_time.plus_seconds(100_000),
+            height: 2,
+            chain_id: "SS".to_string(),
+        });
+        app.execute_contract(
+            test_accounts.subscriber_2.clone(),
+            stream_swap_contract_address.clone(),
+            &subscribe_msg,
+            &[coin(3_000_000_000, "in_denom")],
+        )
+        .unwrap();
+
+        app.set_block(BlockInfo {
+            time: end_time.plus_seconds(1),
+            height: 3,
+            chain_id: "SS".to_string(),
+        });
+        app.execute_contract(
+            test_accounts.creator_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::FinalizeStream {
+                new_treasury: None,
+                create_pool: None,
+                salt: None,
+            },
+            &[],
+        )
+        .unwrap();
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::ExitStream { salt: None },
+            &[],
+        )
+        .unwrap();
+
+        // Grace period passes without the second position exiting
+        app.set_block(BlockInfo {
+            time: end_time.plus_seconds(1_001),
+            height: 4,
+            chain_id: "SS".to_string(),
+        });
+        let position_2: PositionResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::Position {
+                    owner: test_accounts.subscriber_2.to_string(),
+                    at_time: None,
+                },
+            )
+            .unwrap();
+        app.execute_contract(
+            test_accounts.wrong_user.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::SweepDust {},
+            &[],
+        )
+        .unwrap();
+
+        // Purchases and unspent balance of the unexited position stay in the stream
+        let out_balance = app
+            .wrap()
+            .query_balance(stream_swap_contract_address.clone(), "out_denom")
+            .unwrap();
+        assert!(Uint256::from(out_balance.amount.u128()) >= position_2.purchased);
+        let in_balance = app
+            .wrap()
+            .query_balance(stream_swap_contract_address.clone(), "in_denom")
+            .unwrap();
+        assert!(Uint256::from(in_balance.amount.u128()) >= position_2.in_balance);
+
+        // Dust is swept once
+        let res = app
+            .execute_contract(
+                test_accounts.wrong_user.clone(),
+                stream_swap_contract_address.clone(),
+                &StreamSwapExecuteMsg::SweepDust {},
+                &[],
+            )
+            .unwrap_err();
+        let err = res.source().unwrap();
+        let error = err.downcast_ref::<StreamSwapError>().unwrap();
+        assert_eq!(*error, StreamSwapError::DustAlreadySwept {});
+
+        // Unexited position still exits with its purchases
+        let subscriber_balance_before = app
+            .wrap()
+            .query_balance(test_accounts.subscriber_2.clone(), "out_denom")
+            .unwrap();
+        app.execute_contract(
+            test_accounts.subscriber_2.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::ExitStream { salt: None },
+            &[],
+        )
+        .unwrap();
+        let subscriber_balance = app
+            .wrap()
+            .query_balance(test_accounts.subscriber_2.clone(), "out_denom")
+            .unwrap();
+        assert_eq!(
+            Uint256::from((subscriber_balance.amount - subscriber_balance_before.amount).u128()),
+            position_2.purchased
+        );
     }
 }
@@ -1,22 +1,28 @@
 #[cfg(test)]
 mod shares {
-    use cosmwasm_std::{Coin, Timestamp, Uint128, Uint256};
+    use cosmwasm_std::{Timestamp, Uint128, Uint256};
+    use cw_denom::CheckedDenom;
     use streamswap_stream::stream::compute_shares_amount;
-    use streamswap_types::stream::StreamState;
+    use streamswap_types::stream::{Asset, EmissionCurve, StreamState};
 
     #[test]
     fn test_compute_shares_amount() {
         let mut stream = StreamState::new(
             Timestamp::from_seconds(0),
-            Coin {
-                denom: "out_denom".to_string(),
-                amount: Uint128::from(100u128),
-            },
-            "in_denom".to_string(),
+            Asset::new(
+                CheckedDenom::Native("out_denom".to_string()),
+                Uint128::from(100u128),
+            ),
+            CheckedDenom::Native("in_denom".to_string()),
             Timestamp::from_seconds(0),
             Timestamp::from_seconds(100),
             Timestamp::from_seconds(0),
             None,
+            None,
+            EmissionCurve::Linear,
+            None,
+            None,
+            None,
         );
 
         // add new shares
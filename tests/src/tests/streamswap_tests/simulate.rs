@@ -0,0 +1,361 @@
+#[cfg(test)]
+mod simulate {
+    use crate::helpers::mock_messages::CreateStreamMsgBuilder;
+    use crate::helpers::suite::SuiteBuilder;
+    use crate::helpers::{
+        mock_messages::get_controller_inst_msg, suite::Suite, utils::get_contract_address_from_res,
+    };
+    use cosmwasm_std::{coin, Addr, BlockInfo, HexBinary, QuerierWrapper, StdResult, Uint256};
+    use cw_multi_test::Executor;
+    use sha2::{Digest, Sha256};
+    use streamswap_stream::ContractError as StreamSwapError;
+    use streamswap_types::stream::{
+        ExecuteMsg as StreamSwapExecuteMsg, PositionResponse, QueryMsg as StreamSwapQueryMsg,
+        ShareRounding, SimulateSubscribeResponse, SimulateWithdrawResponse,
+    };
+
+    fn simulate(
+        querier: QuerierWrapper,
+        stream: &Addr,
+        amount: u128,
+        subscriber: Option<&Addr>,
+        proof: Option<Vec<String>>,
+    ) -> StdResult<SimulateSubscribeResponse> {
+        querier.query_wasm_smart(
+            stream,
+            &StreamSwapQueryMsg::SimulateSubscribe {
+                amount: Uint256::from(amount),
+                at_time: None,
+                subscriber: subscriber.map(|subscriber| subscriber.to_string()),
+                proof,
+            },
+        )
+    }
+
+    #[test]
+    fn simulate_subscribe_and_withdraw() {
+        let Suite {
+            mut app,
+            test_accounts,
+            stream_swap_code_id,
+            stream_swap_controller_code_id,
+            vesting_code_id,
+        } = SuiteBuilder::default().build();
+
+        let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+        let controller_address = app
+            .instantiate_contract(
+                stream_swap_controller_code_id,
+                test_accounts.admin.clone(),
+                &msg,
+                &[],
+                "Controller".to_string(),
+                None,
+            )
+            .unwrap();
+
+        let start_time = app.block_info().time.plus_seconds(100);
+        let end_time = app.block_info().time.plus_seconds(200);
+        let bootstrapping_start_time = app.block_info().time.plus_seconds(50);
+        let create_stream_msg = CreateStreamMsgBuilder::new(
+            "Stream Swap tests",
+            test_accounts.creator_1.as_ref(),
+            coin(1_000_000, "out_denom"),
+            "in_denom",
+            bootstrapping_start_time,
+            start_time,
+            end_time,
+        )
+        .build();
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address,
+                &create_stream_msg,
+                &[coin(100, "fee_denom"), coin(1_000_000, "out_denom")],
+            )
+            .unwrap();
+        let stream_swap_contract_address = Addr::unchecked(get_contract_address_from_res(res));
+
+        // Subscriptions are not simulated before bootstrapping
+        app.wrap()
+            .query_wasm_smart::<SimulateSubscribeResponse>(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::SimulateSubscribe {
+                    amount: Uint256::from(1_000u128),
+                    at_time: None,
+                    subscriber: None,
+                    proof: None,
+                },
+            )
+            .unwrap_err();
+
+        // First subscription mints shares one to one
+        let simulated: SimulateSubscribeResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::SimulateSubscribe {
+                    amount: Uint256::from(1_000u128),
+                    at_time: Some(start_time),
+                    subscriber: None,
+                    proof: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            simulated,
+            SimulateSubscribeResponse {
+                shares: Uint256::from(1_000u128),
+                rounding: ShareRounding::Exact,
+                in_supply: Uint256::from(1_000u128),
+                total_shares: Uint256::from(1_000u128),
+                projected_out: Uint256::zero(),
+                hard_cap_remaining: None,
+            }
+        );
+
+        app.set_block(BlockInfo {
+            height: 1_100,
+            time: start_time,
+            chain_id: "test".to_string(),
+        });
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::Subscribe {
+                proof: None,
+                on_behalf_of: None,
+                referrer: None,
+            },
+            &[coin(1_000, "in_denom")],
+        )
+        .unwrap();
+
+        // 300 in is spent for 300_000 out, 700 in supply is left for 1_000 shares
+        app.set_block(BlockInfo {
+            height: 1_130,
+            time: start_time.plus_seconds(30),
+            chain_id: "test".to_string(),
+        });
+        let simulated: SimulateSubscribeResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::SimulateSubscribe {
+                    amount: Uint256::from(100u128),
+                    at_time: None,
+                    subscriber: None,
+                    proof: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            simulated,
+            SimulateSubscribeResponse {
+                shares: Uint256::from(142u128),
+                rounding: ShareRounding::Down,
+                in_supply: Uint256::from(800u128),
+                total_shares: Uint256::from(1_142u128),
+                projected_out: Uint256::from(100_000u128),
+                hard_cap_remaining: None,
+            }
+        );
+
+        let simulated: SimulateWithdrawResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::SimulateWithdraw {
+                    owner: test_accounts.subscriber_1.to_string(),
+                    cap: Some(Uint256::from(100u128)),
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            simulated,
+            SimulateWithdrawResponse {
+                withdraw_amount: Uint256::from(100u128),
+                shares: Uint256::from(143u128),
+                rounding: ShareRounding::Up,
+                in_balance: Uint256::from(600u128),
+                remaining_shares: Uint256::from(857u128),
+                purchased: Uint256::from(300_000u128),
+                spent: Uint256::from(300u128),
+            }
+        );
+
+        // Simulation matches the executed withdrawal
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::Withdraw {
+                cap: Some(Uint256::from(100u128)),
+            },
+            &[],
+        )
+        .unwrap();
+        let position: PositionResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::Position {
+                    owner: test_accounts.subscriber_1.to_string(),
+                    at_time: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(position.in_balance, simulated.in_balance);
+        assert_eq!(position.shares, simulated.remaining_shares);
+
+        // Full withdrawal burns every share
+        let simulated: SimulateWithdrawResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address,
+                &StreamSwapQueryMsg::SimulateWithdraw {
+                    owner: test_accounts.subscriber_1.to_string(),
+                    cap: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(simulated.shares, Uint256::from(857u128));
+        assert_eq!(simulated.rounding, ShareRounding::Exact);
+        assert_eq!(simulated.remaining_shares, Uint256::zero());
+    }
+
+    #[test]
+    fn simulate_subscribe_checks() {
+        let Suite {
+            mut app,
+            test_accounts,
+            stream_swap_code_id,
+            stream_swap_controller_code_id,
+            vesting_code_id,
+        } = SuiteBuilder::default().build();
+
+        let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+        let controller_address = app
+            .instantiate_contract(
+                stream_swap_controller_code_id,
+                test_accounts.admin.clone(),
+                &msg,
+                &[],
+                "Controller".to_string(),
+                None,
+            )
+            .unwrap();
+
+        let leaf_1: [u8; 32] = Sha256::digest(test_accounts.subscriber_1.as_bytes()).into();
+        let leaf_2: [u8; 32] = Sha256::digest(test_accounts.subscriber_2.as_bytes()).into();
+        let mut hashes = [leaf_1, leaf_2];
+        hashes.sort_unstable();
+        let merkle_root = HexBinary::from(Sha256::digest(hashes.concat()).as_slice()).to_hex();
+        let proof = vec![HexBinary::from(leaf_2).to_hex()];
+
+        let start_time = app.block_info().time.plus_seconds(100);
+        let end_time = app.block_info().time.plus_seconds(200);
+        let bootstrapping_start_time = app.block_info().time.plus_seconds(50);
+        let create_stream_msg = CreateStreamMsgBuilder::new(
+            "Stream Swap tests",
+            test_accounts.creator_1.as_ref(),
+            coin(1_000_000, "out_denom"),
+            "in_denom",
+            bootstrapping_start_time,
+            start_time,
+            end_time,
+        )
+        .max_in_per_address(Uint256::from(1_000u128))
+        .max_in_supply(Uint256::from(1_500u128))
+        .hard_cap(Uint256::from(1_200u128))
+        .merkle_root(merkle_root)
+        .build();
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address,
+                &create_stream_msg,
+                &[coin(100, "fee_denom"), coin(1_000_000, "out_denom")],
+            )
+            .unwrap();
+        let stream_swap_contract_address = Addr::unchecked(get_contract_address_from_res(res));
+
+        app.set_block(BlockInfo {
+            height: 1_100,
+            time: start_time,
+            chain_id: "test".to_string(),
+        });
+
+        // Hard cap headroom is returned
+        let simulated =
+            simulate(app.wrap(), &stream_swap_contract_address, 600, None, None).unwrap();
+        assert_eq!(simulated.hard_cap_remaining, Some(Uint256::from(1_200u128)));
+
+        // Allowlist is checked for the subscriber
+        let err = simulate(
+            app.wrap(),
+            &stream_swap_contract_address,
+            600,
+            Some(&test_accounts.subscriber_1),
+            None,
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains(&StreamSwapError::NotAllowlisted {}.to_string()));
+        simulate(
+            app.wrap(),
+            &stream_swap_contract_address,
+            600,
+            Some(&test_accounts.subscriber_1),
+            Some(proof.clone()),
+        )
+        .unwrap();
+
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::Subscribe {
+                proof: Some(proof),
+                on_behalf_of: None,
+                referrer: None,
+            },
+            &[coin(600, "in_denom")],
+        )
+        .unwrap();
+
+        // Per address cap includes the existing position
+        let err = simulate(
+            app.wrap(),
+            &stream_swap_contract_address,
+            600,
+            Some(&test_accounts.subscriber_1),
+            None,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains(
+            &StreamSwapError::MaxInPerAddressExceeded {
+                cap: Uint256::from(1_000u128)
+            }
+            .to_string()
+        ));
+        simulate(
+            app.wrap(),
+            &stream_swap_contract_address,
+            400,
+            Some(&test_accounts.subscriber_1),
+            None,
+        )
+        .unwrap();
+
+        // Stream cap includes every subscription
+        let err =
+            simulate(app.wrap(), &stream_swap_contract_address, 1_000, None, None).unwrap_err();
+        assert!(err.to_string().contains(
+            &StreamSwapError::MaxInSupplyExceeded {
+                cap: Uint256::from(1_500u128)
+            }
+            .to_string()
+        ));
+    }
+}
@@ -64,7 +64,11 @@ mod subscribe {
             .unwrap();
         let stream_swap_contract_address: String = get_contract_address_from_res(res);
 
-        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
         app.set_block(BlockInfo {
             height: 1_100,
             time: start_time,
@@ -130,6 +134,7 @@ mod subscribe {
                 Addr::unchecked(stream_swap_contract_address.clone()),
                 &StreamSwapQueryMsg::Position {
                     owner: test_accounts.subscriber_1.to_string(),
+                    at_time: None,
                 },
             )
             .unwrap();
@@ -235,7 +240,11 @@ mod subscribe {
             .unwrap();
         let stream_swap_contract_address: String = get_contract_address_from_res(res);
 
-        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
         app.set_block(BlockInfo {
             height: 1_100,
             time: start_time,
@@ -269,6 +278,7 @@ mod subscribe {
                 Addr::unchecked(stream_swap_contract_address.clone()),
                 &StreamSwapQueryMsg::Position {
                     owner: test_accounts.subscriber_1.to_string(),
+                    at_time: None,
                 },
             )
             .unwrap();
@@ -281,7 +291,11 @@ mod subscribe {
             .execute_contract(
                 test_accounts.subscriber_1.clone(),
                 Addr::unchecked(stream_swap_contract_address.clone()),
-                &StreamSwapExecuteMsg::Subscribe {},
+                &StreamSwapExecuteMsg::Subscribe {
+                    proof: None,
+                    on_behalf_of: None,
+                    referrer: None,
+                },
                 &[coin(150, "in_denom")],
             )
             .unwrap();
@@ -303,6 +317,7 @@ mod subscribe {
                 Addr::unchecked(stream_swap_contract_address.clone()),
                 &StreamSwapQueryMsg::Position {
                     owner: test_accounts.subscriber_1.to_string(),
+                    at_time: None,
                 },
             )
             .unwrap();
@@ -321,7 +336,11 @@ mod subscribe {
             .execute_contract(
                 test_accounts.subscriber_1.clone(),
                 Addr::unchecked(stream_swap_contract_address.clone()),
-                &StreamSwapExecuteMsg::Subscribe {},
+                &StreamSwapExecuteMsg::Subscribe {
+                    proof: None,
+                    on_behalf_of: None,
+                    referrer: None,
+                },
                 &[coin(150, "in_denom")],
             )
             .unwrap();
@@ -346,6 +365,7 @@ mod subscribe {
                 Addr::unchecked(stream_swap_contract_address.clone()),
                 &StreamSwapQueryMsg::Position {
                     owner: test_accounts.subscriber_1.to_string(),
+                    at_time: None,
                 },
             )
             .unwrap();
@@ -405,7 +425,11 @@ mod subscribe {
             .unwrap();
         let stream_swap_contract_address: String = get_contract_address_from_res(res);
 
-        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
         app.set_block(BlockInfo {
             height: 1_100,
             time: bootstrapping_start_time,
@@ -439,6 +463,7 @@ mod subscribe {
                 Addr::unchecked(stream_swap_contract_address.clone()),
                 &StreamSwapQueryMsg::Position {
                     owner: test_accounts.subscriber_1.to_string(),
+                    at_time: None,
                 },
             )
             .unwrap();
@@ -481,7 +506,11 @@ mod subscribe {
             .execute_contract(
                 test_accounts.subscriber_1.clone(),
                 Addr::unchecked(stream_swap_contract_address.clone()),
-                &StreamSwapExecuteMsg::Subscribe {},
+                &StreamSwapExecuteMsg::Subscribe {
+                    proof: None,
+                    on_behalf_of: None,
+                    referrer: None,
+                },
                 &[coin(150, "in_denom")],
             )
             .unwrap();
@@ -522,7 +551,11 @@ mod subscribe {
             .execute_contract(
                 test_accounts.subscriber_2.clone(),
                 Addr::unchecked(stream_swap_contract_address.clone()),
-                &StreamSwapExecuteMsg::Subscribe {},
+                &StreamSwapExecuteMsg::Subscribe {
+                    proof: None,
+                    on_behalf_of: None,
+                    referrer: None,
+                },
                 &[coin(150, "in_denom")],
             )
             .unwrap();
@@ -596,6 +629,7 @@ mod subscribe {
                 Addr::unchecked(stream_swap_contract_address.clone()),
                 &StreamSwapQueryMsg::Position {
                     owner: test_accounts.subscriber_1.to_string(),
+                    at_time: None,
                 },
             )
             .unwrap();
@@ -626,6 +660,7 @@ mod subscribe {
                 Addr::unchecked(stream_swap_contract_address.clone()),
                 &StreamSwapQueryMsg::Position {
                     owner: test_accounts.subscriber_2.to_string(),
+                    at_time: None,
                 },
             )
             .unwrap();
@@ -687,7 +722,11 @@ mod subscribe {
             .unwrap();
         let stream_swap_contract_address: String = get_contract_address_from_res(res);
 
-        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
         app.set_block(BlockInfo {
             height: 1_100,
             time: bootstrapping_start_time.minus_seconds(1),
@@ -763,4 +802,149 @@ mod subscribe {
 
         assert_eq!(stream_after_update, stream);
     }
+
+    #[test]
+    fn subscribe_on_behalf_of() {
+        let Suite {
+            mut app,
+            test_accounts,
+            stream_swap_code_id,
+            stream_swap_controller_code_id,
+            vesting_code_id,
+        } = SuiteBuilder::default().build();
+
+        let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+        let controller_address = app
+            .instantiate_contract(
+                stream_swap_controller_code_id,
+                test_accounts.admin.clone(),
+                &msg,
+                &[],
+                "Controller".to_string(),
+                None,
+            )
+            .unwrap();
+
+        let start_time = app.block_info().time.plus_seconds(100);
+        let end_time = app.block_info().time.plus_seconds(200);
+        let bootstrapping_start_time = app.block_info().time.plus_seconds(50);
+        let create_stream_msg = CreateStreamMsgBuilder::new(
+            "stream",
+            test_accounts.creator_1.as_ref(),
+            coin(1_000_000, "out_denom"),
+            "in_denom",
+            bootstrapping_start_time,
+            start_time,
+            end_time,
+        )
+        .max_in_per_address(Uint256::from(1_000u128))
+        .build();
+
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address,
+                &create_stream_msg,
+                &[coin(100, "fee_denom"), coin(1_000_000, "out_denom")],
+            )
+            .unwrap();
+        let stream_swap_contract_address = Addr::unchecked(get_contract_address_from_res(res));
+
+        app.set_block(BlockInfo {
+            height: 1_100,
+            time: start_time,
+            chain_id: "test".to_string(),
+        });
+        app.execute_contract(
+            test_accounts.subscriber_2.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::Subscribe {
+                proof: None,
+                on_behalf_of: Some(test_accounts.subscriber_1.to_string()),
+                referrer: None,
+            },
+            &[coin(600, "in_denom")],
+        )
+        .unwrap();
+
+        // Caps apply to the subscriber, not the sender
+        let res = app
+            .execute_contract(
+                test_accounts.subscriber_1.clone(),
+                stream_swap_contract_address.clone(),
+                &StreamSwapExecuteMsg::Subscribe {
+                    proof: None,
+                    on_behalf_of: None,
+                    referrer: None,
+                },
+                &[coin(600, "in_denom")],
+            )
+            .unwrap_err();
+        let err = res.source().unwrap();
+        let error = err.downcast_ref::<StreamSwapError>().unwrap();
+        assert_eq!(
+            *error,
+            StreamSwapError::MaxInPerAddressExceeded {
+                cap: Uint256::from(1_000u128)
+            }
+        );
+
+        let position: PositionResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::Position {
+                    owner: test_accounts.subscriber_1.to_string(),
+                    at_time: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(position.owner, test_accounts.subscriber_1.to_string());
+        assert_eq!(position.in_balance, Uint256::from(600u128));
+
+        // Terms of service are signed by the sender only
+        let tos: String = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::ToS {
+                    addr: Some(test_accounts.subscriber_2.to_string()),
+                },
+            )
+            .unwrap();
+        assert_eq!(tos, "v1".to_string());
+        let res: Result<String, _> = app.wrap().query_wasm_smart(
+            stream_swap_contract_address.clone(),
+            &StreamSwapQueryMsg::ToS {
+                addr: Some(test_accounts.subscriber_1.to_string()),
+            },
+        );
+        assert!(res.is_err());
+
+        // Sender does not hold a position
+        app.execute_contract(
+            test_accounts.subscriber_2.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::Withdraw { cap: None },
+            &[],
+        )
+        .unwrap_err();
+
+        let balance_before = app
+            .wrap()
+            .query_balance(test_accounts.subscriber_1.clone(), "in_denom")
+            .unwrap();
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::Withdraw { cap: None },
+            &[],
+        )
+        .unwrap();
+        let balance = app
+            .wrap()
+            .query_balance(test_accounts.subscriber_1.clone(), "in_denom")
+            .unwrap();
+        assert_eq!((balance.amount - balance_before.amount).u128(), 600);
+    }
 }
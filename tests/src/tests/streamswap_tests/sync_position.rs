@@ -11,7 +11,8 @@ mod sync_position {
     use cosmwasm_std::{coin, Addr, BlockInfo, Decimal256, Uint256};
     use cw_multi_test::Executor;
     use streamswap_types::stream::{
-        ExecuteMsg as StreamSwapExecuteMsg, PositionResponse, QueryMsg as StreamSwapQueryMsg,
+        ExecuteMsg as StreamSwapExecuteMsg, PositionResponse, PositionsResponse,
+        QueryMsg as StreamSwapQueryMsg, StreamResponse,
     };
 
     #[test]
@@ -80,7 +81,11 @@ mod sync_position {
             .unwrap_err();
 
         // First subscription
-        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
 
         let _res = app
             .execute_contract(
@@ -114,6 +119,7 @@ mod sync_position {
                 Addr::unchecked(stream_swap_contract_address.clone()),
                 &StreamSwapQueryMsg::Position {
                     owner: test_accounts.subscriber_1.to_string(),
+                    at_time: None,
                 },
             )
             .unwrap();
@@ -160,6 +166,7 @@ mod sync_position {
                 Addr::unchecked(stream_swap_contract_address.clone()),
                 &StreamSwapQueryMsg::Position {
                     owner: test_accounts.subscriber_1.to_string(),
+                    at_time: None,
                 },
             )
             .unwrap();
@@ -202,6 +209,7 @@ mod sync_position {
                 Addr::unchecked(stream_swap_contract_address.clone()),
                 &StreamSwapQueryMsg::Position {
                     owner: test_accounts.subscriber_1.to_string(),
+                    at_time: None,
                 },
             )
             .unwrap();
@@ -248,6 +256,7 @@ mod sync_position {
                 Addr::unchecked(stream_swap_contract_address.clone()),
                 &StreamSwapQueryMsg::Position {
                     owner: test_accounts.subscriber_1.to_string(),
+                    at_time: None,
                 },
             )
             .unwrap();
@@ -284,9 +293,148 @@ mod sync_position {
                 Addr::unchecked(stream_swap_contract_address.clone()),
                 &StreamSwapQueryMsg::Position {
                     owner: test_accounts.subscriber_1.to_string(),
+                    at_time: None,
                 },
             )
             .unwrap();
         assert_eq!(position.exit_date, app.block_info().time);
     }
+
+    #[test]
+    fn position_query_simulates_sync() {
+        let Suite {
+            mut app,
+            test_accounts,
+            stream_swap_code_id,
+            stream_swap_controller_code_id,
+            vesting_code_id,
+        } = SuiteBuilder::default().build();
+        let start_time = app.block_info().time.plus_seconds(100);
+        let end_time = app.block_info().time.plus_seconds(200);
+        let bootstrapping_start_time = app.block_info().time.plus_seconds(50);
+
+        let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+        let controller_address = app
+            .instantiate_contract(
+                stream_swap_controller_code_id,
+                test_accounts.admin.clone(),
+                &msg,
+                &[],
+                "Controller".to_string(),
+                None,
+            )
+            .unwrap();
+        let create_stream_msg = CreateStreamMsgBuilder::new(
+            "Stream Swap tests",
+            test_accounts.creator_1.as_ref(),
+            coin(1_000_000, "out_denom"),
+            "in_denom",
+            bootstrapping_start_time,
+            start_time,
+            end_time,
+        )
+        .build();
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address,
+                &create_stream_msg,
+                &[coin(100, "fee_denom"), coin(1_000_000, "out_denom")],
+            )
+            .unwrap();
+        let stream_swap_contract_address = get_contract_address_from_res(res);
+
+        app.set_block(BlockInfo {
+            height: 1_100,
+            time: start_time,
+            chain_id: "test".to_string(),
+        });
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            Addr::unchecked(stream_swap_contract_address.clone()),
+            &StreamSwapExecuteMsg::Subscribe {
+                proof: None,
+                on_behalf_of: None,
+                referrer: None,
+            },
+            &[coin(1_000, "in_denom")],
+        )
+        .unwrap();
+
+        // Position is synced in the query without any transaction
+        app.set_block(BlockInfo {
+            height: 1_150,
+            time: start_time.plus_seconds(50),
+            chain_id: "test".to_string(),
+        });
+        let query_position = |at_time| StreamSwapQueryMsg::Position {
+            owner: test_accounts.subscriber_1.to_string(),
+            at_time,
+        };
+        let position: PositionResponse = app
+            .wrap()
+            .query_wasm_smart(
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &query_position(None),
+            )
+            .unwrap();
+        assert_eq!(position.purchased, Uint256::from(500_000u128));
+        assert_eq!(position.spent, Uint256::from(500u128));
+        assert_eq!(position.in_balance, Uint256::from(500u128));
+        assert_eq!(position.exit_fee, Uint256::from(5u128));
+        assert_eq!(position.net_out_payout, Uint256::from(500_000u128));
+        assert_eq!(position.net_in_payout, Uint256::from(500u128));
+
+        // Simulated at the end time
+        let position: PositionResponse = app
+            .wrap()
+            .query_wasm_smart(
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &query_position(Some(end_time)),
+            )
+            .unwrap();
+        assert_eq!(position.purchased, Uint256::from(1_000_000u128));
+        assert_eq!(position.spent, Uint256::from(1_000u128));
+        assert_eq!(position.exit_fee, Uint256::from(10u128));
+        assert_eq!(position.net_out_payout, Uint256::from(1_000_000u128));
+        assert_eq!(position.net_in_payout, Uint256::zero());
+
+        let positions: PositionsResponse = app
+            .wrap()
+            .query_wasm_smart(
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapQueryMsg::ListPositions {
+                    start_after: None,
+                    limit: None,
+                    at_time: Some(end_time),
+                },
+            )
+            .unwrap();
+        assert_eq!(positions.positions, vec![position]);
+
+        // Nothing is written by the queries
+        let stream: StreamResponse = app
+            .wrap()
+            .query_wasm_smart(
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapQueryMsg::Stream {},
+            )
+            .unwrap();
+        assert_eq!(stream.spent_in, Uint256::zero());
+
+        // Simulation can not go back before the last stream update
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            Addr::unchecked(stream_swap_contract_address.clone()),
+            &StreamSwapExecuteMsg::SyncStream {},
+            &[],
+        )
+        .unwrap();
+        app.wrap()
+            .query_wasm_smart::<PositionResponse>(
+                Addr::unchecked(stream_swap_contract_address),
+                &query_position(Some(start_time)),
+            )
+            .unwrap_err();
+    }
 }
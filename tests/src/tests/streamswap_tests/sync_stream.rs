@@ -176,7 +176,11 @@ mod sync_stream {
         // - We have tried to update stream without subscription at Waiting, Bootstrapping and Active status
         // - Now we will subscribe to stream and update stream at Active status
         // - We will check if stream is updated successfully in next 10 seconds and compare with previous state which no subscription was made
-        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
 
         let _res = app
             .execute_contract(
@@ -298,7 +302,11 @@ mod sync_stream {
             chain_id: "test".to_string(),
         });
 
-        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
 
         let _res = app
             .execute_contract(
@@ -433,7 +441,11 @@ mod sync_stream {
             Decimal256::new(Uint256::zero())
         );
 
-        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
         // First subscription
         let _res = app
             .execute_contract(
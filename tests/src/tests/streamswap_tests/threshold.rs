@@ -92,7 +92,7 @@
 //             .unwrap();
 //         let stream_swap_contract_address: String = get_contract_address_from_res(res);
 
-//         let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+//         let subscribe_msg = StreamSwapExecuteMsg::Subscribe { proof: None, on_behalf_of: None, referrer: None };
 //         // Set time to start of the stream
 //         app.set_block(BlockInfo {
 //             time: start_time,
@@ -224,7 +224,7 @@
 //             chain_id: "test".to_string(),
 //         });
 
-//         let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+//         let subscribe_msg = StreamSwapExecuteMsg::Subscribe { proof: None, on_behalf_of: None, referrer: None };
 //         // Subscription 1
 //         let _res = app
 //             .execute_contract(
@@ -453,7 +453,7 @@
 //         });
 
 //         // Subscription 1
-//         let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+//         let subscribe_msg = StreamSwapExecuteMsg::Subscribe { proof: None, on_behalf_of: None, referrer: None };
 
 //         let _res = app
 //             .execute_contract(
@@ -0,0 +1,253 @@
+#[cfg(test)]
+mod transfer_position {
+    use crate::helpers::mock_messages::CreateStreamMsgBuilder;
+    use crate::helpers::suite::SuiteBuilder;
+    use crate::helpers::{
+        mock_messages::get_controller_inst_msg, suite::Suite, utils::get_contract_address_from_res,
+    };
+    use cosmwasm_std::{coin, Addr, BlockInfo, Uint256};
+    use cw_multi_test::Executor;
+    use streamswap_stream::ContractError as StreamSwapError;
+    use streamswap_types::stream::{
+        ExecuteMsg as StreamSwapExecuteMsg, PositionResponse, QueryMsg as StreamSwapQueryMsg,
+    };
+
+    #[test]
+    fn transfer_position() {
+        let Suite {
+            mut app,
+            test_accounts,
+            stream_swap_code_id,
+            stream_swap_controller_code_id,
+            vesting_code_id,
+        } = SuiteBuilder::default().build();
+
+        let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+        let controller_address = app
+            .instantiate_contract(
+                stream_swap_controller_code_id,
+                test_accounts.admin.clone(),
+                &msg,
+                &[],
+                "Controller".to_string(),
+                None,
+            )
+            .unwrap();
+
+        let start_time = app.block_info().time.plus_seconds(100);
+        let end_time = app.block_info().time.plus_seconds(200);
+        let bootstrapping_start_time = app.block_info().time.plus_seconds(50);
+        let create_stream_msg = CreateStreamMsgBuilder::new(
+            "stream",
+            test_accounts.creator_1.as_ref(),
+            coin(1_000_000, "out_denom"),
+            "in_denom",
+            bootstrapping_start_time,
+            start_time,
+            end_time,
+        )
+        .build();
+
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address,
+                &create_stream_msg,
+                &[coin(100, "fee_denom"), coin(1_000_000, "out_denom")],
+            )
+            .unwrap();
+        let stream_swap_contract_address = Addr::unchecked(get_contract_address_from_res(res));
+
+        app.set_block(BlockInfo {
+            height: 1_100,
+            time: start_time,
+            chain_id: "test".to_string(),
+        });
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::Subscribe {
+                proof: None,
+                on_behalf_of: None,
+                referrer: None,
+            },
+            &[coin(1_000, "in_denom")],
+        )
+        .unwrap();
+        app.execute_contract(
+            test_accounts.subscriber_2.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::Subscribe {
+                proof: None,
+                on_behalf_of: None,
+                referrer: None,
+            },
+            &[coin(1_000, "in_denom")],
+        )
+        .unwrap();
+
+        app.set_block(BlockInfo {
+            height: 1_150,
+            time: start_time.plus_seconds(50),
+            chain_id: "test".to_string(),
+        });
+
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::TransferPosition {
+                recipient: test_accounts.wrong_user.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+        // Position is synced before the transfer
+        let position: PositionResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::Position {
+                    owner: test_accounts.wrong_user.to_string(),
+                    at_time: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(position.owner, test_accounts.wrong_user.to_string());
+        assert_eq!(position.in_balance, Uint256::from(500u128));
+        assert_eq!(position.spent, Uint256::from(500u128));
+        assert_eq!(position.purchased, Uint256::from(250_000u128));
+
+        let res: Result<PositionResponse, _> = app.wrap().query_wasm_smart(
+            stream_swap_contract_address.clone(),
+            &StreamSwapQueryMsg::Position {
+                owner: test_accounts.subscriber_1.to_string(),
+                at_time: None,
+            },
+        );
+        assert!(res.is_err());
+
+        // Signed terms of service are carried over
+        let tos: String = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::ToS {
+                    addr: Some(test_accounts.wrong_user.to_string()),
+                },
+            )
+            .unwrap();
+        assert_eq!(tos, "v1".to_string());
+
+        // Previous owner can not withdraw anymore
+        let res = app
+            .execute_contract(
+                test_accounts.subscriber_1.clone(),
+                stream_swap_contract_address.clone(),
+                &StreamSwapExecuteMsg::Withdraw { cap: None },
+                &[],
+            )
+            .unwrap_err();
+        assert!(res.source().is_some());
+
+        // Position can not be transferred to its owner
+        let res = app
+            .execute_contract(
+                test_accounts.wrong_user.clone(),
+                stream_swap_contract_address.clone(),
+                &StreamSwapExecuteMsg::TransferPosition {
+                    recipient: test_accounts.wrong_user.to_string(),
+                },
+                &[],
+            )
+            .unwrap_err();
+        let err = res.source().unwrap();
+        let error = err.downcast_ref::<StreamSwapError>().unwrap();
+        assert_eq!(*error, StreamSwapError::SelfTransfer {});
+        let position: PositionResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::Position {
+                    owner: test_accounts.wrong_user.to_string(),
+                    at_time: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(position.in_balance, Uint256::from(500u128));
+        assert_eq!(position.spent, Uint256::from(500u128));
+        assert_eq!(position.purchased, Uint256::from(250_000u128));
+        assert_eq!(position.shares, Uint256::from(1_000u128));
+
+        // Transferring to a holder merges the positions
+        app.execute_contract(
+            test_accounts.wrong_user.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::TransferPosition {
+                recipient: test_accounts.subscriber_2.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+        let position: PositionResponse = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address.clone(),
+                &StreamSwapQueryMsg::Position {
+                    owner: test_accounts.subscriber_2.to_string(),
+                    at_time: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(position.owner, test_accounts.subscriber_2.to_string());
+        assert_eq!(position.in_balance, Uint256::from(1_000u128));
+        assert_eq!(position.spent, Uint256::from(1_000u128));
+        assert_eq!(position.purchased, Uint256::from(500_000u128));
+        let res: Result<PositionResponse, _> = app.wrap().query_wasm_smart(
+            stream_swap_contract_address.clone(),
+            &StreamSwapQueryMsg::Position {
+                owner: test_accounts.wrong_user.to_string(),
+                at_time: None,
+            },
+        );
+        assert!(res.is_err());
+
+        // New owner exits with the whole merged position
+        let balance_before = app
+            .wrap()
+            .query_balance(test_accounts.subscriber_2.clone(), "out_denom")
+            .unwrap();
+        app.set_block(BlockInfo {
+            height: 1_250,
+            time: end_time.plus_seconds(1),
+            chain_id: "test".to_string(),
+        });
+        app.execute_contract(
+            test_accounts.subscriber_2.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::ExitStream { salt: None },
+            &[],
+        )
+        .unwrap();
+        let balance = app
+            .wrap()
+            .query_balance(test_accounts.subscriber_2.clone(), "out_denom")
+            .unwrap();
+        assert_eq!((balance.amount - balance_before.amount).u128(), 1_000_000);
+
+        // Exited positions can not be transferred
+        let res = app
+            .execute_contract(
+                test_accounts.subscriber_2.clone(),
+                stream_swap_contract_address.clone(),
+                &StreamSwapExecuteMsg::TransferPosition {
+                    recipient: test_accounts.subscriber_1.to_string(),
+                },
+                &[],
+            )
+            .unwrap_err();
+        let err = res.source().unwrap();
+        let error = err.downcast_ref::<StreamSwapError>().unwrap();
+        assert_eq!(*error, StreamSwapError::SubscriberAlreadyExited {});
+    }
+}
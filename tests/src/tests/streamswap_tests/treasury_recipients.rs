@@ -0,0 +1,294 @@
+#[cfg(test)]
+mod treasury_recipients {
+    use crate::helpers::mock_messages::CreateStreamMsgBuilder;
+    use crate::helpers::suite::SuiteBuilder;
+    use crate::helpers::utils::{get_funds_from_res, get_wasm_attribute_with_key};
+    use crate::helpers::{
+        mock_messages::get_controller_inst_msg, suite::Suite, utils::get_contract_address_from_res,
+    };
+    use cosmwasm_std::{coin, Addr, Binary, BlockInfo, Coin, Decimal256, Uint128};
+    use cw_multi_test::Executor;
+    use cw_vesting::vesting::Schedule;
+    use sha2::{Digest, Sha256};
+    use std::str::FromStr;
+    use streamswap_stream::ContractError as StreamSwapError;
+    use streamswap_types::controller::{TreasuryRecipientMsg, VestingConfig};
+    use streamswap_types::stream::{
+        ExecuteMsg as StreamSwapExecuteMsg, QueryMsg as StreamSwapQueryMsg,
+    };
+
+    #[test]
+    fn creator_revenue_split_between_recipients() {
+        let Suite {
+            mut app,
+            test_accounts,
+            stream_swap_code_id,
+            stream_swap_controller_code_id,
+            vesting_code_id,
+        } = SuiteBuilder::default().build();
+
+        let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+        let controller_address = app
+            .instantiate_contract(
+                stream_swap_controller_code_id,
+                test_accounts.admin.clone(),
+                &msg,
+                &[],
+                "Controller".to_string(),
+                None,
+            )
+            .unwrap();
+
+        let start_time = app.block_info().time.plus_seconds(100);
+        let end_time = app.block_info().time.plus_seconds(200);
+        let bootstrapping_start_time = app.block_info().time.plus_seconds(50);
+        let recipients = |first: &str, second: &str| {
+            vec![
+                TreasuryRecipientMsg {
+                    address: test_accounts.creator_1.to_string(),
+                    weight: Decimal256::from_str(first).unwrap(),
+                    vesting: None,
+                },
+                TreasuryRecipientMsg {
+                    address: test_accounts.creator_2.to_string(),
+                    weight: Decimal256::from_str(second).unwrap(),
+                    vesting: Some(VestingConfig {
+                        schedule: Schedule::SaturatingLinear,
+                        vesting_duration_seconds: 150,
+                        unbonding_duration_seconds: 0,
+                    }),
+                },
+            ]
+        };
+        let create_stream_msg = |first: &str, second: &str| {
+            CreateStreamMsgBuilder::new(
+                "Stream Swap tests",
+                test_accounts.creator_1.as_ref(),
+                coin(1_000_000, "out_denom"),
+                "in_denom",
+                bootstrapping_start_time,
+                start_time,
+                end_time,
+            )
+            .treasury_recipients(recipients(first, second))
+            .build()
+        };
+
+        // Weights must sum up to 100%
+        let err = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address.clone(),
+                &create_stream_msg("0.6", "0.5"),
+                &[coin(100, "fee_denom"), coin(1_000_000, "out_denom")],
+            )
+            .unwrap_err();
+        let err = err.source().unwrap().source().unwrap();
+        let error = err.downcast_ref::<StreamSwapError>().unwrap();
+        assert_eq!(*error, StreamSwapError::InvalidTreasuryRecipients {});
+
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address.clone(),
+                &create_stream_msg("0.6", "0.4"),
+                &[coin(100, "fee_denom"), coin(1_000_000, "out_denom")],
+            )
+            .unwrap();
+        let stream_swap_contract_address = get_contract_address_from_res(res);
+
+        app.set_block(BlockInfo {
+            height: 1_100,
+            time: start_time,
+            chain_id: "test".to_string(),
+        });
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            Addr::unchecked(stream_swap_contract_address.clone()),
+            &StreamSwapExecuteMsg::Subscribe {
+                proof: None,
+                on_behalf_of: None,
+                referrer: None,
+            },
+            &[coin(1_000, "in_denom")],
+        )
+        .unwrap();
+
+        app.set_block(BlockInfo {
+            height: 1_200,
+            time: end_time,
+            chain_id: "test".to_string(),
+        });
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapExecuteMsg::FinalizeStream {
+                    new_treasury: None,
+                    create_pool: None,
+                    salt: None,
+                },
+                &[],
+            )
+            .unwrap();
+
+        // Creator revenue of 990 is split 594 / 396, the vested share goes to a vesting contract
+        let vesting_addr: String = app
+            .wrap()
+            .query_wasm_smart(
+                Addr::unchecked(stream_swap_contract_address.clone()),
+                &StreamSwapQueryMsg::TreasuryVesting {
+                    addr: test_accounts.creator_2.to_string(),
+                },
+            )
+            .unwrap();
+        let funds = get_funds_from_res(res);
+        assert!(funds.contains(&(
+            test_accounts.creator_1.to_string(),
+            Coin {
+                denom: "in_denom".to_string(),
+                amount: Uint128::new(594)
+            }
+        )));
+        let balance = app
+            .wrap()
+            .query_balance(vesting_addr.clone(), "in_denom")
+            .unwrap();
+        assert_eq!(balance.amount, Uint128::new(396));
+
+        let vest: cw_vesting::vesting::Vest = app
+            .wrap()
+            .query_wasm_smart(
+                Addr::unchecked(vesting_addr),
+                &cw_vesting::msg::QueryMsg::Info {},
+            )
+            .unwrap();
+        assert_eq!(vest.recipient, test_accounts.creator_2.to_string());
+        assert_eq!(vest.status, cw_vesting::vesting::Status::Funded);
+    }
+
+    #[test]
+    fn subscriber_salt_does_not_collide_with_treasury_vesting() {
+        let Suite {
+            mut app,
+            test_accounts,
+            stream_swap_code_id,
+            stream_swap_controller_code_id,
+            vesting_code_id,
+        } = SuiteBuilder::default().build();
+
+        let msg = get_controller_inst_msg(stream_swap_code_id, vesting_code_id, &test_accounts);
+        let controller_address = app
+            .instantiate_contract(
+                stream_swap_controller_code_id,
+                test_accounts.admin.clone(),
+                &msg,
+                &[],
+                "Controller".to_string(),
+                None,
+            )
+            .unwrap();
+
+        let start_time = app.block_info().time.plus_seconds(100);
+        let end_time = app.block_info().time.plus_seconds(200);
+        let bootstrapping_start_time = app.block_info().time.plus_seconds(50);
+        let vesting_config = VestingConfig {
+            schedule: Schedule::SaturatingLinear,
+            vesting_duration_seconds: 150,
+            unbonding_duration_seconds: 0,
+        };
+        let create_stream_msg = CreateStreamMsgBuilder::new(
+            "Stream Swap tests",
+            test_accounts.creator_1.as_ref(),
+            coin(1_000_000, "out_denom"),
+            "in_denom",
+            bootstrapping_start_time,
+            start_time,
+            end_time,
+        )
+        .subscriber_vesting(vesting_config.clone())
+        .treasury_recipients(vec![TreasuryRecipientMsg {
+            address: test_accounts.creator_2.to_string(),
+            weight: Decimal256::one(),
+            vesting: Some(vesting_config),
+        }])
+        .build();
+        let res = app
+            .execute_contract(
+                test_accounts.creator_1.clone(),
+                controller_address,
+                &create_stream_msg,
+                &[coin(100, "fee_denom"), coin(1_000_000, "out_denom")],
+            )
+            .unwrap();
+        let stream_swap_contract_address = Addr::unchecked(get_contract_address_from_res(res));
+
+        app.set_block(BlockInfo {
+            height: 1_100,
+            time: start_time,
+            chain_id: "test".to_string(),
+        });
+        app.execute_contract(
+            test_accounts.subscriber_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::Subscribe {
+                proof: None,
+                on_behalf_of: None,
+                referrer: None,
+            },
+            &[coin(1_000, "in_denom")],
+        )
+        .unwrap();
+
+        // Subscriber exits first with the salt of the treasury recipient vesting contract
+        app.set_block(BlockInfo {
+            height: 1_200,
+            time: end_time,
+            chain_id: "test".to_string(),
+        });
+        let mut hasher = Sha256::new();
+        hasher.update(b"treasury_vesting");
+        hasher.update(test_accounts.creator_2.as_bytes());
+        let treasury_salt = Binary::from(hasher.finalize().as_slice());
+        let res = app
+            .execute_contract(
+                test_accounts.subscriber_1.clone(),
+                stream_swap_contract_address.clone(),
+                &StreamSwapExecuteMsg::ExitStream {
+                    salt: Some(treasury_salt),
+                },
+                &[],
+            )
+            .unwrap();
+        let subscriber_vesting_addr =
+            get_wasm_attribute_with_key(res, "vesting_address".to_string());
+
+        // Treasury vesting contract is still created on finalization
+        app.execute_contract(
+            test_accounts.creator_1.clone(),
+            stream_swap_contract_address.clone(),
+            &StreamSwapExecuteMsg::FinalizeStream {
+                new_treasury: None,
+                create_pool: None,
+                salt: None,
+            },
+            &[],
+        )
+        .unwrap();
+        let treasury_vesting_addr: String = app
+            .wrap()
+            .query_wasm_smart(
+                stream_swap_contract_address,
+                &StreamSwapQueryMsg::TreasuryVesting {
+                    addr: test_accounts.creator_2.to_string(),
+                },
+            )
+            .unwrap();
+        assert_ne!(treasury_vesting_addr, subscriber_vesting_addr);
+        let balance = app
+            .wrap()
+            .query_balance(treasury_vesting_addr, "in_denom")
+            .unwrap();
+        assert_eq!(balance.amount, Uint128::new(990));
+    }
+}
@@ -64,7 +64,11 @@ mod vesting {
             )
             .unwrap();
         let stream_swap_contract_address: String = get_contract_address_from_res(res);
-        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
         app.update_block(|b| b.time = start_time);
         // First subscription
         let _res = app
@@ -229,7 +233,11 @@ mod vesting {
             )
             .unwrap();
         let stream_swap_contract_address: String = get_contract_address_from_res(res);
-        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
         app.update_block(|b| b.time = start_time);
         // First subscription
         let _res = app
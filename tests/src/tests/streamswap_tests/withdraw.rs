@@ -67,7 +67,11 @@ mod withdraw {
             chain_id: "test".to_string(),
         });
         // Subscribe to stream
-        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
         let subscriber_1_balance_before = app
             .wrap()
             .query_balance(test_accounts.subscriber_1.clone(), "in_denom")
@@ -109,6 +113,7 @@ mod withdraw {
                 Addr::unchecked(stream_swap_contract_address.clone()),
                 &StreamSwapQueryMsg::Position {
                     owner: test_accounts.subscriber_1.clone().into_string(),
+                    at_time: None,
                 },
             )
             .unwrap();
@@ -263,7 +268,11 @@ mod withdraw {
             chain_id: "test".to_string(),
         });
 
-        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
         let _res = app
             .execute_contract(
                 test_accounts.subscriber_1.clone(),
@@ -412,7 +421,11 @@ mod withdraw {
             .execute_contract(
                 test_accounts.subscriber_1.clone(),
                 Addr::unchecked(stream_swap_contract_address.clone()),
-                &StreamSwapExecuteMsg::Subscribe {},
+                &StreamSwapExecuteMsg::Subscribe {
+                    proof: None,
+                    on_behalf_of: None,
+                    referrer: None,
+                },
                 &[coin(1_000, "in_denom")],
             )
             .unwrap();
@@ -563,7 +576,11 @@ mod withdraw {
         });
 
         // Subscribe to stream
-        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {};
+        let subscribe_msg = StreamSwapExecuteMsg::Subscribe {
+            proof: None,
+            on_behalf_of: None,
+            referrer: None,
+        };
         let _res = app
             .execute_contract(
                 test_accounts.subscriber_1.clone(),